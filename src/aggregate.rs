@@ -0,0 +1,154 @@
+// Quick aggregate: pick the columns to group by and the aggregates to compute
+// over the others, and the GROUP BY query is written into the editor and run,
+// over a whole table or over the result of the last query. With no aggregate
+// picked the groups' row counts are shown.
+
+use crate::dialect::Dialect;
+use crate::profile::ProfileSource;
+use anyhow::{Result, anyhow};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    pub const ALL: [Aggregate; 5] = [
+        Aggregate::Count,
+        Aggregate::Sum,
+        Aggregate::Avg,
+        Aggregate::Min,
+        Aggregate::Max,
+    ];
+
+    pub fn function(&self) -> &'static str {
+        match self {
+            Aggregate::Count => "COUNT",
+            Aggregate::Sum => "SUM",
+            Aggregate::Avg => "AVG",
+            Aggregate::Min => "MIN",
+            Aggregate::Max => "MAX",
+        }
+    }
+
+    /// Key toggling the aggregate on the selected column.
+    pub fn key(&self) -> char {
+        match self {
+            Aggregate::Count => 'c',
+            Aggregate::Sum => 's',
+            Aggregate::Avg => 'a',
+            Aggregate::Min => 'n',
+            Aggregate::Max => 'x',
+        }
+    }
+
+    pub fn from_key(key: char) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|aggregate| aggregate.key() == key)
+    }
+}
+
+/// The quick aggregate popup: the source's columns and what each is used for.
+#[derive(Debug, Clone)]
+pub struct AggregateBuilder {
+    pub source: ProfileSource,
+    pub columns: Vec<String>,
+    pub selected: usize,                     // Index into `columns`
+    pub group_by: Vec<usize>,                // In GROUP BY order
+    pub aggregates: Vec<(Aggregate, usize)>, // In SELECT order
+}
+
+impl AggregateBuilder {
+    pub fn new(source: ProfileSource, columns: Vec<String>, selected: usize) -> Self {
+        Self {
+            source,
+            selected: selected.min(columns.len().saturating_sub(1)),
+            columns,
+            group_by: Vec::new(),
+            aggregates: Vec::new(),
+        }
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.columns.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn toggle_group_by(&mut self) {
+        if let Some(position) = self.group_by.iter().position(|&c| c == self.selected) {
+            self.group_by.remove(position);
+        } else {
+            self.group_by.push(self.selected);
+        }
+    }
+
+    pub fn toggle_aggregate(&mut self, aggregate: Aggregate) {
+        let entry = (aggregate, self.selected);
+        if let Some(position) = self.aggregates.iter().position(|&a| a == entry) {
+            self.aggregates.remove(position);
+        } else {
+            self.aggregates.push(entry);
+        }
+    }
+
+    /// What the column is used for, e.g. `group 1, SUM, MAX`.
+    pub fn describe_column(&self, column: usize) -> String {
+        let mut uses = Vec::new();
+        if let Some(position) = self.group_by.iter().position(|&c| c == column) {
+            uses.push(format!("group {}", position + 1));
+        }
+        for (aggregate, _) in self.aggregates.iter().filter(|(_, c)| *c == column) {
+            uses.push(aggregate.function().to_string());
+        }
+        uses.join(", ")
+    }
+
+    /// The GROUP BY query, sorted by the groups.
+    pub fn query(&self, dialect: Dialect) -> Result<String> {
+        if self.group_by.is_empty() && self.aggregates.is_empty() {
+            return Err(anyhow!(
+                "Pick a column to group by (g) or an aggregate to compute"
+            ));
+        }
+        let groups: Vec<String> = self
+            .group_by
+            .iter()
+            .map(|&c| dialect.quote_identifier(&self.columns[c]))
+            .collect();
+        let mut select = groups.clone();
+        if self.aggregates.is_empty() {
+            select.push(format!("COUNT(*) AS {}", dialect.quote_identifier("count")));
+        }
+        for &(aggregate, column) in &self.aggregates {
+            let name = &self.columns[column];
+            select.push(format!(
+                "{}({}) AS {}",
+                aggregate.function(),
+                dialect.quote_identifier(name),
+                dialect.quote_identifier(&format!(
+                    "{}_{}",
+                    aggregate.function().to_lowercase(),
+                    name
+                ))
+            ));
+        }
+        let mut query = format!(
+            "SELECT {}\nFROM {}",
+            select.join(", "),
+            self.source.relation(dialect, "grouped")
+        );
+        if !groups.is_empty() {
+            query.push_str(&format!(
+                "\nGROUP BY {}\nORDER BY {}",
+                groups.join(", "),
+                groups.join(", ")
+            ));
+        }
+        Ok(query)
+    }
+}
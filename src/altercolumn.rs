@@ -0,0 +1,662 @@
+// Column changes from the table browser: pick a column, change its name, type,
+// NOT NULL or default in a form, and the generated statements are previewed
+// before they run in one transaction. PostgreSQL gets ALTER TABLE ... ALTER
+// COLUMN actions and RENAME COLUMN; MySQL a CHANGE COLUMN restating the rest of
+// the column's definition from SHOW CREATE TABLE. SQLite can only rename a
+// column in place, so any other change rebuilds the table: a copy is created
+// from the table's CREATE statement with the column's definition changed, the
+// rows are copied over, the old table is dropped, the copy takes its name and
+// the table's indexes and triggers are created again.
+
+use crate::database::{DatabasePool, TableInfo};
+use crate::dialect::Dialect;
+use crate::textinput::TextInput;
+use anyhow::{Result, anyhow};
+use sqlx::Row;
+
+// Words that end a column's type and start its constraints or attributes
+const TYPE_END_KEYWORDS: [&str; 24] = [
+    "CONSTRAINT",
+    "PRIMARY",
+    "NOT",
+    "NULL",
+    "UNIQUE",
+    "CHECK",
+    "DEFAULT",
+    "COLLATE",
+    "REFERENCES",
+    "GENERATED",
+    "AS",
+    "CHARACTER",
+    "CHARSET",
+    "AUTO_INCREMENT",
+    "AUTOINCREMENT",
+    "COMMENT",
+    "ON",
+    "VIRTUAL",
+    "STORED",
+    "KEY",
+    "INVISIBLE",
+    "VISIBLE",
+    "SRID",
+    "COLUMN_FORMAT",
+];
+
+/// A column as the database defines it, loaded when the form opens.
+#[derive(Debug, Clone)]
+pub struct ColumnDefinition {
+    pub name: String,
+    pub data_type: String,
+    pub not_null: bool,
+    pub default: Option<String>, // An SQL expression
+    definition: String,          // MySQL: the definition after the name
+    create_table: String,        // SQLite: the table's CREATE statement
+    schema_objects: Vec<String>, // SQLite: CREATE statements of indexes and triggers
+    copied_columns: Vec<String>, // SQLite: columns whose values are copied
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnField {
+    Name,
+    Type,
+    NotNull,
+    Default,
+}
+
+const FIELDS: [ColumnField; 4] = [
+    ColumnField::Name,
+    ColumnField::Type,
+    ColumnField::NotNull,
+    ColumnField::Default,
+];
+
+/// The new name, type, NOT NULL and default of a column.
+#[derive(Debug, Clone)]
+pub struct ColumnForm {
+    pub original: ColumnDefinition,
+    pub name: TextInput,
+    pub data_type: TextInput,
+    pub not_null: bool,
+    pub default: TextInput, // Empty for no default
+    pub selected: usize,    // Index into FIELDS
+}
+
+impl ColumnForm {
+    pub fn new(original: ColumnDefinition) -> Self {
+        Self {
+            name: TextInput::new(original.name.clone()),
+            data_type: TextInput::new(original.data_type.clone()),
+            not_null: original.not_null,
+            default: TextInput::new(original.default.clone().unwrap_or_default()),
+            selected: 0,
+            original,
+        }
+    }
+
+    pub fn selected_field(&self) -> ColumnField {
+        FIELDS[self.selected]
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(FIELDS.len() - 1);
+    }
+
+    /// The selected field's input; NOT NULL is a toggle and has none.
+    pub fn selected_input(&mut self) -> Option<&mut TextInput> {
+        match self.selected_field() {
+            ColumnField::Name => Some(&mut self.name),
+            ColumnField::Type => Some(&mut self.data_type),
+            ColumnField::Default => Some(&mut self.default),
+            ColumnField::NotNull => None,
+        }
+    }
+
+    pub fn toggle_not_null(&mut self) {
+        if self.selected_field() == ColumnField::NotNull {
+            self.not_null = !self.not_null;
+        }
+    }
+
+    fn renamed(&self) -> bool {
+        self.name.text.trim() != self.original.name
+    }
+
+    fn retyped(&self) -> bool {
+        !self
+            .data_type
+            .text
+            .trim()
+            .eq_ignore_ascii_case(&self.original.data_type)
+    }
+
+    fn new_default(&self) -> Option<&str> {
+        Some(self.default.text.trim()).filter(|d| !d.is_empty())
+    }
+
+    fn default_changed(&self) -> bool {
+        self.new_default() != self.original.default.as_deref().map(str::trim)
+    }
+}
+
+/// The column changes popup of one table.
+#[derive(Debug, Clone)]
+pub struct ColumnEditor {
+    pub dialect: Dialect,
+    pub table: TableInfo,
+    pub columns: Vec<String>,
+    pub selected: usize,              // Index into `columns`
+    pub form: Option<ColumnForm>,     // Changing the selected column
+    pub preview: Option<Vec<String>>, // Statements waiting to be confirmed
+}
+
+impl ColumnEditor {
+    pub fn new(dialect: Dialect, table: TableInfo, columns: Vec<String>) -> Self {
+        Self {
+            dialect,
+            table,
+            columns,
+            selected: 0,
+            form: None,
+            preview: None,
+        }
+    }
+
+    pub fn selected_column(&self) -> Option<&str> {
+        self.columns.get(self.selected).map(String::as_str)
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.columns.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// The statements making the form's changes, run in one transaction.
+    pub fn statements(&self) -> Result<Vec<String>> {
+        let Some(form) = &self.form else {
+            return Err(anyhow!("No column is being changed"));
+        };
+        if form.name.text.trim().is_empty() {
+            return Err(anyhow!("The column needs a name"));
+        }
+        if form.data_type.text.trim().is_empty() {
+            return Err(anyhow!("The column needs a type"));
+        }
+        if !form.renamed()
+            && !form.retyped()
+            && form.not_null == form.original.not_null
+            && !form.default_changed()
+        {
+            return Err(anyhow!("Nothing was changed"));
+        }
+        Ok(match self.dialect {
+            Dialect::PostgreSQL => self.postgres_statements(form),
+            Dialect::MySQL => vec![self.mysql_statement(form)],
+            Dialect::SQLite => self.sqlite_statements(form)?,
+        })
+    }
+
+    fn postgres_statements(&self, form: &ColumnForm) -> Vec<String> {
+        let table = self.dialect.quote_table(&self.table.qualified_name());
+        let column = self.dialect.quote_identifier(&form.original.name);
+        let data_type = form.data_type.text.trim();
+        let mut actions = Vec::new();
+        // An existing default isn't cast to the new type, so it is dropped
+        // first and set again after
+        let retyped_default = form.retyped() && form.original.default.is_some();
+        if retyped_default {
+            actions.push(format!("ALTER COLUMN {} DROP DEFAULT", column));
+        }
+        if form.retyped() {
+            // USING converts values the database has no implicit cast for
+            actions.push(format!(
+                "ALTER COLUMN {} TYPE {} USING {}::{}",
+                column, data_type, column, data_type
+            ));
+        }
+        if form.default_changed() || retyped_default {
+            match form.new_default() {
+                Some(default) => {
+                    actions.push(format!("ALTER COLUMN {} SET DEFAULT {}", column, default))
+                }
+                None if !retyped_default => {
+                    actions.push(format!("ALTER COLUMN {} DROP DEFAULT", column))
+                }
+                None => {}
+            }
+        }
+        if form.not_null != form.original.not_null {
+            actions.push(format!(
+                "ALTER COLUMN {} {} NOT NULL",
+                column,
+                if form.not_null { "SET" } else { "DROP" }
+            ));
+        }
+        let mut statements = Vec::new();
+        if !actions.is_empty() {
+            statements.push(format!("ALTER TABLE {} {};", table, actions.join(",\n  ")));
+        }
+        if form.renamed() {
+            statements.push(format!(
+                "ALTER TABLE {} RENAME COLUMN {} TO {};",
+                table,
+                column,
+                self.dialect.quote_identifier(form.name.text.trim())
+            ));
+        }
+        statements
+    }
+
+    /// CHANGE COLUMN with the new name, type, NOT NULL and default, keeping
+    /// the rest of the definition (character set and collation only while
+    /// the type stays).
+    fn mysql_statement(&self, form: &ColumnForm) -> String {
+        let tokens = tokens(&form.original.definition, true);
+        let type_length = type_length(&tokens);
+        let (mut charset, mut rest) = (Vec::new(), Vec::new());
+        let mut i = type_length;
+        while i < tokens.len() {
+            let word = tokens[i].to_uppercase();
+            let next = tokens.get(i + 1).map(|t| t.to_uppercase());
+            match word.as_str() {
+                "NOT" if next.as_deref() == Some("NULL") => i += 2,
+                "NULL" => i += 1,
+                "DEFAULT" => i += 2,
+                "CHARACTER" => {
+                    charset.extend(tokens[i..(i + 3).min(tokens.len())].iter().cloned());
+                    i += 3;
+                }
+                "CHARSET" | "COLLATE" => {
+                    charset.extend(tokens[i..(i + 2).min(tokens.len())].iter().cloned());
+                    i += 2;
+                }
+                _ => {
+                    rest.push(tokens[i].clone());
+                    i += 1;
+                }
+            }
+        }
+
+        let mut definition = vec![form.data_type.text.trim().to_string()];
+        if !form.retyped() {
+            definition.extend(charset);
+        }
+        definition.push(if form.not_null { "NOT NULL" } else { "NULL" }.to_string());
+        if let Some(default) = form.new_default() {
+            definition.push(format!("DEFAULT {}", default));
+        }
+        definition.extend(rest);
+        format!(
+            "ALTER TABLE {} CHANGE COLUMN {} {} {};",
+            self.dialect.quote_table(&self.table.qualified_name()),
+            self.dialect.quote_identifier(&form.original.name),
+            self.dialect.quote_identifier(form.name.text.trim()),
+            definition.join(" ")
+        )
+    }
+
+    /// RENAME COLUMN alone, or a rebuild of the table with the column's new
+    /// definition followed by the rename.
+    fn sqlite_statements(&self, form: &ColumnForm) -> Result<Vec<String>> {
+        let table = self.dialect.quote_table(&self.table.qualified_name());
+        let rename = format!(
+            "ALTER TABLE {} RENAME COLUMN {} TO {};",
+            table,
+            self.dialect.quote_identifier(&form.original.name),
+            self.dialect.quote_identifier(form.name.text.trim())
+        );
+        if !form.retyped() && form.not_null == form.original.not_null && !form.default_changed() {
+            return Ok(vec![rename]);
+        }
+
+        let create = &form.original.create_table;
+        let open = create
+            .find('(')
+            .ok_or_else(|| anyhow!("The table's CREATE statement wasn't understood"))?;
+        let close = matching_paren(create, open)
+            .ok_or_else(|| anyhow!("The table's CREATE statement wasn't understood"))?;
+        let mut found = false;
+        let definitions: Vec<String> = split_top_level(&create[open + 1..close])
+            .into_iter()
+            .map(|definition| {
+                let tokens = tokens(&definition, false);
+                let is_column = tokens
+                    .first()
+                    .is_some_and(|name| unquote(name).eq_ignore_ascii_case(&form.original.name));
+                if !is_column {
+                    return definition;
+                }
+                found = true;
+                sqlite_column_definition(form, &tokens)
+            })
+            .collect();
+        if !found {
+            return Err(anyhow!(
+                "{} wasn't found in the table's CREATE statement",
+                form.original.name
+            ));
+        }
+
+        let temporary_name = format!("{}__rebuild", self.table.name);
+        let temporary = match &self.table.schema {
+            Some(schema) => format!("{}.{}", schema, temporary_name),
+            None => temporary_name.clone(),
+        };
+        let columns = form
+            .original
+            .copied_columns
+            .iter()
+            .map(|c| self.dialect.quote_identifier(c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut statements = vec![
+            format!(
+                "CREATE TABLE {} (\n  {}\n){};",
+                self.dialect.quote_table(&temporary),
+                definitions.join(",\n  "),
+                create[close + 1..].trim_end_matches(';')
+            ),
+            format!(
+                "INSERT INTO {} ({}) SELECT {} FROM {};",
+                self.dialect.quote_table(&temporary),
+                columns,
+                columns,
+                table
+            ),
+            format!("DROP TABLE {};", table),
+            format!(
+                "ALTER TABLE {} RENAME TO {};",
+                self.dialect.quote_table(&temporary),
+                self.dialect.quote_identifier(&self.table.name)
+            ),
+        ];
+        statements.extend(
+            form.original
+                .schema_objects
+                .iter()
+                .map(|sql| format!("{};", sql.trim_end_matches(';'))),
+        );
+        if form.renamed() {
+            statements.push(rename);
+        }
+        Ok(statements)
+    }
+}
+
+/// A SQLite column definition with the form's type, NOT NULL and default,
+/// keeping its other constraints.
+fn sqlite_column_definition(form: &ColumnForm, tokens: &[String]) -> String {
+    let mut definition = vec![tokens[0].clone(), form.data_type.text.trim().to_string()];
+    let mut i = 1 + type_length(&tokens[1..]);
+    while i < tokens.len() {
+        let word = tokens[i].to_uppercase();
+        let next = tokens.get(i + 1).map(|t| t.to_uppercase());
+        match word.as_str() {
+            "NOT" if next.as_deref() == Some("NULL") => i += 2,
+            "NULL" => i += 1,
+            "DEFAULT" => i += 2,
+            _ => {
+                definition.push(tokens[i].clone());
+                i += 1;
+            }
+        }
+    }
+    if form.not_null {
+        definition.push("NOT NULL".to_string());
+    }
+    if let Some(default) = form.new_default() {
+        definition.push(format!("DEFAULT {}", default));
+    }
+    definition.join(" ")
+}
+
+/// How many of the tokens, from the start, spell the column's type.
+fn type_length(tokens: &[String]) -> usize {
+    tokens
+        .iter()
+        .position(|t| TYPE_END_KEYWORDS.contains(&t.to_uppercase().as_str()) || t.starts_with("/*"))
+        .unwrap_or(tokens.len())
+}
+
+/// Splits a definition into words, keeping quoted names and strings,
+/// parenthesized parts (`varchar(10)`, `(now())`) and `/* */` comments whole.
+fn tokens(text: &str, backslash_escapes: bool) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i - 1] == '*' && chars[i] == '/') {
+                i += 1;
+            }
+            i += 1;
+        } else {
+            while i < chars.len() && !chars[i].is_whitespace() {
+                i = match chars[i] {
+                    '\'' | '"' | '`' | '[' => skip_quoted(&chars, i, backslash_escapes),
+                    '(' => skip_parens(&chars, i, backslash_escapes),
+                    _ => i + 1,
+                };
+            }
+        }
+        tokens.push(chars[start..i.min(chars.len())].iter().collect());
+    }
+    tokens
+}
+
+/// Index after the quoted part starting at `start`; a doubled quote is part
+/// of it. SQLite also quotes names in brackets.
+fn skip_quoted(chars: &[char], start: usize, backslash_escapes: bool) -> usize {
+    let quote = match chars[start] {
+        '[' => ']',
+        quote => quote,
+    };
+    let mut i = start + 1;
+    while i < chars.len() {
+        if backslash_escapes && chars[i] == '\\' {
+            i += 2;
+        } else if chars[i] == quote {
+            if chars.get(i + 1) == Some(&quote) {
+                i += 2;
+            } else {
+                return i + 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    chars.len()
+}
+
+/// Index after the parenthesized part starting at `start`.
+fn skip_parens(chars: &[char], start: usize, backslash_escapes: bool) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\'' | '"' | '`' => {
+                i = skip_quoted(chars, i, backslash_escapes);
+                continue;
+            }
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// Byte index of the `)` closing the `(` at `open`.
+fn matching_paren(text: &str, open: usize) -> Option<usize> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let start = chars.iter().position(|(i, _)| *i == open)?;
+    let plain: Vec<char> = chars.iter().map(|(_, c)| *c).collect();
+    let end = skip_parens(&plain, start, false);
+    chars.get(end.checked_sub(1)?).map(|(i, _)| *i)
+}
+
+/// The comma-separated parts of a CREATE TABLE body.
+fn split_top_level(body: &str) -> Vec<String> {
+    let chars: Vec<char> = body.chars().collect();
+    let mut parts = Vec::new();
+    let (mut start, mut i) = (0, 0);
+    while i < chars.len() {
+        match chars[i] {
+            '\'' | '"' | '`' | '[' => {
+                i = skip_quoted(&chars, i, false);
+                continue;
+            }
+            '(' => {
+                i = skip_parens(&chars, i, false);
+                continue;
+            }
+            ',' => {
+                parts.push(
+                    chars[start..i]
+                        .iter()
+                        .collect::<String>()
+                        .trim()
+                        .to_string(),
+                );
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    parts.push(chars[start..].iter().collect::<String>().trim().to_string());
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// A name without its quotes.
+fn unquote(name: &str) -> String {
+    match (name.chars().next(), name.chars().last()) {
+        (Some(open @ ('"' | '`')), Some(close)) if open == close && name.len() > 1 => {
+            let inner = &name[1..name.len() - 1];
+            inner.replace(&format!("{}{}", open, open), &open.to_string())
+        }
+        (Some('['), Some(']')) => name[1..name.len() - 1].to_string(),
+        _ => name.to_string(),
+    }
+}
+
+/// Loads how the database defines `column` of `table`.
+pub async fn load_column(
+    pool: &DatabasePool,
+    table: &TableInfo,
+    column: &str,
+) -> Result<ColumnDefinition> {
+    let mut definition = ColumnDefinition {
+        name: column.to_string(),
+        data_type: String::new(),
+        not_null: false,
+        default: None,
+        definition: String::new(),
+        create_table: String::new(),
+        schema_objects: Vec::new(),
+        copied_columns: Vec::new(),
+    };
+    match pool {
+        DatabasePool::PostgreSQL(pool) => {
+            let relation = format!(
+                "{}.{}",
+                Dialect::PostgreSQL.quote_identifier(table.schema.as_deref().unwrap_or("public")),
+                Dialect::PostgreSQL.quote_identifier(&table.name)
+            );
+            let row = sqlx::query(
+                "SELECT format_type(a.atttypid, a.atttypmod) AS data_type, a.attnotnull,
+                        pg_get_expr(d.adbin, d.adrelid) AS default_value
+                 FROM pg_attribute a
+                 LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+                 WHERE a.attrelid = $1::regclass AND a.attname = $2 AND NOT a.attisdropped",
+            )
+            .bind(&relation)
+            .bind(column)
+            .fetch_one(pool)
+            .await?;
+            definition.data_type = row.get("data_type");
+            definition.not_null = row.get("attnotnull");
+            definition.default = row.get("default_value");
+        }
+        DatabasePool::MySQL(pool) => {
+            let (_, text) =
+                crate::comments::column_definitions(&DatabasePool::MySQL(pool.clone()), table)
+                    .await?
+                    .into_iter()
+                    .find(|(name, _)| name == column)
+                    .ok_or_else(|| anyhow!("The definition of {} wasn't found", column))?;
+            let tokens = tokens(&text, true);
+            let type_length = type_length(&tokens);
+            definition.data_type = tokens[..type_length].join(" ");
+            definition.not_null = tokens.windows(2).any(|pair| {
+                pair[0].eq_ignore_ascii_case("NOT") && pair[1].eq_ignore_ascii_case("NULL")
+            });
+            definition.default = tokens
+                .iter()
+                .position(|t| t.eq_ignore_ascii_case("DEFAULT"))
+                .and_then(|i| tokens.get(i + 1).cloned());
+            definition.definition = text;
+        }
+        DatabasePool::SQLite(pool) => {
+            let schema = table
+                .schema
+                .as_deref()
+                .map(|s| Dialect::SQLite.quote_identifier(s));
+            let master = match &schema {
+                Some(schema) => format!("{}.sqlite_master", schema),
+                None => "sqlite_master".to_string(),
+            };
+            let create: String = sqlx::query_scalar(&format!(
+                "SELECT sql FROM {} WHERE type = 'table' AND name = ?",
+                master
+            ))
+            .bind(&table.name)
+            .fetch_one(pool)
+            .await?;
+            definition.schema_objects = sqlx::query_scalar(&format!(
+                "SELECT sql FROM {} WHERE type IN ('index', 'trigger') AND tbl_name = ?
+                   AND sql IS NOT NULL ORDER BY type, name",
+                master
+            ))
+            .bind(&table.name)
+            .fetch_all(pool)
+            .await?;
+            let rows = sqlx::query(
+                "SELECT name, type, \"notnull\", dflt_value FROM pragma_table_info(?, ?)",
+            )
+            .bind(&table.name)
+            .bind(table.schema.as_deref().unwrap_or("main"))
+            .fetch_all(pool)
+            .await?;
+            for row in &rows {
+                let name: String = row.get("name");
+                if name == column {
+                    definition.data_type = row.get("type");
+                    definition.not_null = row.get::<i32, _>("notnull") != 0;
+                    definition.default = row.get("dflt_value");
+                }
+                definition.copied_columns.push(name);
+            }
+            definition.create_table = create;
+        }
+    }
+    if definition.data_type.is_empty() && !matches!(pool, DatabasePool::SQLite(_)) {
+        return Err(anyhow!("{} wasn't found", column));
+    }
+    Ok(definition)
+}
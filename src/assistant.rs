@@ -0,0 +1,124 @@
+// Natural-language assistant: a question is sent with the connection's tables
+// and columns to an OpenAI-compatible chat completions endpoint, and the SQL
+// it answers with goes into the editor to be reviewed before it runs. Only
+// the schema leaves the machine, never rows.
+
+use crate::database::{DatabasePool, TableInfo};
+use crate::textinput::TextInput;
+use anyhow::{Result, anyhow};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+// Tables described to the model; columns are looked up one table at a time
+const MAX_SCHEMA_TABLES: usize = 100;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Where requests go, from the settings.
+#[derive(Debug, Clone)]
+pub struct AssistantConfig {
+    pub endpoint: String, // Base URL, e.g. https://api.openai.com/v1
+    pub api_key: String,  // Sent as a bearer token when set
+    pub model: String,
+}
+
+/// The assistant popup: the question being typed and the request in flight.
+#[derive(Debug, Default)]
+pub struct AssistantView {
+    pub question: TextInput,
+    pub error: Option<String>,
+    pub task: Option<JoinHandle<Result<String>>>, // Generated SQL
+}
+
+/// Asks the model for SQL answering `question` on `tables` of `pool`.
+pub async fn generate_sql(
+    config: AssistantConfig,
+    pool: DatabasePool,
+    tables: Vec<TableInfo>,
+    question: String,
+) -> Result<String> {
+    let database = pool.database_type().display_name();
+    let schema = schema_context(&pool, &tables).await;
+    let system = format!(
+        "You write {} SQL. Answer the user's question with a single query on this schema, \
+         and reply with the SQL only, without explanations or Markdown.\n\n{}",
+        database, schema
+    );
+    let body = serde_json::json!({
+        "model": config.model,
+        "temperature": 0,
+        "messages": [
+            { "role": "system", "content": system },
+            { "role": "user", "content": question },
+        ],
+    });
+
+    let url = format!(
+        "{}/chat/completions",
+        config.endpoint.trim().trim_end_matches('/')
+    );
+    let mut request = reqwest::Client::new()
+        .post(&url)
+        .timeout(REQUEST_TIMEOUT)
+        .json(&body);
+    if !config.api_key.is_empty() {
+        request = request.bearer_auth(&config.api_key);
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    let text = response.text().await?;
+    let reply: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+    if !status.is_success() {
+        let message = reply["error"]["message"].as_str().unwrap_or(text.trim());
+        return Err(anyhow!("{} returned {}: {}", url, status, message));
+    }
+    let content = reply["choices"][0]["message"]["content"]
+        .as_str()
+        .ok_or_else(|| anyhow!("The response has no message"))?;
+    let sql = strip_code_fence(content);
+    if sql.is_empty() {
+        return Err(anyhow!("The model answered with no SQL"));
+    }
+    Ok(sql)
+}
+
+/// One line per table: `name(column type, ...)`. Tables whose columns can't
+/// be read are listed by name only.
+async fn schema_context(pool: &DatabasePool, tables: &[TableInfo]) -> String {
+    let mut lines = Vec::new();
+    for table in tables.iter().take(MAX_SCHEMA_TABLES) {
+        let columns = pool
+            .get_table_columns(&table.name, table.schema.as_deref())
+            .await
+            .unwrap_or_default();
+        let columns: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                let key = if c.is_primary_key { " primary key" } else { "" };
+                format!("{} {}{}", c.name, c.data_type, key)
+            })
+            .collect();
+        lines.push(format!(
+            "{}({})",
+            table.qualified_name(),
+            columns.join(", ")
+        ));
+    }
+    if tables.len() > MAX_SCHEMA_TABLES {
+        lines.push(format!(
+            "... and {} more tables",
+            tables.len() - MAX_SCHEMA_TABLES
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Models tend to wrap SQL in a Markdown code block despite being asked not to.
+fn strip_code_fence(content: &str) -> String {
+    let content = content.trim();
+    let Some(inner) = content.strip_prefix("```") else {
+        return content.to_string();
+    };
+    // Drop the language tag on the opening line
+    let inner = inner.split_once('\n').map_or("", |(_, rest)| rest);
+    inner.trim_end().trim_end_matches("```").trim().to_string()
+}
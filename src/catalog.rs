@@ -0,0 +1,89 @@
+// Connection catalogs: saved connections written to a portable JSON file without
+// their passwords, and read back in merged by name, so a team can share one list.
+
+use crate::database::ConnectionConfig;
+use crate::interpolate;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+const CATALOG_VERSION: u32 = 1;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Catalog {
+    version: u32,
+    connections: Vec<ConnectionConfig>,
+}
+
+/// What an import changed.
+#[derive(Debug, Default)]
+pub struct MergeSummary {
+    pub added: usize,
+    pub updated: usize,
+}
+
+/// The connection string with the password taken out of its `user:password@`
+/// part. `${NAME}` placeholders are kept, since they hold no secret.
+pub fn without_password(connection_string: &str) -> String {
+    let Some(range) = interpolate::userinfo_range(connection_string) else {
+        return connection_string.to_string();
+    };
+    match connection_string[range.clone()].split_once(':') {
+        Some((user, password)) if !interpolate::is_placeholder(password) => format!(
+            "{}{}{}",
+            &connection_string[..range.start],
+            user,
+            &connection_string[range.end..]
+        ),
+        _ => connection_string.to_string(),
+    }
+}
+
+/// Writes the connections to `path` with passwords removed.
+pub fn export(path: &Path, connections: &[ConnectionConfig]) -> Result<()> {
+    let catalog = Catalog {
+        version: CATALOG_VERSION,
+        connections: connections
+            .iter()
+            .map(|c| ConnectionConfig {
+                connection_string: without_password(&c.connection_string),
+                last_used: None,
+                ..c.clone()
+            })
+            .collect(),
+    };
+    fs::write(path, serde_json::to_string_pretty(&catalog)?)?;
+    Ok(())
+}
+
+/// Reads an exported catalog, or a plain `connections.json`.
+pub fn read(path: &Path) -> Result<Vec<ConnectionConfig>> {
+    let content = fs::read_to_string(path)?;
+    match serde_json::from_str::<Catalog>(&content) {
+        Ok(catalog) => Ok(catalog.connections),
+        Err(_) => serde_json::from_str(&content).context("not a connection catalog"),
+    }
+}
+
+/// Adds imported connections, replacing saved ones with the same name. A saved
+/// password is kept when the import is the same connection without it.
+pub fn merge(saved: &mut Vec<ConnectionConfig>, imported: Vec<ConnectionConfig>) -> MergeSummary {
+    let mut summary = MergeSummary::default();
+    for mut connection in imported {
+        match saved.iter_mut().find(|c| c.name == connection.name) {
+            Some(existing) => {
+                if without_password(&existing.connection_string) == connection.connection_string {
+                    connection.connection_string = existing.connection_string.clone();
+                }
+                connection.last_used = connection.last_used.or(existing.last_used);
+                *existing = connection;
+                summary.updated += 1;
+            }
+            None => {
+                saved.push(connection);
+                summary.added += 1;
+            }
+        }
+    }
+    summary
+}
@@ -0,0 +1,158 @@
+// Charts of the current result: one column for the X axis and one or more
+// numeric columns plotted against it, as grouped bars or as lines, and the
+// histogram sparkline of the selected column. Values are parsed from the
+// result's text cells; cells that aren't numbers (NULLs included) leave a gap.
+
+use crate::database::QueryResult;
+
+// Block characters of a sparkline, lowest first
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartKind {
+    Bar,
+    Line,
+}
+
+impl ChartKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChartKind::Bar => "Bar",
+            ChartKind::Line => "Line",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChartView {
+    pub kind: ChartKind,
+    pub x_column: usize,
+    pub y_columns: Vec<usize>, // In the order they were picked
+    pub selected: usize,       // Cursor in the column list
+    pub numeric: Vec<bool>,    // Per column: every value is a number
+}
+
+/// Parses a cell as a number; NULLs and text are `None`.
+pub fn parse_number(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// Values of `column` when every non-NULL value is a number and there is at
+/// least one; `None` otherwise.
+pub fn numeric_values(result: &QueryResult, column: usize) -> Option<Vec<f64>> {
+    let values: Option<Vec<f64>> = result
+        .rows
+        .iter()
+        .filter_map(|row| row.get(column))
+        .filter(|v| !v.is_empty() && v.as_str() != "NULL")
+        .map(|v| parse_number(v))
+        .collect();
+    values.filter(|v| !v.is_empty())
+}
+
+/// Counts of `values` in `buckets` equal-width ranges between their minimum
+/// and maximum, which are returned alongside.
+pub fn histogram(values: &[f64], buckets: usize) -> (Vec<usize>, f64, f64) {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    // All equal: one bucket holds everything
+    let buckets = if min < max { buckets.max(1) } else { 1 };
+    let mut counts = vec![0; buckets];
+    for value in values {
+        let bucket = ((value - min) / (max - min) * buckets as f64) as usize;
+        counts[bucket.min(buckets - 1)] += 1;
+    }
+    (counts, min, max)
+}
+
+/// Counts drawn as block characters scaled to the largest count; empty
+/// buckets are blank.
+pub fn sparkline(counts: &[usize]) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+    counts
+        .iter()
+        .map(|&count| match count {
+            0 => ' ',
+            _ => SPARK_LEVELS[(count * SPARK_LEVELS.len()).div_ceil(max) - 1],
+        })
+        .collect()
+}
+
+impl ChartView {
+    /// Starts with the first non-numeric column as X and the first numeric
+    /// column as Y. `hidden` columns (masked in privacy mode) can't be
+    /// plotted. Returns `None` when the result has no numeric column.
+    pub fn new(result: &QueryResult, hidden: &[bool]) -> Option<Self> {
+        let numeric: Vec<bool> = (0..result.columns.len())
+            .map(|c| {
+                !hidden.get(c).copied().unwrap_or(false) && numeric_values(result, c).is_some()
+            })
+            .collect();
+        let x_column = numeric.iter().position(|n| !n).unwrap_or(0);
+        let y_column = (0..numeric.len()).find(|&c| numeric[c] && c != x_column)?;
+        Some(Self {
+            kind: ChartKind::Bar,
+            x_column,
+            y_columns: vec![y_column],
+            selected: y_column,
+            numeric,
+        })
+    }
+
+    /// Adds the column under the cursor to the plotted columns, or removes
+    /// it. Returns false for a column that can't be plotted.
+    pub fn toggle_y_column(&mut self) -> bool {
+        if let Some(position) = self.y_columns.iter().position(|&c| c == self.selected) {
+            self.y_columns.remove(position);
+            return true;
+        }
+        if !self.numeric[self.selected] {
+            return false;
+        }
+        self.y_columns.push(self.selected);
+        true
+    }
+
+    /// Uses the column under the cursor as the X axis.
+    pub fn set_x_column(&mut self) {
+        self.x_column = self.selected;
+        self.y_columns.retain(|&c| c != self.selected);
+    }
+
+    pub fn toggle_kind(&mut self) {
+        self.kind = match self.kind {
+            ChartKind::Bar => ChartKind::Line,
+            ChartKind::Line => ChartKind::Bar,
+        };
+    }
+
+    /// Lines are drawn against the X values when they are numbers, and against
+    /// the row position otherwise.
+    pub fn x_is_numeric(&self) -> bool {
+        self.numeric[self.x_column]
+    }
+
+    /// Points of each plotted column for a line chart, in X order.
+    pub fn line_points(&self, result: &QueryResult) -> Vec<Vec<(f64, f64)>> {
+        self.y_columns
+            .iter()
+            .map(|&y| {
+                let mut points: Vec<(f64, f64)> = result
+                    .rows
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, row)| {
+                        let x = if self.x_is_numeric() {
+                            parse_number(row.get(self.x_column)?)?
+                        } else {
+                            i as f64
+                        };
+                        Some((x, parse_number(row.get(y)?)?))
+                    })
+                    .collect();
+                points.sort_by(|a, b| a.0.total_cmp(&b.0));
+                points
+            })
+            .collect()
+    }
+}
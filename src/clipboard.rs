@@ -0,0 +1,22 @@
+// Copying to the system clipboard with the OSC 52 terminal escape sequence.
+// The terminal does the copying, so it also works over SSH and without a
+// display server; terminals that don't support OSC 52 ignore it.
+
+use anyhow::Result;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::io::Write;
+
+pub fn copy(text: &str) -> Result<()> {
+    let encoded = STANDARD.encode(text);
+    let sequence = if std::env::var_os("TMUX").is_some() {
+        // tmux only forwards escape sequences wrapped in its passthrough
+        format!("\x1bPtmux;\x1b\x1b]52;c;{}\x07\x1b\\", encoded)
+    } else {
+        format!("\x1b]52;c;{}\x07", encoded)
+    };
+    let mut stdout = std::io::stdout();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
@@ -0,0 +1,122 @@
+// Filter of the result rows by one column. The pattern is `=value` for an exact
+// match, `~regex` for a regular expression, or any other text to look for,
+// ignoring case. It narrows the rows already loaded, and can be turned into a
+// WHERE clause to run the query again with the filter applied on the server.
+
+use crate::database::QueryResult;
+use crate::dialect::Dialect;
+use anyhow::{Result, anyhow};
+use regex::Regex;
+
+#[derive(Debug, Clone)]
+enum FilterMatch {
+    Equals(String),
+    Contains(String), // Lowercased
+    Regex(Regex),
+}
+
+/// An active filter and the result it narrowed, restored when it is cleared.
+#[derive(Debug, Clone)]
+pub struct ColumnFilter {
+    pub column: String,
+    pub pattern: String, // As typed
+    index: usize,        // Of the column in the result
+    matcher: FilterMatch,
+    pub unfiltered: QueryResult,
+}
+
+impl ColumnFilter {
+    pub fn new(column: usize, pattern: &str, unfiltered: QueryResult) -> Result<Self> {
+        let name = unfiltered
+            .columns
+            .get(column)
+            .cloned()
+            .ok_or_else(|| anyhow!("No column is selected"))?;
+        let matcher = if let Some(value) = pattern.strip_prefix('=') {
+            FilterMatch::Equals(value.to_string())
+        } else if let Some(regex) = pattern.strip_prefix('~') {
+            FilterMatch::Regex(Regex::new(regex).map_err(|e| anyhow!("Invalid regex: {}", e))?)
+        } else {
+            FilterMatch::Contains(pattern.to_lowercase())
+        };
+        Ok(Self {
+            column: name,
+            pattern: pattern.to_string(),
+            index: column,
+            matcher,
+            unfiltered,
+        })
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        match &self.matcher {
+            FilterMatch::Equals(expected) => value == expected,
+            FilterMatch::Contains(text) => value.to_lowercase().contains(text),
+            FilterMatch::Regex(regex) => regex.is_match(value),
+        }
+    }
+
+    /// The unfiltered result narrowed to the matching rows.
+    pub fn apply(&self) -> QueryResult {
+        let mut result = self.unfiltered.clone();
+        (result.rows, result.nulls) = result
+            .rows
+            .into_iter()
+            .zip(result.nulls)
+            .filter(|(row, _)| row.get(self.index).is_some_and(|v| self.matches(v)))
+            .unzip();
+        result.total_count = Some(result.rows.len());
+        result.total_count_estimated = false;
+        result
+    }
+
+    /// e.g. `name contains "smith"`, for the title and status bar.
+    pub fn describe(&self) -> String {
+        match &self.matcher {
+            FilterMatch::Equals(value) => format!("{} = {:?}", self.column, value),
+            FilterMatch::Contains(text) => format!("{} contains {:?}", self.column, text),
+            FilterMatch::Regex(regex) => format!("{} ~ /{}/", self.column, regex.as_str()),
+        }
+    }
+
+    /// The filter as a condition on the column of a derived table, comparing
+    /// its text form as the results show it. SQLite has no REGEXP by default.
+    pub fn where_clause(&self, dialect: Dialect) -> Result<String> {
+        let column = dialect.quote_identifier(&self.column);
+        let text = dialect.cast_to_text(&column);
+        Ok(match &self.matcher {
+            FilterMatch::Equals(value) if value == "NULL" => format!("{} IS NULL", column),
+            FilterMatch::Equals(value) => format!("{} = {}", text, dialect.string_literal(value)),
+            FilterMatch::Contains(value) => {
+                // `!` escapes LIKE's wildcards the same way in all three databases
+                let escaped = value
+                    .replace('!', "!!")
+                    .replace('%', "!%")
+                    .replace('_', "!_");
+                let like = match dialect {
+                    Dialect::PostgreSQL => "ILIKE",
+                    Dialect::MySQL | Dialect::SQLite => "LIKE",
+                };
+                format!(
+                    "{} {} {} ESCAPE '!'",
+                    text,
+                    like,
+                    dialect.string_literal(&format!("%{}%", escaped))
+                )
+            }
+            FilterMatch::Regex(regex) => match dialect {
+                Dialect::PostgreSQL => {
+                    format!("{} ~ {}", text, dialect.string_literal(regex.as_str()))
+                }
+                Dialect::MySQL => {
+                    format!("{} REGEXP {}", text, dialect.string_literal(regex.as_str()))
+                }
+                Dialect::SQLite => {
+                    return Err(anyhow!(
+                        "SQLite has no REGEXP; use a contains or = filter to run it on the server"
+                    ));
+                }
+            },
+        })
+    }
+}
@@ -0,0 +1,200 @@
+// Comments on a table and its columns, opened from the table browser: the
+// table's comment and each column's are listed, and editing one previews the
+// COMMENT ON statement (PostgreSQL) or ALTER TABLE (MySQL) before it runs. An
+// empty comment removes it. MySQL has no statement for a column's comment
+// alone, so its MODIFY COLUMN restates the column's definition as SHOW CREATE
+// TABLE gives it, with the new COMMENT clause.
+
+use crate::database::{ColumnInfo, DatabasePool, TableInfo};
+use crate::dialect::Dialect;
+use crate::textinput::TextInput;
+use anyhow::{Result, anyhow};
+use sqlx::Row;
+
+/// The table (no column) or a column, and its current comment.
+#[derive(Debug, Clone)]
+pub struct CommentTarget {
+    pub column: Option<String>,
+    pub data_type: String,
+    pub comment: Option<String>,
+}
+
+/// The comments popup of one table.
+#[derive(Debug, Clone)]
+pub struct CommentEditor {
+    pub dialect: Dialect,
+    pub table: TableInfo,
+    pub targets: Vec<CommentTarget>, // The table first, then its columns
+    pub selected: usize,
+    pub input: Option<TextInput>, // New comment of the selected target being typed
+    pub preview: Option<String>,  // Statement waiting to be confirmed
+    column_definitions: Vec<(String, String)>, // MySQL: name and definition
+}
+
+impl CommentEditor {
+    pub fn new(
+        dialect: Dialect,
+        table: TableInfo,
+        table_comment: Option<String>,
+        columns: &[ColumnInfo],
+        column_definitions: Vec<(String, String)>,
+    ) -> Self {
+        let mut targets = vec![CommentTarget {
+            column: None,
+            data_type: String::new(),
+            comment: table_comment,
+        }];
+        targets.extend(columns.iter().map(|column| CommentTarget {
+            column: Some(column.name.clone()),
+            data_type: column.data_type.clone(),
+            comment: column.comment.clone(),
+        }));
+        Self {
+            dialect,
+            table,
+            targets,
+            selected: 0,
+            input: None,
+            preview: None,
+            column_definitions,
+        }
+    }
+
+    pub fn selected_target(&self) -> Option<&CommentTarget> {
+        self.targets.get(self.selected)
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.targets.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Starts typing the selected target's comment, from its current one.
+    pub fn start_editing(&mut self) {
+        if let Some(target) = self.selected_target() {
+            self.input = Some(TextInput::new(target.comment.clone().unwrap_or_default()));
+        }
+    }
+
+    /// The statement setting the selected target's comment to the input.
+    pub fn statement(&self) -> Result<String> {
+        let (Some(target), Some(input)) = (self.selected_target(), &self.input) else {
+            return Err(anyhow!("Nothing is being edited"));
+        };
+        let comment = input.text.trim();
+        let table = self.dialect.quote_table(&self.table.qualified_name());
+        match (self.dialect, &target.column) {
+            (Dialect::MySQL, None) => Ok(format!(
+                "ALTER TABLE {} COMMENT = {};",
+                table,
+                self.dialect.string_literal(comment)
+            )),
+            (Dialect::MySQL, Some(column)) => {
+                let definition = self
+                    .column_definitions
+                    .iter()
+                    .find(|(name, _)| name == column)
+                    .map(|(_, definition)| definition)
+                    .ok_or_else(|| anyhow!("The definition of {} wasn't found", column))?;
+                let mut statement = format!(
+                    "ALTER TABLE {} MODIFY COLUMN {} {}",
+                    table,
+                    self.dialect.quote_identifier(column),
+                    without_comment(definition)
+                );
+                if !comment.is_empty() {
+                    statement.push_str(&format!(
+                        " COMMENT {}",
+                        self.dialect.string_literal(comment)
+                    ));
+                }
+                statement.push(';');
+                Ok(statement)
+            }
+            (_, column) => {
+                let object = match column {
+                    Some(column) => {
+                        format!("COLUMN {}.{}", table, self.dialect.quote_identifier(column))
+                    }
+                    None => format!("TABLE {}", table),
+                };
+                let comment = if comment.is_empty() {
+                    "NULL".to_string()
+                } else {
+                    self.dialect.string_literal(comment)
+                };
+                Ok(format!("COMMENT ON {} IS {};", object, comment))
+            }
+        }
+    }
+}
+
+/// MySQL: each column's definition after its name, from SHOW CREATE TABLE.
+pub async fn column_definitions(
+    pool: &DatabasePool,
+    table: &TableInfo,
+) -> Result<Vec<(String, String)>> {
+    let DatabasePool::MySQL(pool) = pool else {
+        return Ok(Vec::new());
+    };
+    let query = format!(
+        "SHOW CREATE TABLE {}",
+        Dialect::MySQL.quote_identifier(&table.name)
+    );
+    let row = sqlx::query(&query).fetch_one(pool).await?;
+    let create: String = match row.try_get(1) {
+        Ok(create) => create,
+        Err(_) => String::from_utf8_lossy(&row.try_get::<Vec<u8>, _>(1)?).to_string(),
+    };
+    // Column lines look like "  `name` int NOT NULL DEFAULT '0',"
+    Ok(create
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix('`')?;
+            let mut name = String::new();
+            let mut chars = rest.char_indices();
+            while let Some((i, c)) = chars.next() {
+                if c != '`' {
+                    name.push(c);
+                } else if rest[i + 1..].starts_with('`') {
+                    name.push('`');
+                    chars.next();
+                } else {
+                    let definition = rest[i + 1..].trim().trim_end_matches(',');
+                    return Some((name, definition.to_string()));
+                }
+            }
+            None
+        })
+        .collect())
+}
+
+/// A MySQL column definition without its COMMENT clause.
+fn without_comment(definition: &str) -> String {
+    let Some(start) = definition.find(" COMMENT '") else {
+        return definition.to_string();
+    };
+    // The literal ends at a quote that isn't doubled or escaped
+    let literal = &definition[start + " COMMENT '".len()..];
+    let mut end = None;
+    let mut chars = literal.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '\'' if chars.peek().is_some_and(|(_, next)| *next == '\'') => {
+                chars.next();
+            }
+            '\'' => {
+                end = Some(i + 1);
+                break;
+            }
+            _ => {}
+        }
+    }
+    match end {
+        Some(end) => format!("{}{}", &definition[..start], &literal[end..]),
+        None => definition[..start].to_string(),
+    }
+}
@@ -0,0 +1,371 @@
+// Dashboards: queries pinned as tiles, each refreshed on its own interval and
+// shown as a small table or, for a stat tile, its first value on its own. The
+// tiles live in dashboards.toml in the config directory, where each names the
+// connection it belongs to (or none, to show on every connection); adding,
+// changing or removing tiles on the dashboard screen writes the file back,
+// dropping any comments in it. Only queries that read are run, each in its own
+// task so a slow tile doesn't hold up the others, and only while the dashboard
+// is shown; tiles with an alert rule (`> 100` on their first value) keep
+// refreshing in the background once the dashboard has been opened, so the
+// rule is watched from any screen.
+
+use crate::database::{DatabasePool, QueryResult};
+use crate::settings::config_dir;
+use crate::sql;
+use anyhow::{Result, anyhow};
+use std::fs;
+use std::time::Instant;
+use tokio::task::JoinHandle;
+
+const DASHBOARD_FILE: &str = "dashboards.toml";
+
+// Refresh interval of a tile added on the dashboard screen
+const DEFAULT_REFRESH_SECONDS: u64 = 10;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TileKind {
+    #[default]
+    Table,
+    Stat, // The first column of the first row
+}
+
+fn default_refresh_seconds() -> u64 {
+    DEFAULT_REFRESH_SECONDS
+}
+
+/// A tile as written in dashboards.toml.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TileConfig {
+    pub title: String,
+    pub query: String,
+    #[serde(default)]
+    pub kind: TileKind,
+    #[serde(default = "default_refresh_seconds")]
+    pub refresh_seconds: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<String>, // Shown on every connection without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert: Option<String>, // Rule on the first value, e.g. `> 100`
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+}
+
+/// A threshold on a tile's first value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlertRule {
+    comparison: Comparison,
+    threshold: f64,
+}
+
+impl AlertRule {
+    /// Reads a rule like `> 100`, `<= 0.5` or `!= 0`.
+    pub fn parse(rule: &str) -> Result<Self> {
+        let rule = rule.trim();
+        let (comparison, rest) = [
+            (">=", Comparison::GreaterOrEqual),
+            ("<=", Comparison::LessOrEqual),
+            ("!=", Comparison::NotEqual),
+            ("<>", Comparison::NotEqual),
+            ("==", Comparison::Equal),
+            (">", Comparison::Greater),
+            ("<", Comparison::Less),
+            ("=", Comparison::Equal),
+        ]
+        .into_iter()
+        .find_map(|(operator, comparison)| Some((comparison, rule.strip_prefix(operator)?)))
+        .ok_or_else(|| anyhow!("An alert starts with >, >=, <, <=, = or !=, as in > 100"))?;
+        let threshold = rest
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("{} isn't a number to compare with", rest.trim()))?;
+        Ok(Self {
+            comparison,
+            threshold,
+        })
+    }
+
+    /// Whether the value breaks the rule; values that aren't numbers never do.
+    pub fn fires(&self, value: &str) -> bool {
+        let Ok(value) = value.trim().parse::<f64>() else {
+            return false;
+        };
+        match self.comparison {
+            Comparison::Greater => value > self.threshold,
+            Comparison::GreaterOrEqual => value >= self.threshold,
+            Comparison::Less => value < self.threshold,
+            Comparison::LessOrEqual => value <= self.threshold,
+            Comparison::Equal => value == self.threshold,
+            Comparison::NotEqual => value != self.threshold,
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct DashboardFile {
+    #[serde(default, rename = "tile")]
+    tiles: Vec<TileConfig>,
+}
+
+fn load() -> Result<Vec<TileConfig>> {
+    let file = config_dir()?.join(DASHBOARD_FILE);
+    if !file.exists() {
+        return Ok(Vec::new());
+    }
+    let parsed: DashboardFile = toml::from_str(&fs::read_to_string(&file)?)
+        .map_err(|e| anyhow!("{}: {}", file.display(), e))?;
+    Ok(parsed.tiles)
+}
+
+fn save(tiles: &[TileConfig]) -> Result<()> {
+    let file = config_dir()?.join(DASHBOARD_FILE);
+    let content = toml::to_string_pretty(&DashboardFile {
+        tiles: tiles.to_vec(),
+    })?;
+    fs::write(file, content)?;
+    Ok(())
+}
+
+/// A tile shown on the dashboard and its latest result.
+#[derive(Debug)]
+pub struct Tile {
+    pub config: usize, // Index into the dashboard's `configs`
+    pub result: Option<Result<QueryResult, String>>,
+    pub refreshed_at: Option<Instant>,
+    pub task: Option<JoinHandle<Result<QueryResult>>>,
+    pub alerting: bool, // The latest result breaks the tile's alert rule
+}
+
+/// The dashboard of one connection.
+#[derive(Debug)]
+pub struct Dashboard {
+    pub connection: String,
+    pub configs: Vec<TileConfig>, // Every tile in the file, other connections' too
+    pub tiles: Vec<Tile>,         // This connection's tiles, in file order
+    pub selected: usize,
+    pub confirming_delete: bool, // `d` was pressed once on the selected tile
+}
+
+impl Dashboard {
+    pub fn open(connection: &str) -> Result<Self> {
+        let mut dashboard = Self {
+            connection: connection.to_string(),
+            configs: load()?,
+            tiles: Vec::new(),
+            selected: 0,
+            confirming_delete: false,
+        };
+        dashboard.list_tiles();
+        Ok(dashboard)
+    }
+
+    fn list_tiles(&mut self) {
+        self.tiles = self
+            .configs
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.connection.as_ref().is_none_or(|n| *n == self.connection))
+            .map(|(config, _)| Tile {
+                config,
+                result: None,
+                refreshed_at: None,
+                task: None,
+                alerting: false,
+            })
+            .collect();
+        self.selected = self.selected.min(self.tiles.len().saturating_sub(1));
+    }
+
+    /// Reads the file again, keeping nothing of the old tiles' results.
+    pub fn reload(&mut self) -> Result<()> {
+        self.stop();
+        self.configs = load()?;
+        self.list_tiles();
+        Ok(())
+    }
+
+    pub fn config(&self, tile: &Tile) -> &TileConfig {
+        &self.configs[tile.config]
+    }
+
+    pub fn selected_config_mut(&mut self) -> Option<&mut TileConfig> {
+        let tile = self.tiles.get(self.selected)?;
+        self.configs.get_mut(tile.config)
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.tiles.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+        self.confirming_delete = false;
+    }
+
+    /// Adds a tile for this connection and selects it.
+    pub fn add(&mut self, title: &str, query: &str) -> Result<()> {
+        self.configs.push(TileConfig {
+            title: title.to_string(),
+            query: query.trim().to_string(),
+            kind: TileKind::Table,
+            refresh_seconds: DEFAULT_REFRESH_SECONDS,
+            connection: Some(self.connection.clone()),
+            alert: None,
+        });
+        self.tiles.push(Tile {
+            config: self.configs.len() - 1,
+            result: None,
+            refreshed_at: None,
+            task: None,
+            alerting: false,
+        });
+        self.selected = self.tiles.len() - 1;
+        save(&self.configs)
+    }
+
+    /// Removes the selected tile from the file.
+    pub fn delete_selected(&mut self) -> Result<()> {
+        if self.selected >= self.tiles.len() {
+            return Ok(());
+        }
+        let tile = self.tiles.remove(self.selected);
+        if let Some(task) = tile.task {
+            task.abort();
+        }
+        self.configs.remove(tile.config);
+        for other in &mut self.tiles {
+            if other.config > tile.config {
+                other.config -= 1;
+            }
+        }
+        self.selected = self.selected.min(self.tiles.len().saturating_sub(1));
+        self.confirming_delete = false;
+        save(&self.configs)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        save(&self.configs)
+    }
+
+    /// Runs every tile again now.
+    pub fn refresh_all(&mut self) {
+        for tile in &mut self.tiles {
+            tile.refreshed_at = None;
+        }
+    }
+
+    /// Stops tiles' queries still running, when the dashboard is left.
+    pub fn stop(&mut self) {
+        for tile in &mut self.tiles {
+            if let Some(task) = tile.task.take() {
+                task.abort();
+            }
+        }
+    }
+
+    /// Whether any of this connection's tiles has an alert to watch.
+    pub fn has_alerts(&self) -> bool {
+        self.tiles
+            .iter()
+            .any(|tile| self.config(tile).alert.is_some())
+    }
+
+    /// Sets or, with an empty rule, removes the selected tile's alert.
+    pub fn set_selected_alert(&mut self, rule: &str) -> Result<()> {
+        let rule = rule.trim();
+        if !rule.is_empty() {
+            AlertRule::parse(rule)?;
+        }
+        let Some(tile) = self.tiles.get_mut(self.selected) else {
+            return Ok(());
+        };
+        self.configs[tile.config].alert = (!rule.is_empty()).then(|| rule.to_string());
+        tile.alerting = false;
+        tile.refreshed_at = None;
+        save(&self.configs)
+    }
+
+    /// Titles and values of the tiles whose alert is firing.
+    pub fn firing(&self) -> Vec<String> {
+        self.tiles
+            .iter()
+            .filter(|tile| tile.alerting)
+            .map(|tile| self.describe_alert(tile))
+            .collect()
+    }
+
+    fn describe_alert(&self, tile: &Tile) -> String {
+        let config = self.config(tile);
+        format!(
+            "{} is {} ({})",
+            config.title,
+            first_value(tile).unwrap_or_default(),
+            config.alert.as_deref().unwrap_or_default().trim()
+        )
+    }
+
+    /// Collects finished tiles' results and starts the queries of tiles due
+    /// for a refresh: every tile while the dashboard is shown, otherwise only
+    /// those with an alert. Called every tick; returns the alerts that started
+    /// firing.
+    pub async fn poll(&mut self, pool: &DatabasePool, shown: bool) -> Vec<String> {
+        let database_type = pool.database_type();
+        let mut fired = Vec::new();
+        for i in 0..self.tiles.len() {
+            let tile = &mut self.tiles[i];
+            let config = &self.configs[tile.config];
+            if tile.task.as_ref().is_some_and(|task| task.is_finished())
+                && let Some(task) = tile.task.take()
+            {
+                tile.result = Some(match task.await {
+                    Ok(result) => result.map_err(|e| e.to_string()),
+                    Err(e) => Err(format!("The query task failed: {}", e)),
+                });
+                let was_alerting = tile.alerting;
+                tile.alerting = match config.alert.as_deref().map(AlertRule::parse) {
+                    Some(Ok(rule)) => first_value(tile).is_some_and(|v| rule.fires(v)),
+                    Some(Err(e)) => {
+                        tile.result = Some(Err(format!("Invalid alert: {}", e)));
+                        false
+                    }
+                    None => false,
+                };
+                if tile.alerting && !was_alerting {
+                    fired.push(self.describe_alert(&self.tiles[i]));
+                }
+            }
+
+            let tile = &mut self.tiles[i];
+            let config = &self.configs[tile.config];
+            let due = tile
+                .refreshed_at
+                .is_none_or(|at| at.elapsed().as_secs() >= config.refresh_seconds.max(1));
+            if tile.task.is_some() || !due || (!shown && config.alert.is_none()) {
+                continue;
+            }
+            tile.refreshed_at = Some(Instant::now());
+            if !sql::is_query(&config.query, &database_type) {
+                tile.result = Some(Err("Only queries that read can be tiles".to_string()));
+                continue;
+            }
+            let (pool, query) = (pool.clone(), config.query.clone());
+            tile.task = Some(tokio::spawn(
+                async move { pool.execute_query(&query).await },
+            ));
+        }
+        fired
+    }
+}
+
+/// The first column of the first row of a tile's latest result.
+fn first_value(tile: &Tile) -> Option<&str> {
+    match &tile.result {
+        Some(Ok(result)) => result.rows.first()?.first().map(String::as_str),
+        _ => None,
+    }
+}
@@ -0,0 +1,162 @@
+use crate::database::DatabaseType;
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, Timelike};
+
+/// Where the picked value ends up once confirmed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DatePickerTarget {
+    QueryEditor,
+    ColumnFilter, // Filter of the selected result column, by the picked day or time
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PickerFocus {
+    Calendar,
+    Hour,
+    Minute,
+    Second,
+}
+
+/// Keyboard-driven calendar/time picker that produces dialect-specific literals.
+#[derive(Debug, Clone)]
+pub struct DatePicker {
+    pub target: DatePickerTarget,
+    pub value: NaiveDateTime,
+    pub include_time: bool,
+    pub focus: PickerFocus,
+}
+
+impl DatePicker {
+    /// Creates a picker starting at `initial` if it parses as a date or timestamp,
+    /// otherwise at the current local time.
+    pub fn new(target: DatePickerTarget, initial: Option<&str>) -> Self {
+        let parsed = initial.and_then(parse_datetime);
+        let include_time = parsed.is_none_or(|(_, has_time)| has_time);
+        let value = parsed.map(|(value, _)| value).unwrap_or_else(|| {
+            chrono::Local::now()
+                .naive_local()
+                .with_nanosecond(0)
+                .unwrap()
+        });
+
+        Self {
+            target,
+            value,
+            include_time,
+            focus: PickerFocus::Calendar,
+        }
+    }
+
+    pub fn move_days(&mut self, days: i64) {
+        self.value += Duration::days(days);
+    }
+
+    pub fn move_months(&mut self, months: i32) {
+        let shifted = if months >= 0 {
+            self.value.checked_add_months(Months::new(months as u32))
+        } else {
+            self.value
+                .checked_sub_months(Months::new(months.unsigned_abs()))
+        };
+        if let Some(value) = shifted {
+            self.value = value;
+        }
+    }
+
+    pub fn toggle_time(&mut self) {
+        self.include_time = !self.include_time;
+        if !self.include_time {
+            self.focus = PickerFocus::Calendar;
+        }
+    }
+
+    pub fn next_focus(&mut self) {
+        self.focus = if !self.include_time {
+            PickerFocus::Calendar
+        } else {
+            match self.focus {
+                PickerFocus::Calendar => PickerFocus::Hour,
+                PickerFocus::Hour => PickerFocus::Minute,
+                PickerFocus::Minute => PickerFocus::Second,
+                PickerFocus::Second => PickerFocus::Calendar,
+            }
+        };
+    }
+
+    /// Adjusts the focused time component by `delta`, wrapping within its range.
+    pub fn adjust_time(&mut self, delta: i64) {
+        let seconds = match self.focus {
+            PickerFocus::Calendar => return,
+            PickerFocus::Hour => 3600,
+            PickerFocus::Minute => 60,
+            PickerFocus::Second => 1,
+        };
+        let date = self.value.date();
+        let time = self.value.time() + Duration::seconds(seconds * delta);
+        self.value = date.and_time(time);
+    }
+
+    pub fn first_of_month(&self) -> NaiveDate {
+        self.value.date().with_day(1).unwrap()
+    }
+
+    pub fn days_in_month(&self) -> u32 {
+        let first = self.first_of_month();
+        let next = first.checked_add_months(Months::new(1)).unwrap();
+        (next - first).num_days() as u32
+    }
+
+    pub fn formatted_value(&self) -> String {
+        if self.include_time {
+            self.value.format("%Y-%m-%d %H:%M:%S").to_string()
+        } else {
+            self.value.format("%Y-%m-%d").to_string()
+        }
+    }
+
+    /// Renders the picked value as a literal for the given database.
+    pub fn to_literal(&self, database_type: &DatabaseType) -> String {
+        let value = self.formatted_value();
+        match database_type {
+            // SQLite stores dates as text; plain ISO strings compare correctly
+            DatabaseType::SQLite => format!("'{}'", value),
+            DatabaseType::PostgreSQL | DatabaseType::MySQL => {
+                if self.include_time {
+                    format!("TIMESTAMP '{}'", value)
+                } else {
+                    format!("DATE '{}'", value)
+                }
+            }
+        }
+    }
+}
+
+/// Whether the values are dates or timestamps: there is at least one and every
+/// one parses as such. NULL cells are left out by the caller.
+pub fn holds_dates<'a>(mut values: impl Iterator<Item = &'a str>) -> bool {
+    let mut any = false;
+    values.all(|value| {
+        any = true;
+        parse_datetime(value).is_some()
+    }) && any
+}
+
+/// Parses common ISO date/timestamp strings, returning whether a time part was present.
+fn parse_datetime(value: &str) -> Option<(NaiveDateTime, bool)> {
+    let value = value.trim().trim_matches('\'');
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value.get(..19).unwrap_or(value), format) {
+            return Some((dt, true));
+        }
+    }
+    NaiveDate::parse_from_str(value.get(..10).unwrap_or(value), "%Y-%m-%d")
+        .ok()
+        .map(|d| (d.and_hms_opt(0, 0, 0).unwrap(), false))
+}
+
+/// Weekday header for a Monday-first calendar grid.
+pub const WEEKDAY_HEADER: &str = "Mo Tu We Th Fr Sa Su";
+
+/// Number of blank cells before day 1 in a Monday-first calendar grid.
+pub fn leading_blanks(first: NaiveDate) -> u32 {
+    first.weekday().num_days_from_monday()
+}
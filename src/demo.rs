@@ -1,18 +1,48 @@
-use crate::database::{ConnectionConfig, DatabasePool, DatabaseType};
-use anyhow::Result;
-
-pub async fn create_demo_database() -> Result<()> {
-    let config = ConnectionConfig {
-        name: "Demo SQLite Database".to_string(),
-        database_type: DatabaseType::SQLite,
-        connection_string: "sqlite:demo.db".to_string(),
-        ssl_config: None,
-    };
+// The demo database (`--create-demo`, in demo.db or on a given PostgreSQL or
+// MySQL database) and a seed-data generator for development databases. Seeding fills the selected table with rows of
+// plausible fake values: each column gets a generator guessed from its name and
+// type (names, emails, dates, numbers, ...), which can be changed before the
+// rows are inserted in batches. Foreign key columns pick values that exist in
+// the referenced table, and columns left to the database's default (such as
+// auto-increment keys) are not inserted at all.
 
-    let pool = DatabasePool::connect(&config).await?;
+use crate::database::{ColumnInfo, ConnectionConfig, DatabasePool, DatabaseType, TableInfo};
+use crate::dialect::Dialect;
+use crate::dump;
+use crate::textinput::TextInput;
+use anyhow::{Result, anyhow};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 
-    // Create demo tables
-    let create_users_table = r#"
+// Demo rows, the same on every database
+const DEMO_USERS: &str = r#"users (id, name, email, age) VALUES
+        (1, 'John Doe', 'john@example.com', 30),
+        (2, 'Jane Smith', 'jane@example.com', 25),
+        (3, 'Bob Johnson', 'bob@example.com', 35),
+        (4, 'Alice Brown', 'alice@example.com', 28),
+        (5, 'Charlie Wilson', 'charlie@example.com', 42)"#;
+
+const DEMO_ORDERS: &str = r#"orders (id, user_id, product_name, quantity, price) VALUES
+        (1, 1, 'Laptop', 1, 999.99),
+        (2, 1, 'Mouse', 2, 25.50),
+        (3, 2, 'Keyboard', 1, 75.00),
+        (4, 3, 'Monitor', 1, 299.99),
+        (5, 2, 'Webcam', 1, 89.99),
+        (6, 4, 'Headphones', 1, 149.99),
+        (7, 5, 'Tablet', 1, 399.99),
+        (8, 3, 'Phone', 1, 699.99)"#;
+
+const DEMO_CATEGORIES: &str = r#"categories (id, name, description) VALUES
+        (1, 'Electronics', 'Electronic devices and gadgets'),
+        (2, 'Computers', 'Computer hardware and accessories'),
+        (3, 'Audio', 'Audio equipment and accessories'),
+        (4, 'Mobile', 'Mobile phones and accessories')"#;
+
+/// The demo tables' CREATE statements: users, orders and categories.
+fn demo_tables(database_type: &DatabaseType) -> [&'static str; 3] {
+    match database_type {
+        DatabaseType::SQLite => [
+            r#"
         CREATE TABLE IF NOT EXISTS users (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             name TEXT NOT NULL,
@@ -20,9 +50,8 @@ pub async fn create_demo_database() -> Result<()> {
             age INTEGER,
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP
         )
-    "#;
-
-    let create_orders_table = r#"
+    "#,
+            r#"
         CREATE TABLE IF NOT EXISTS orders (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             user_id INTEGER NOT NULL,
@@ -32,73 +61,665 @@ pub async fn create_demo_database() -> Result<()> {
             order_date DATETIME DEFAULT CURRENT_TIMESTAMP,
             FOREIGN KEY (user_id) REFERENCES users(id)
         )
-    "#;
-
-    let create_categories_table = r#"
+    "#,
+            r#"
         CREATE TABLE IF NOT EXISTS categories (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             name TEXT NOT NULL UNIQUE,
             description TEXT
         )
-    "#;
-
-    // Execute table creation
-    if let Err(e) = pool.execute_query(create_users_table).await {
-        eprintln!("Error creating users table: {}", e);
-        return Err(e);
+    "#,
+        ],
+        DatabaseType::PostgreSQL => [
+            r#"
+        CREATE TABLE IF NOT EXISTS users (
+            id SERIAL PRIMARY KEY,
+            name TEXT NOT NULL,
+            email TEXT UNIQUE NOT NULL,
+            age INTEGER,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )
+    "#,
+            r#"
+        CREATE TABLE IF NOT EXISTS orders (
+            id SERIAL PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            product_name TEXT NOT NULL,
+            quantity INTEGER NOT NULL DEFAULT 1,
+            price NUMERIC(10,2) NOT NULL,
+            order_date TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )
+    "#,
+            r#"
+        CREATE TABLE IF NOT EXISTS categories (
+            id SERIAL PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            description TEXT
+        )
+    "#,
+        ],
+        // TEXT can't be UNIQUE on MySQL without a prefix length
+        DatabaseType::MySQL => [
+            r#"
+        CREATE TABLE IF NOT EXISTS users (
+            id INT AUTO_INCREMENT PRIMARY KEY,
+            name VARCHAR(255) NOT NULL,
+            email VARCHAR(255) UNIQUE NOT NULL,
+            age INT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )
+    "#,
+            r#"
+        CREATE TABLE IF NOT EXISTS orders (
+            id INT AUTO_INCREMENT PRIMARY KEY,
+            user_id INT NOT NULL,
+            product_name VARCHAR(255) NOT NULL,
+            quantity INT NOT NULL DEFAULT 1,
+            price DECIMAL(10,2) NOT NULL,
+            order_date DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (user_id) REFERENCES users(id)
+        )
+    "#,
+            r#"
+        CREATE TABLE IF NOT EXISTS categories (
+            id INT AUTO_INCREMENT PRIMARY KEY,
+            name VARCHAR(255) NOT NULL UNIQUE,
+            description TEXT
+        )
+    "#,
+        ],
     }
-    if let Err(e) = pool.execute_query(create_orders_table).await {
-        eprintln!("Error creating orders table: {}", e);
-        return Err(e);
+}
+
+/// INSERT of demo rows. SQLite replaces rows with the same id; PostgreSQL and
+/// MySQL keep them, as other rows may reference them.
+fn demo_insert(database_type: &DatabaseType, rows: &str) -> String {
+    match database_type {
+        DatabaseType::SQLite => format!("INSERT OR REPLACE INTO {}", rows),
+        DatabaseType::PostgreSQL => format!("INSERT INTO {} ON CONFLICT (id) DO NOTHING", rows),
+        DatabaseType::MySQL => format!("INSERT IGNORE INTO {}", rows),
     }
-    if let Err(e) = pool.execute_query(create_categories_table).await {
-        eprintln!("Error creating categories table: {}", e);
-        return Err(e);
+}
+
+/// Creates the demo tables and rows in `demo.db`, or on the database at
+/// `connection_string` (PostgreSQL, MySQL or another SQLite file).
+pub async fn create_demo_database(connection_string: Option<&str>) -> Result<()> {
+    let config = match connection_string {
+        Some(connection_string) => {
+            ConnectionConfig::new("Demo Database".to_string(), connection_string.to_string())?
+        }
+        None => ConnectionConfig {
+            name: "Demo SQLite Database".to_string(),
+            database_type: DatabaseType::SQLite,
+            // rwc creates the file when it doesn't exist yet
+            connection_string: "sqlite:demo.db?mode=rwc".to_string(),
+            ssl_config: None,
+            settings: Default::default(),
+            group: None,
+            tags: Vec::new(),
+            color: None,
+            last_used: None,
+        },
+    };
+    let database_type = config.database_type.clone();
+
+    let pool = DatabasePool::connect(&config).await?;
+
+    // Create demo tables
+    for (name, statement) in ["users", "orders", "categories"]
+        .into_iter()
+        .zip(demo_tables(&database_type))
+    {
+        if let Err(e) = pool.execute_statement(statement).await {
+            eprintln!("Error creating {} table: {}", name, e);
+            return Err(e);
+        }
     }
 
     // Insert demo data
-    let insert_users = r#"
-        INSERT OR REPLACE INTO users (id, name, email, age) VALUES
-        (1, 'John Doe', 'john@example.com', 30),
-        (2, 'Jane Smith', 'jane@example.com', 25),
-        (3, 'Bob Johnson', 'bob@example.com', 35),
-        (4, 'Alice Brown', 'alice@example.com', 28),
-        (5, 'Charlie Wilson', 'charlie@example.com', 42)
-    "#;
+    for (name, rows) in [
+        ("users", DEMO_USERS),
+        ("orders", DEMO_ORDERS),
+        ("categories", DEMO_CATEGORIES),
+    ] {
+        if let Err(e) = pool
+            .execute_statement(&demo_insert(&database_type, rows))
+            .await
+        {
+            eprintln!("Error inserting {}: {}", name, e);
+            return Err(e);
+        }
+        // Serial columns don't advance for explicit ids, so new rows would
+        // start at 1 again
+        if matches!(database_type, DatabaseType::PostgreSQL) {
+            pool.execute_query(&format!(
+                "SELECT setval(pg_get_serial_sequence('{0}', 'id'), (SELECT MAX(id) FROM {0}))",
+                name
+            ))
+            .await?;
+        }
+    }
 
-    let insert_orders = r#"
-        INSERT OR REPLACE INTO orders (id, user_id, product_name, quantity, price) VALUES
-        (1, 1, 'Laptop', 1, 999.99),
-        (2, 1, 'Mouse', 2, 25.50),
-        (3, 2, 'Keyboard', 1, 75.00),
-        (4, 3, 'Monitor', 1, 299.99),
-        (5, 2, 'Webcam', 1, 89.99),
-        (6, 4, 'Headphones', 1, 149.99),
-        (7, 5, 'Tablet', 1, 399.99),
-        (8, 3, 'Phone', 1, 699.99)
-    "#;
+    match connection_string {
+        Some(_) => println!(
+            "Demo tables created successfully on {}! Add the connection in the app to browse them.",
+            database_type.display_name()
+        ),
+        None => println!("Demo database created successfully with sample data!"),
+    }
+    Ok(())
+}
 
-    let insert_categories = r#"
-        INSERT OR REPLACE INTO categories (id, name, description) VALUES
-        (1, 'Electronics', 'Electronic devices and gadgets'),
-        (2, 'Computers', 'Computer hardware and accessories'),
-        (3, 'Audio', 'Audio equipment and accessories'),
-        (4, 'Mobile', 'Mobile phones and accessories')
-    "#;
+// Rows per INSERT statement
+const INSERT_BATCH_ROWS: usize = 200;
+
+// Most rows one seeding inserts
+const MAX_SEED_ROWS: usize = 1_000_000;
 
-    if let Err(e) = pool.execute_query(insert_users).await {
-        eprintln!("Error inserting users: {}", e);
-        return Err(e);
+// Existing values a foreign key column picks from
+const REFERENCE_SAMPLE: usize = 1000;
+
+const FIRST_NAMES: [&str; 24] = [
+    "James",
+    "Mary",
+    "Robert",
+    "Patricia",
+    "John",
+    "Jennifer",
+    "Michael",
+    "Linda",
+    "David",
+    "Elizabeth",
+    "William",
+    "Barbara",
+    "Richard",
+    "Susan",
+    "Joseph",
+    "Jessica",
+    "Thomas",
+    "Sarah",
+    "Daniel",
+    "Karen",
+    "Matthew",
+    "Nancy",
+    "Anthony",
+    "Emma",
+];
+
+const LAST_NAMES: [&str; 24] = [
+    "Smith",
+    "Johnson",
+    "Williams",
+    "Brown",
+    "Jones",
+    "Garcia",
+    "Miller",
+    "Davis",
+    "Rodriguez",
+    "Martinez",
+    "Hernandez",
+    "Lopez",
+    "Wilson",
+    "Anderson",
+    "Thomas",
+    "Taylor",
+    "Moore",
+    "Jackson",
+    "Martin",
+    "Lee",
+    "Perez",
+    "Thompson",
+    "White",
+    "Harris",
+];
+
+const CITIES: [&str; 16] = [
+    "Springfield",
+    "Riverside",
+    "Franklin",
+    "Greenville",
+    "Bristol",
+    "Clinton",
+    "Fairview",
+    "Salem",
+    "Madison",
+    "Georgetown",
+    "Arlington",
+    "Ashland",
+    "Dover",
+    "Oxford",
+    "Jackson",
+    "Milton",
+];
+
+const ADJECTIVES: [&str; 20] = [
+    "quick", "silent", "bright", "ancient", "gentle", "brave", "calm", "eager", "fancy", "giant",
+    "happy", "jolly", "kind", "lively", "modern", "noble", "proud", "rapid", "smart", "tidy",
+];
+
+const NOUNS: [&str; 20] = [
+    "river", "mountain", "garden", "window", "engine", "planet", "forest", "bridge", "castle",
+    "market", "harbor", "island", "meadow", "rocket", "tower", "valley", "canyon", "desert",
+    "lantern", "orchard",
+];
+
+// Reserved for documentation, so generated addresses never reach anyone
+const EMAIL_DOMAINS: [&str; 3] = ["example.com", "example.org", "example.net"];
+
+/// How a column's values are made up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Generator {
+    Default, // Not inserted: the database's default or auto-increment
+    Null,
+    FirstName,
+    LastName,
+    FullName,
+    Email,
+    Phone,
+    City,
+    Word,
+    Sentence,
+    Integer,
+    Decimal,
+    Boolean,
+    Date,
+    Timestamp,
+    Uuid,
+    Reference, // An existing value of the referenced table
+}
+
+impl Generator {
+    const ALL: [Generator; 17] = [
+        Generator::Default,
+        Generator::Null,
+        Generator::FirstName,
+        Generator::LastName,
+        Generator::FullName,
+        Generator::Email,
+        Generator::Phone,
+        Generator::City,
+        Generator::Word,
+        Generator::Sentence,
+        Generator::Integer,
+        Generator::Decimal,
+        Generator::Boolean,
+        Generator::Date,
+        Generator::Timestamp,
+        Generator::Uuid,
+        Generator::Reference,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Generator::Default => "default",
+            Generator::Null => "NULL",
+            Generator::FirstName => "first name",
+            Generator::LastName => "last name",
+            Generator::FullName => "full name",
+            Generator::Email => "email",
+            Generator::Phone => "phone",
+            Generator::City => "city",
+            Generator::Word => "words",
+            Generator::Sentence => "sentence",
+            Generator::Integer => "integer",
+            Generator::Decimal => "decimal",
+            Generator::Boolean => "boolean",
+            Generator::Date => "date",
+            Generator::Timestamp => "timestamp",
+            Generator::Uuid => "UUID",
+            Generator::Reference => "reference",
+        }
+    }
+}
+
+/// A column of the table being seeded and its generator.
+#[derive(Debug, Clone)]
+pub struct SeedColumn {
+    pub name: String,
+    pub data_type: String,
+    pub generator: Generator,
+    pub reference: Option<(String, Option<String>)>, // Referenced table and column
+    max_length: Option<usize>,                       // Of a varchar/char column
+}
+
+impl SeedColumn {
+    pub fn new(column: &ColumnInfo, reference: Option<(String, Option<String>)>) -> Self {
+        let generator = if reference.is_some() {
+            Generator::Reference
+        } else {
+            guess_generator(column)
+        };
+        let max_length = column
+            .data_type
+            .split_once('(')
+            .and_then(|(_, rest)| rest.split([')', ',']).next())
+            .and_then(|n| n.trim().parse().ok())
+            .filter(|_| column.data_type.to_lowercase().contains("char"));
+        Self {
+            name: column.name.clone(),
+            data_type: column.data_type.clone(),
+            generator,
+            reference,
+            max_length,
+        }
+    }
+
+    /// Moves to the next or previous generator; Reference only for foreign
+    /// key columns.
+    pub fn cycle_generator(&mut self, delta: isize) {
+        let available: Vec<Generator> = Generator::ALL
+            .into_iter()
+            .filter(|g| *g != Generator::Reference || self.reference.is_some())
+            .collect();
+        let current = available
+            .iter()
+            .position(|g| *g == self.generator)
+            .unwrap_or(0);
+        let next = (current as isize + delta).rem_euclid(available.len() as isize);
+        self.generator = available[next as usize];
     }
-    if let Err(e) = pool.execute_query(insert_orders).await {
-        eprintln!("Error inserting orders: {}", e);
-        return Err(e);
+}
+
+/// The seeding popup: how many rows, and each column's generator.
+#[derive(Debug, Clone)]
+pub struct SeedPlan {
+    pub table: TableInfo,
+    pub columns: Vec<SeedColumn>,
+    pub rows: TextInput, // Digits only
+    pub selected: usize, // Index into `columns`
+}
+
+impl SeedPlan {
+    pub fn new(table: TableInfo, columns: Vec<SeedColumn>) -> Self {
+        Self {
+            table,
+            columns,
+            rows: TextInput::new("100"),
+            selected: 0,
+        }
     }
-    if let Err(e) = pool.execute_query(insert_categories).await {
-        eprintln!("Error inserting categories: {}", e);
-        return Err(e);
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.columns.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
     }
 
-    println!("Demo database created successfully with sample data!");
-    Ok(())
+    pub fn row_count(&self) -> Result<usize> {
+        match self.rows.text.parse::<usize>() {
+            Ok(rows) if (1..=MAX_SEED_ROWS).contains(&rows) => Ok(rows),
+            _ => Err(anyhow!("Rows must be between 1 and {}", MAX_SEED_ROWS)),
+        }
+    }
+}
+
+/// A seeding running in a spawned task.
+#[derive(Debug)]
+pub struct SeedTask {
+    pub table: String,
+    pub total: usize,
+    pub progress: watch::Receiver<usize>, // Rows inserted so far
+    pub handle: JoinHandle<Result<usize>>,
+}
+
+/// A generator from the column's name, then its type.
+fn guess_generator(column: &ColumnInfo) -> Generator {
+    let name = column.name.to_lowercase();
+    let data_type = column.data_type.to_lowercase();
+    let is_integer = data_type.contains("int") || data_type.contains("serial");
+    let is_text = data_type.contains("char") || data_type.contains("text") || data_type.is_empty();
+
+    // Integer keys usually fill themselves in
+    if column.is_primary_key && is_integer {
+        return Generator::Default;
+    }
+    if data_type == "uuid" || (is_text && (name == "uuid" || name.ends_with("_uuid"))) {
+        return Generator::Uuid;
+    }
+    if data_type.starts_with("bool") || data_type == "tinyint(1)" {
+        return Generator::Boolean;
+    }
+    if data_type.starts_with("timestamp") || data_type.starts_with("datetime") {
+        return Generator::Timestamp;
+    }
+    if data_type == "date" {
+        return Generator::Date;
+    }
+    if is_text {
+        let has = |words: &[&str]| words.iter().any(|w| name.contains(w));
+        return if has(&["email", "mail"]) {
+            Generator::Email
+        } else if has(&["first"]) {
+            Generator::FirstName
+        } else if has(&["last", "surname"]) {
+            Generator::LastName
+        } else if has(&["phone", "mobile", "tel"]) {
+            Generator::Phone
+        } else if has(&["city", "town"]) {
+            Generator::City
+        } else if name == "name" || has(&["full", "author", "owner", "customer", "contact"]) {
+            Generator::FullName
+        } else if has(&[
+            "desc", "comment", "note", "bio", "body", "message", "summary",
+        ]) {
+            Generator::Sentence
+        } else {
+            Generator::Word
+        };
+    }
+    if is_integer {
+        return Generator::Integer;
+    }
+    if ["numeric", "decimal", "real", "float", "double", "money"]
+        .iter()
+        .any(|t| data_type.contains(t))
+    {
+        return Generator::Decimal;
+    }
+    Generator::Word
+}
+
+fn pick<'a>(values: &[&'a str]) -> &'a str {
+    values[fastrand::usize(..values.len())]
+}
+
+/// One value for `column`, or `None` for NULL; `row` keeps generated emails apart.
+fn generate_value(column: &SeedColumn, row: usize, references: &[String]) -> Option<String> {
+    let name = column.name.to_lowercase();
+    let value = match column.generator {
+        Generator::Default | Generator::Null => return None,
+        Generator::FirstName => pick(&FIRST_NAMES).to_string(),
+        Generator::LastName => pick(&LAST_NAMES).to_string(),
+        Generator::FullName => format!("{} {}", pick(&FIRST_NAMES), pick(&LAST_NAMES)),
+        Generator::Email => format!(
+            "{}.{}{}@{}",
+            pick(&FIRST_NAMES).to_lowercase(),
+            pick(&LAST_NAMES).to_lowercase(),
+            row,
+            pick(&EMAIL_DOMAINS)
+        ),
+        // 555-01xx numbers are set aside for fiction
+        Generator::Phone => format!(
+            "({}) 555-01{:02}",
+            fastrand::u32(200..1000),
+            fastrand::u32(..100)
+        ),
+        Generator::City => pick(&CITIES).to_string(),
+        // Cut down to a few letters words would mostly repeat, so short
+        // columns get codes
+        Generator::Word => match column.max_length {
+            Some(max) if max < 10 => (0..max)
+                .map(|_| fastrand::alphanumeric().to_ascii_uppercase())
+                .collect(),
+            _ => format!("{} {}", pick(&ADJECTIVES), pick(&NOUNS)),
+        },
+        Generator::Sentence => {
+            let mut sentence = format!("The {} {}", pick(&ADJECTIVES), pick(&NOUNS));
+            for _ in 0..fastrand::usize(1..4) {
+                sentence.push_str(&format!(" and the {} {}", pick(&ADJECTIVES), pick(&NOUNS)));
+            }
+            sentence.push('.');
+            sentence
+        }
+        Generator::Integer => {
+            let range = if name.contains("age") {
+                18..=90
+            } else if name.contains("year") {
+                1970..=2025
+            } else if ["quantity", "qty", "count"]
+                .iter()
+                .any(|w| name.contains(w))
+            {
+                1..=20
+            } else if column.data_type.to_lowercase().contains("tinyint") {
+                0..=100
+            } else {
+                1..=10000
+            };
+            fastrand::i64(range).to_string()
+        }
+        Generator::Decimal => format!("{}.{:02}", fastrand::u32(1..1000), fastrand::u32(..100)),
+        Generator::Boolean => if fastrand::bool() { "1" } else { "0" }.to_string(),
+        Generator::Date => random_timestamp().format("%Y-%m-%d").to_string(),
+        Generator::Timestamp => random_timestamp().format("%Y-%m-%d %H:%M:%S").to_string(),
+        Generator::Uuid => uuid::Uuid::new_v4().to_string(),
+        Generator::Reference => references[fastrand::usize(..references.len())].clone(),
+    };
+    Some(match column.max_length {
+        Some(max) => value.chars().take(max).collect(),
+        None => value,
+    })
+}
+
+/// A moment in the last ten years.
+fn random_timestamp() -> chrono::NaiveDateTime {
+    let now = chrono::Local::now().naive_local();
+    now - chrono::Duration::seconds(fastrand::i64(0..10 * 365 * 24 * 60 * 60))
+}
+
+/// Up to REFERENCE_SAMPLE existing values of the referenced column, the
+/// primary key when the foreign key doesn't name one (SQLite).
+async fn reference_values(
+    pool: &DatabasePool,
+    table: &str,
+    column: &Option<String>,
+) -> Result<Vec<String>> {
+    let database_type = pool.database_type();
+    let column = match column {
+        Some(column) => column.clone(),
+        None => {
+            let (schema, name) = match table.split_once('.') {
+                Some((schema, name)) => (Some(schema), name),
+                None => (None, table),
+            };
+            pool.get_table_columns(name, schema)
+                .await?
+                .into_iter()
+                .find(|c| c.is_primary_key)
+                .map(|c| c.name)
+                .ok_or_else(|| anyhow!("{} has no primary key to reference", table))?
+        }
+    };
+    // As text, which every key type (uuid, numeric, ...) reads back as
+    let dialect = Dialect::of(&database_type);
+    let quoted = dialect.quote_identifier(&column);
+    let query = format!(
+        "SELECT DISTINCT {} FROM {} WHERE {} IS NOT NULL LIMIT {}",
+        dialect.cast_to_text(&quoted),
+        dialect.quote_table(table),
+        quoted,
+        REFERENCE_SAMPLE
+    );
+    let values: Vec<String> = pool
+        .execute_query(&query)
+        .await?
+        .rows
+        .into_iter()
+        .filter_map(|row| row.into_iter().next())
+        .collect();
+    if values.is_empty() {
+        return Err(anyhow!("{} has no rows for {} to reference", table, column));
+    }
+    Ok(values)
+}
+
+/// Declared lengths of the table's character columns. PostgreSQL's column
+/// types don't carry them; SQLite doesn't enforce them.
+async fn character_lengths(pool: &DatabasePool, table: &TableInfo) -> Result<Vec<(String, usize)>> {
+    let dialect = Dialect::of(&pool.database_type());
+    let schema = match (pool, &table.schema) {
+        (DatabasePool::SQLite(_), _) => return Ok(Vec::new()),
+        (DatabasePool::PostgreSQL(_), schema) => {
+            dialect.string_literal(schema.as_deref().unwrap_or("public"))
+        }
+        (DatabasePool::MySQL(_), _) => "DATABASE()".to_string(),
+    };
+    let query = format!(
+        "SELECT column_name, character_maximum_length FROM information_schema.columns
+         WHERE table_schema = {} AND table_name = {} AND character_maximum_length IS NOT NULL",
+        schema,
+        dialect.string_literal(&table.name)
+    );
+    Ok(pool
+        .execute_query(&query)
+        .await?
+        .rows
+        .into_iter()
+        .filter_map(|row| Some((row.first()?.clone(), row.get(1)?.parse().ok()?)))
+        .collect())
+}
+
+/// Inserts `rows` generated rows into `table`, INSERT_BATCH_ROWS at a time.
+pub async fn seed_table(
+    pool: DatabasePool,
+    table: TableInfo,
+    columns: Vec<SeedColumn>,
+    rows: usize,
+    progress: watch::Sender<usize>,
+) -> Result<usize> {
+    let database_type = pool.database_type();
+    let lengths = character_lengths(&pool, &table).await?;
+    let columns: Vec<SeedColumn> = columns
+        .into_iter()
+        .filter(|c| c.generator != Generator::Default)
+        .map(|mut column| {
+            if let Some((_, length)) = lengths.iter().find(|(name, _)| *name == column.name) {
+                column.max_length = Some(*length);
+            }
+            column
+        })
+        .collect();
+    if columns.is_empty() {
+        return Err(anyhow!("Every column is left to its default"));
+    }
+    let mut references = Vec::new();
+    for column in &columns {
+        references.push(match (&column.generator, &column.reference) {
+            (Generator::Reference, Some((table, referenced))) => {
+                reference_values(&pool, table, referenced).await?
+            }
+            _ => Vec::new(),
+        });
+    }
+
+    let names: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+    // Emails carry a number from a random start, so seeding twice rarely repeats one
+    let first_row = fastrand::usize(..1_000_000);
+    let mut inserted = 0;
+    while inserted < rows {
+        let batch: Vec<Vec<Option<String>>> = (inserted..rows.min(inserted + INSERT_BATCH_ROWS))
+            .map(|row| {
+                columns
+                    .iter()
+                    .zip(&references)
+                    .map(|(column, references)| generate_value(column, first_row + row, references))
+                    .collect()
+            })
+            .collect();
+        let insert =
+            dump::insert_statement(&database_type, &table.qualified_name(), &names, &[], &batch);
+        pool.execute_statement(&insert).await?;
+        inserted += batch.len();
+        progress.send_replace(inserted);
+    }
+    Ok(inserted)
 }
@@ -0,0 +1,146 @@
+// SQL syntax that differs between the supported databases: identifier quoting,
+// LIMIT, TRUNCATE and parameter placeholders. Generated statements go through
+// the active connection's dialect so they run as they are on every database.
+// Queries typed by the user are parsed with the matching sqlparser grammar.
+
+use crate::database::DatabaseType;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dialect {
+    SQLite,
+    PostgreSQL,
+    MySQL,
+}
+
+impl Dialect {
+    pub fn of(database_type: &DatabaseType) -> Self {
+        match database_type {
+            DatabaseType::SQLite => Dialect::SQLite,
+            DatabaseType::PostgreSQL => Dialect::PostgreSQL,
+            DatabaseType::MySQL => Dialect::MySQL,
+        }
+    }
+
+    /// Quotes an identifier: backticks on MySQL, double quotes elsewhere.
+    pub fn quote_identifier(&self, name: &str) -> String {
+        match self {
+            Dialect::MySQL => format!("`{}`", name.replace('`', "``")),
+            Dialect::SQLite | Dialect::PostgreSQL => {
+                format!("\"{}\"", name.replace('"', "\"\""))
+            }
+        }
+    }
+
+    /// Quotes a table name as `TableInfo::qualified_name` gives it; PostgreSQL
+    /// names and SQLite names in attached databases carry a schema.
+    pub fn quote_table(&self, name: &str) -> String {
+        match (self, name.split_once('.')) {
+            (Dialect::PostgreSQL | Dialect::SQLite, Some((schema, table))) => format!(
+                "{}.{}",
+                self.quote_identifier(schema),
+                self.quote_identifier(table)
+            ),
+            _ => self.quote_identifier(name),
+        }
+    }
+
+    /// A quoted string; MySQL also reads backslashes as escapes.
+    pub fn string_literal(&self, value: &str) -> String {
+        let value = value.replace('\'', "''");
+        match self {
+            Dialect::MySQL => format!("'{}'", value.replace('\\', "\\\\")),
+            Dialect::SQLite | Dialect::PostgreSQL => format!("'{}'", value),
+        }
+    }
+
+    /// Bytes as a binary literal: `X'0102'`, or a hex `bytea` string on
+    /// PostgreSQL.
+    pub fn blob_literal(&self, bytes: &[u8]) -> String {
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        match self {
+            Dialect::PostgreSQL => format!("'\\x{}'::bytea", hex),
+            Dialect::SQLite | Dialect::MySQL => format!("X'{}'", hex),
+        }
+    }
+
+    /// Clause limiting a SELECT to `limit` rows, with a leading space.
+    pub fn limit_clause(&self, limit: usize) -> String {
+        // All three agree on LIMIT; kept here so a dialect without it has one place to change
+        format!(" LIMIT {}", limit)
+    }
+
+    pub fn supports_truncate(&self) -> bool {
+        !matches!(self, Dialect::SQLite)
+    }
+
+    /// Statement removing every row of a table; `DELETE` where there is no
+    /// `TRUNCATE`.
+    pub fn truncate_statement(&self, table: &str) -> String {
+        if self.supports_truncate() {
+            format!("TRUNCATE TABLE {};", self.quote_table(table))
+        } else {
+            format!("DELETE FROM {};", self.quote_table(table))
+        }
+    }
+
+    /// `CREATE [UNIQUE] INDEX` on `columns` of `table` (a qualified name).
+    pub fn create_index_statement(
+        &self,
+        name: &str,
+        table: &str,
+        columns: &[String],
+        unique: bool,
+    ) -> String {
+        let columns: Vec<String> = columns.iter().map(|c| self.quote_identifier(c)).collect();
+        format!(
+            "CREATE {}INDEX {} ON {} ({});",
+            if unique { "UNIQUE " } else { "" },
+            self.quote_identifier(name),
+            self.quote_table(table),
+            columns.join(", ")
+        )
+    }
+
+    /// Statement dropping index `name` of `table`: MySQL names the table, and
+    /// a PostgreSQL index lives in its table's schema.
+    pub fn drop_index_statement(&self, name: &str, table: &str) -> String {
+        match (self, table.split_once('.')) {
+            (Dialect::MySQL, _) => format!(
+                "DROP INDEX {} ON {};",
+                self.quote_identifier(name),
+                self.quote_table(table)
+            ),
+            (Dialect::PostgreSQL, Some((schema, _))) => format!(
+                "DROP INDEX {}.{};",
+                self.quote_identifier(schema),
+                self.quote_identifier(name)
+            ),
+            _ => format!("DROP INDEX {};", self.quote_identifier(name)),
+        }
+    }
+
+    /// Casts `expr` to the database's text type.
+    pub fn cast_to_text(&self, expr: &str) -> String {
+        match self {
+            Dialect::MySQL => format!("CAST({} AS CHAR)", expr),
+            Dialect::SQLite | Dialect::PostgreSQL => format!("CAST({} AS TEXT)", expr),
+        }
+    }
+
+    /// Grammar for parsing the database's SQL.
+    pub fn parser(&self) -> Box<dyn sqlparser::dialect::Dialect> {
+        match self {
+            Dialect::SQLite => Box::new(sqlparser::dialect::SQLiteDialect {}),
+            Dialect::PostgreSQL => Box::new(sqlparser::dialect::PostgreSqlDialect {}),
+            Dialect::MySQL => Box::new(sqlparser::dialect::MySqlDialect {}),
+        }
+    }
+
+    /// Placeholder for the `index`th (1-based) bound parameter.
+    pub fn placeholder(&self, index: usize) -> String {
+        match self {
+            Dialect::PostgreSQL => format!("${}", index),
+            Dialect::SQLite | Dialect::MySQL => "?".to_string(),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::database::QueryResult;
+use std::collections::HashMap;
+
+/// A single row-level difference between two result sets.
+#[derive(Debug, Clone)]
+pub enum RowChange {
+    Added(Vec<String>),
+    Removed(Vec<String>),
+    Changed {
+        before: Vec<String>,
+        after: Vec<String>,
+    },
+}
+
+/// Row-level differences between a baseline result and a newer one.
+#[derive(Debug, Clone)]
+pub struct ResultDiff {
+    pub columns: Vec<String>,
+    pub changes: Vec<RowChange>,
+    pub keyed: bool, // Whether rows were matched by their first column
+}
+
+impl ResultDiff {
+    pub fn counts(&self) -> (usize, usize, usize) {
+        let mut counts = (0, 0, 0);
+        for change in &self.changes {
+            match change {
+                RowChange::Added(_) => counts.0 += 1,
+                RowChange::Removed(_) => counts.1 += 1,
+                RowChange::Changed { .. } => counts.2 += 1,
+            }
+        }
+        counts
+    }
+
+    /// Flattens the diff into rows prefixed with a `change` column, for export.
+    /// Changed rows produce a `changed_from` row followed by a `changed_to` row.
+    pub fn export_rows(&self) -> (Vec<String>, Vec<Vec<String>>) {
+        let mut columns = vec!["change".to_string()];
+        columns.extend(self.columns.iter().cloned());
+
+        let prefixed = |label: &str, row: &[String]| {
+            let mut out = vec![label.to_string()];
+            out.extend(row.iter().cloned());
+            out
+        };
+
+        let mut rows = Vec::new();
+        for change in &self.changes {
+            match change {
+                RowChange::Added(row) => rows.push(prefixed("added", row)),
+                RowChange::Removed(row) => rows.push(prefixed("removed", row)),
+                RowChange::Changed { before, after } => {
+                    rows.push(prefixed("changed_from", before));
+                    rows.push(prefixed("changed_to", after));
+                }
+            }
+        }
+        (columns, rows)
+    }
+}
+
+/// Returns the first column's values if they uniquely identify every row.
+fn unique_keys(rows: &[Vec<String>]) -> Option<HashMap<&str, &Vec<String>>> {
+    let mut keys = HashMap::new();
+    for row in rows {
+        let key = row.first()?.as_str();
+        if keys.insert(key, row).is_some() {
+            return None;
+        }
+    }
+    Some(keys)
+}
+
+/// Computes the difference from `baseline` to `current`, which must have the
+/// same columns.
+///
+/// When the first column is unique in both results, rows are matched by it so
+/// edits show up as changed rows. Otherwise rows are compared as whole values
+/// and only additions and removals are reported.
+pub fn diff_results(baseline: &QueryResult, current: &QueryResult) -> ResultDiff {
+    if let (Some(old_keys), Some(new_keys)) =
+        (unique_keys(&baseline.rows), unique_keys(&current.rows))
+    {
+        let mut changes = Vec::new();
+        for row in &current.rows {
+            match old_keys.get(row[0].as_str()) {
+                None => changes.push(RowChange::Added(row.clone())),
+                Some(old_row) if *old_row != row => changes.push(RowChange::Changed {
+                    before: (*old_row).clone(),
+                    after: row.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for row in &baseline.rows {
+            if !new_keys.contains_key(row[0].as_str()) {
+                changes.push(RowChange::Removed(row.clone()));
+            }
+        }
+        return ResultDiff {
+            columns: current.columns.clone(),
+            changes,
+            keyed: true,
+        };
+    }
+
+    // Multiset comparison of whole rows
+    let mut unmatched: HashMap<&Vec<String>, usize> = HashMap::new();
+    for row in &baseline.rows {
+        *unmatched.entry(row).or_default() += 1;
+    }
+
+    let mut changes = Vec::new();
+    for row in &current.rows {
+        match unmatched.get_mut(row) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => changes.push(RowChange::Added(row.clone())),
+        }
+    }
+    for row in &baseline.rows {
+        if let Some(count) = unmatched.get_mut(row)
+            && *count > 0
+        {
+            *count -= 1;
+            changes.push(RowChange::Removed(row.clone()));
+        }
+    }
+
+    ResultDiff {
+        columns: current.columns.clone(),
+        changes,
+        keyed: false,
+    }
+}
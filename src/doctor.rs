@@ -0,0 +1,288 @@
+use crate::database::{ConnectionConfig, DatabasePool};
+use crate::interpolate;
+use crate::settings::{AppSettings, config_dir};
+use crate::theme::{CUSTOM_THEME, Theme};
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// Per-connection budget for the reachability check
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Ok => "[ OK ]",
+            Status::Warn => "[WARN]",
+            Status::Fail => "[FAIL]",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Report {
+    warnings: usize,
+    failures: usize,
+}
+
+impl Report {
+    fn line(&mut self, status: Status, message: impl AsRef<str>) {
+        match status {
+            Status::Ok => {}
+            Status::Warn => self.warnings += 1,
+            Status::Fail => self.failures += 1,
+        }
+        println!("{} {}", status.label(), message.as_ref());
+    }
+
+    fn detail(&self, message: impl AsRef<str>) {
+        println!("       {}", message.as_ref());
+    }
+}
+
+/// Runs `rata-db doctor`: validates the local configuration, checks every
+/// saved connection and prints a report. Returns whether no check failed.
+pub async fn run() -> Result<bool> {
+    let mut report = Report::default();
+    println!(
+        "{} doctor (v{})\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+
+    println!("Configuration");
+    let dir = match config_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            report.line(Status::Fail, format!("Config directory: {}", e));
+            return Ok(finish(&report));
+        }
+    };
+    check_config_dir(&mut report, &dir);
+    check_settings(&mut report, &dir);
+    check_theme(&mut report, &dir);
+    let connections = check_connections_file(&mut report, &dir);
+
+    println!("\nCredentials");
+    check_keyring(&mut report, &connections);
+
+    println!("\nConnections");
+    check_ssl_files(&mut report, &connections);
+    check_reachability(&mut report, &connections).await;
+
+    Ok(finish(&report))
+}
+
+fn finish(report: &Report) -> bool {
+    println!(
+        "\n{} failure(s), {} warning(s)",
+        report.failures, report.warnings
+    );
+    report.failures == 0
+}
+
+fn check_config_dir(report: &mut Report, dir: &Path) {
+    // Write and remove a probe file; `metadata().permissions()` alone doesn't
+    // account for ownership or read-only mounts
+    let probe = dir.join(".doctor-probe");
+    match fs::write(&probe, b"ok").and_then(|_| fs::remove_file(&probe)) {
+        Ok(()) => report.line(
+            Status::Ok,
+            format!("Config directory is writable: {}", dir.display()),
+        ),
+        Err(e) => report.line(
+            Status::Fail,
+            format!(
+                "Config directory is not writable: {} ({})",
+                dir.display(),
+                e
+            ),
+        ),
+    }
+}
+
+fn check_settings(report: &mut Report, dir: &Path) {
+    let path = dir.join("settings.json");
+    if !path.exists() {
+        report.line(Status::Ok, "settings.json not present, defaults are used");
+        return;
+    }
+    match fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(serde_json::from_str::<AppSettings>(&content)?))
+    {
+        Ok(_) => report.line(Status::Ok, "settings.json parsed"),
+        Err(e) => report.line(
+            Status::Fail,
+            format!("settings.json is invalid, defaults will be used: {}", e),
+        ),
+    }
+}
+
+fn check_theme(report: &mut Report, dir: &Path) {
+    if !dir.join("theme.json").exists() {
+        return;
+    }
+    match Theme::load(CUSTOM_THEME) {
+        Ok(_) => report.line(Status::Ok, "theme.json parsed"),
+        Err(e) => report.line(
+            Status::Warn,
+            format!("theme.json is invalid, the custom theme is unusable: {}", e),
+        ),
+    }
+}
+
+fn check_connections_file(report: &mut Report, dir: &Path) -> Vec<ConnectionConfig> {
+    let path = dir.join("connections.json");
+    if !path.exists() {
+        report.line(
+            Status::Ok,
+            "connections.json not present, no saved connections",
+        );
+        return Vec::new();
+    }
+    let parsed = fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(serde_json::from_str::<Vec<ConnectionConfig>>(&content)?));
+    match parsed {
+        Ok(connections) => {
+            report.line(
+                Status::Ok,
+                format!(
+                    "connections.json parsed ({} connections)",
+                    connections.len()
+                ),
+            );
+            connections
+        }
+        Err(e) => {
+            report.line(Status::Fail, format!("connections.json is invalid: {}", e));
+            Vec::new()
+        }
+    }
+}
+
+fn check_keyring(report: &mut Report, connections: &[ConnectionConfig]) {
+    // There is no system keyring integration: credentials live inside the
+    // connection strings, so the useful check is which of them embed a password
+    let with_password: Vec<&str> = connections
+        .iter()
+        .filter(|c| has_password(&c.connection_string))
+        .map(|c| c.name.as_str())
+        .collect();
+
+    if with_password.is_empty() {
+        report.line(
+            Status::Ok,
+            "Keyring not used; no saved connection stores a password",
+        );
+    } else {
+        report.line(
+            Status::Warn,
+            format!(
+                "Keyring not used; {} connection(s) store a plaintext password in connections.json",
+                with_password.len()
+            ),
+        );
+        for name in with_password {
+            report.detail(format!("- {}", name));
+        }
+    }
+}
+
+/// Whether a connection URL carries a `user:password@` section whose password
+/// isn't a `${NAME}` placeholder.
+fn has_password(connection_string: &str) -> bool {
+    let Some((_, rest)) = connection_string.split_once("://") else {
+        return false;
+    };
+    let authority = rest.split(['/', '?']).next().unwrap_or("");
+    authority.rsplit_once('@').is_some_and(|(userinfo, _)| {
+        userinfo
+            .split_once(':')
+            .is_some_and(|(_, p)| !p.is_empty() && !interpolate::is_placeholder(p))
+    })
+}
+
+fn check_ssl_files(report: &mut Report, connections: &[ConnectionConfig]) {
+    for connection in connections {
+        let Some(ssl) = &connection.ssl_config else {
+            continue;
+        };
+        for (label, file) in [
+            ("certificate", &ssl.cert_file),
+            ("key", &ssl.key_file),
+            ("CA certificate", &ssl.ca_file),
+        ] {
+            if let Some(file) = file
+                && !file.is_empty()
+                && !Path::new(file).is_file()
+            {
+                report.line(
+                    Status::Fail,
+                    format!("{}: SSL {} not found: {}", connection.name, label, file),
+                );
+            }
+        }
+    }
+}
+
+async fn check_reachability(report: &mut Report, connections: &[ConnectionConfig]) {
+    if connections.is_empty() {
+        report.line(Status::Ok, "No saved connections to test");
+        return;
+    }
+
+    // Test all connections at once so one unreachable host doesn't hold up the rest
+    let handles: Vec<_> = connections
+        .iter()
+        .cloned()
+        .map(|config| tokio::spawn(async move { probe(&config).await }))
+        .collect();
+
+    for (connection, handle) in connections.iter().zip(handles) {
+        match handle.await {
+            Ok(Ok(elapsed)) => report.line(
+                Status::Ok,
+                format!(
+                    "{} ({:?}) reachable in {} ms",
+                    connection.name,
+                    connection.database_type,
+                    elapsed.as_millis()
+                ),
+            ),
+            Ok(Err(e)) => report.line(
+                Status::Fail,
+                format!(
+                    "{} ({:?}) unreachable: {}",
+                    connection.name, connection.database_type, e
+                ),
+            ),
+            Err(e) => report.line(
+                Status::Fail,
+                format!("{}: check crashed: {}", connection.name, e),
+            ),
+        }
+    }
+}
+
+/// Connects and runs a trivial query, returning the round-trip time.
+async fn probe(config: &ConnectionConfig) -> Result<Duration> {
+    let start = Instant::now();
+    tokio::time::timeout(CONNECT_TIMEOUT, async {
+        let pool = DatabasePool::connect(config).await?;
+        pool.execute_query("SELECT 1").await?;
+        Ok::<_, anyhow::Error>(())
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("timed out after {}s", CONNECT_TIMEOUT.as_secs()))??;
+    Ok(start.elapsed())
+}
@@ -0,0 +1,53 @@
+// Crash-safe drafts of the query editor: while connected, the editor's text is
+// written every few seconds to drafts/<connection>.sql in the config directory.
+// The draft is removed on a clean exit and when another connection is opened,
+// so one found when connecting was left behind by a crash and is loaded back.
+
+use crate::settings::config_dir;
+use anyhow::Result;
+use std::fs;
+use std::path::PathBuf;
+
+const DRAFTS_DIR: &str = "drafts";
+
+/// Draft file of a connection; characters that can't be in a file name are
+/// replaced with `_`.
+fn path(connection: &str) -> Result<PathBuf> {
+    let name: String = connection
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    Ok(config_dir()?.join(DRAFTS_DIR).join(format!("{}.sql", name)))
+}
+
+/// The draft left for `connection`, if any.
+pub fn load(connection: &str) -> Option<String> {
+    fs::read_to_string(path(connection).ok()?)
+        .ok()
+        .filter(|text| !text.trim().is_empty())
+}
+
+/// Writes the draft next to its file first and renames it over, so a crash
+/// mid-write can't leave half a draft.
+pub fn save(connection: &str, text: &str) -> Result<()> {
+    let file = path(connection)?;
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let partial = file.with_extension("sql.partial");
+    fs::write(&partial, text)?;
+    fs::rename(partial, file)?;
+    Ok(())
+}
+
+pub fn remove(connection: &str) {
+    if let Ok(file) = path(connection) {
+        let _ = fs::remove_file(file);
+    }
+}
@@ -0,0 +1,331 @@
+// SQL dumps of a database or a single table: CREATE TABLE statements from the
+// introspected columns, then the rows as batched INSERTs read through a cursor so
+// large tables are never held in memory. Foreign keys are added last (inline on
+// SQLite, which can't add constraints later), so tables load in any order.
+
+use crate::database::{
+    ColumnInfo, DatabasePool, DatabaseType, ForeignKey, LOSSY_MARKER, TableInfo, blob_bytes,
+};
+use crate::dialect::Dialect;
+use anyhow::{Result, anyhow};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// Rows per INSERT statement
+const INSERT_BATCH_ROWS: usize = 100;
+
+// Rows read from the cursor at a time
+const FETCH_ROWS: usize = 1000;
+
+/// What a running dump has written so far, shown in the status bar.
+#[derive(Debug, Clone, Default)]
+pub struct DumpProgress {
+    pub table: String, // Table being written
+    pub tables_done: usize,
+    pub table_count: usize,
+    pub rows: usize,
+}
+
+/// A dump running in a spawned task.
+#[derive(Debug)]
+pub struct DumpTask {
+    pub path: PathBuf,
+    pub progress: watch::Receiver<DumpProgress>,
+    pub handle: JoinHandle<Result<DumpProgress>>,
+}
+
+impl DumpProgress {
+    pub fn describe(&self) -> String {
+        format!(
+            "Dumping {} ({}/{} tables, {} rows written)",
+            self.table,
+            self.tables_done + 1,
+            self.table_count,
+            self.rows
+        )
+    }
+}
+
+fn column_list(database_type: &DatabaseType, columns: &[String]) -> String {
+    let dialect = Dialect::of(database_type);
+    columns
+        .iter()
+        .map(|c| dialect.quote_identifier(c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// PostgreSQL columns are read as text so every type keeps its exact literal
+/// form instead of going through the driver's typed decoding.
+pub fn select_list(database_type: &DatabaseType, columns: &[ColumnInfo]) -> String {
+    let dialect = Dialect::of(database_type);
+    columns
+        .iter()
+        .map(|col| {
+            let name = dialect.quote_identifier(&col.name);
+            match database_type {
+                DatabaseType::PostgreSQL => format!("{}::text AS {}", name, name),
+                _ => name,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// CREATE TABLE with a table-level primary key for composite keys and, when
+/// given, inline foreign keys.
+pub fn create_table_statement(
+    database_type: &DatabaseType,
+    table: &str,
+    columns: &[ColumnInfo],
+    foreign_keys: &[&ForeignKey],
+) -> String {
+    let dialect = Dialect::of(database_type);
+    let primary_key: Vec<String> = columns
+        .iter()
+        .filter(|c| c.is_primary_key)
+        .map(|c| c.name.clone())
+        .collect();
+    let mut definitions: Vec<String> = columns
+        .iter()
+        .map(|col| {
+            let mut def = format!("{} {}", dialect.quote_identifier(&col.name), col.data_type);
+            if !col.is_nullable {
+                def.push_str(" NOT NULL");
+            }
+            if col.is_primary_key && primary_key.len() == 1 {
+                def.push_str(" PRIMARY KEY");
+            }
+            def
+        })
+        .collect();
+    if primary_key.len() > 1 {
+        definitions.push(format!(
+            "PRIMARY KEY ({})",
+            column_list(database_type, &primary_key)
+        ));
+    }
+    definitions.extend(
+        foreign_keys
+            .iter()
+            .map(|key| foreign_key_clause(database_type, key)),
+    );
+
+    format!(
+        "CREATE TABLE {} (\n  {}\n);",
+        dialect.quote_table(table),
+        definitions.join(",\n  ")
+    )
+}
+
+fn foreign_key_clause(database_type: &DatabaseType, key: &ForeignKey) -> String {
+    let mut clause = format!(
+        "FOREIGN KEY ({}) REFERENCES {}",
+        column_list(database_type, &key.columns),
+        Dialect::of(database_type).quote_table(&key.referenced_table)
+    );
+    if !key.referenced_columns.is_empty() {
+        clause.push_str(&format!(
+            " ({})",
+            column_list(database_type, &key.referenced_columns)
+        ));
+    }
+    clause
+}
+
+/// One INSERT for a batch of rows, whose NULL cells are `None`. Values are
+/// written as strings quoted for `database_type` (a table copy's target, not
+/// its source) and left to the database to convert, like the INSERT generator.
+/// Columns marked in `binary` hold bytes as `\x` hex and are written as binary
+/// literals; columns past its end are text.
+pub fn insert_statement(
+    database_type: &DatabaseType,
+    table: &str,
+    columns: &[String],
+    binary: &[bool],
+    rows: &[Vec<Option<String>>],
+) -> String {
+    let dialect = Dialect::of(database_type);
+    let values: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let literals: Vec<String> = row
+                .iter()
+                .enumerate()
+                .map(|(i, v)| match v {
+                    Some(v) if binary.get(i).copied().unwrap_or(false) => blob_bytes(v)
+                        .map(|bytes| dialect.blob_literal(&bytes))
+                        .unwrap_or_else(|| dialect.string_literal(v)),
+                    Some(v) => dialect.string_literal(v),
+                    None => "NULL".to_string(),
+                })
+                .collect();
+            format!("({})", literals.join(", "))
+        })
+        .collect();
+    format!(
+        "INSERT INTO {} ({}) VALUES\n  {};",
+        dialect.quote_table(table),
+        column_list(database_type, columns),
+        values.join(",\n  ")
+    )
+}
+
+/// Whether a column's declared type holds bytes (`bytea`, `BLOB`,
+/// `varbinary(16)`, ...).
+pub fn is_binary_type(data_type: &str) -> bool {
+    let lower = data_type.to_lowercase();
+    let base = lower.split('(').next().unwrap_or_default().trim();
+    matches!(
+        base,
+        "bytea" | "blob" | "tinyblob" | "mediumblob" | "longblob" | "binary" | "varbinary"
+    )
+}
+
+/// Fails on a value MySQL returned as text that wasn't valid UTF-8: it was
+/// decoded lossily and would be written back changed.
+pub fn ensure_lossless(table: &str, rows: &[Vec<Option<String>>]) -> Result<()> {
+    let lossy = rows
+        .iter()
+        .flatten()
+        .flatten()
+        .any(|v| v.starts_with(LOSSY_MARKER));
+    if lossy {
+        return Err(anyhow!(
+            "{} has text that isn't valid UTF-8, which can't be written out unchanged",
+            table
+        ));
+    }
+    Ok(())
+}
+
+/// Dumps every table, or only `only`, to `path`. Runs on its own connection
+/// pool so the rest of the app keeps working meanwhile; progress is published
+/// on `progress` and the final counts returned.
+pub async fn dump(
+    pool: DatabasePool,
+    only: Option<TableInfo>,
+    path: &Path,
+    progress: watch::Sender<DumpProgress>,
+) -> Result<DumpProgress> {
+    let database_type = pool.database_type();
+    let dialect = Dialect::of(&database_type);
+    let mut tables = match only {
+        Some(table) => vec![table],
+        None => pool.get_tables().await?,
+    };
+    // SQLite's own tables (sqlite_sequence, sqlite_stat1) can't be created
+    tables.retain(|t| !t.name.starts_with("sqlite_"));
+    let names: Vec<String> = tables.iter().map(TableInfo::qualified_name).collect();
+    // Only keys between dumped tables, so the script restores on its own
+    let keys: Vec<ForeignKey> = pool
+        .get_foreign_keys()
+        .await?
+        .into_iter()
+        .filter(|k| names.contains(&k.table) && names.contains(&k.referenced_table))
+        .collect();
+
+    let mut out = BufWriter::new(File::create(path)?);
+    let mut state = DumpProgress {
+        table_count: tables.len(),
+        ..DumpProgress::default()
+    };
+    writeln!(
+        out,
+        "-- rata-db dump of {} table(s), {}",
+        tables.len(),
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    )?;
+    match database_type {
+        DatabaseType::SQLite => writeln!(out, "PRAGMA foreign_keys = OFF;\nBEGIN;")?,
+        DatabaseType::PostgreSQL => writeln!(out, "BEGIN;")?,
+        DatabaseType::MySQL => writeln!(out, "SET FOREIGN_KEY_CHECKS = 0;\nSTART TRANSACTION;")?,
+    }
+
+    if matches!(database_type, DatabaseType::PostgreSQL) {
+        let mut schemas: Vec<&str> = tables.iter().filter_map(|t| t.schema.as_deref()).collect();
+        schemas.sort();
+        schemas.dedup();
+        for schema in schemas.into_iter().filter(|s| *s != "public") {
+            writeln!(
+                out,
+                "CREATE SCHEMA IF NOT EXISTS {};",
+                dialect.quote_identifier(schema)
+            )?;
+        }
+    }
+
+    for (table, name) in tables.iter().zip(&names) {
+        state.table = name.clone();
+        progress.send_replace(state.clone());
+
+        let columns = pool
+            .get_table_columns(&table.name, table.schema.as_deref())
+            .await?;
+        let inline_keys: Vec<&ForeignKey> = if matches!(database_type, DatabaseType::SQLite) {
+            keys.iter().filter(|k| &k.table == name).collect()
+        } else {
+            Vec::new()
+        };
+        writeln!(out, "\n-- Table {}", name)?;
+        writeln!(
+            out,
+            "{}",
+            create_table_statement(&database_type, name, &columns, &inline_keys)
+        )?;
+
+        let select = format!(
+            "SELECT {} FROM {}",
+            select_list(&database_type, &columns),
+            dialect.quote_table(name)
+        );
+        let binary: Vec<bool> = columns
+            .iter()
+            .map(|c| is_binary_type(&c.data_type))
+            .collect();
+        let (mut first, mut stream) = pool.stream_query(&select, FETCH_ROWS).await?;
+        let mut rows = first.take_cells();
+        loop {
+            if matches!(database_type, DatabaseType::MySQL) {
+                ensure_lossless(name, &rows)?;
+            }
+            for batch in rows.chunks(INSERT_BATCH_ROWS) {
+                writeln!(
+                    out,
+                    "{}",
+                    insert_statement(&database_type, name, &first.columns, &binary, batch)
+                )?;
+            }
+            state.rows += rows.len();
+            progress.send_replace(state.clone());
+            if stream.exhausted {
+                break;
+            }
+            rows = stream.next_chunk(FETCH_ROWS).await?;
+        }
+        state.tables_done += 1;
+    }
+
+    if !matches!(database_type, DatabaseType::SQLite) && !keys.is_empty() {
+        writeln!(out, "\n-- Foreign keys")?;
+        for key in &keys {
+            writeln!(
+                out,
+                "ALTER TABLE {} ADD {};",
+                dialect.quote_table(&key.table),
+                foreign_key_clause(&database_type, key)
+            )?;
+        }
+    }
+
+    match database_type {
+        DatabaseType::MySQL => writeln!(out, "COMMIT;\nSET FOREIGN_KEY_CHECKS = 1;")?,
+        _ => writeln!(out, "COMMIT;")?,
+    }
+    out.flush()?;
+    state.table.clear();
+    Ok(state)
+}
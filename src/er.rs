@@ -0,0 +1,275 @@
+// Text entity-relationship map built from the database's foreign keys.
+// Tables linked by keys are grouped together, referenced (parent) tables first.
+
+use crate::database::ForeignKey;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Names per line in the list of tables without foreign keys
+const ISOLATED_PER_LINE: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErView {
+    Boxes,     // A box per table with arrows to the tables it references
+    Adjacency, // Outgoing and incoming references listed under each table
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErLineKind {
+    Section,
+    Table, // First line naming a table
+    Detail,
+}
+
+#[derive(Debug, Clone)]
+pub struct ErLine {
+    pub text: String,
+    pub kind: ErLineKind,
+    pub table: Option<String>, // Table the line belongs to
+}
+
+impl ErLine {
+    fn new(text: String, kind: ErLineKind, table: Option<&str>) -> Self {
+        Self {
+            text,
+            kind,
+            table: table.map(str::to_string),
+        }
+    }
+}
+
+/// Tables and the foreign keys between them.
+#[derive(Debug, Clone)]
+pub struct ErMap {
+    pub tables: BTreeSet<String>,
+    pub keys: Vec<ForeignKey>,
+}
+
+impl ErMap {
+    /// Builds the map from the known tables plus every table a key mentions.
+    pub fn new(tables: impl IntoIterator<Item = String>, keys: Vec<ForeignKey>) -> Self {
+        let mut tables: BTreeSet<String> = tables.into_iter().collect();
+        for key in &keys {
+            tables.insert(key.table.clone());
+            tables.insert(key.referenced_table.clone());
+        }
+        Self { tables, keys }
+    }
+
+    fn outgoing<'a>(&'a self, table: &'a str) -> impl Iterator<Item = &'a ForeignKey> + 'a {
+        self.keys.iter().filter(move |k| k.table == table)
+    }
+
+    fn incoming<'a>(&'a self, table: &'a str) -> impl Iterator<Item = &'a ForeignKey> + 'a {
+        self.keys
+            .iter()
+            .filter(move |k| k.referenced_table == table)
+    }
+
+    /// Groups of tables connected by foreign keys (largest first, each ordered
+    /// parents first), and the tables without any key.
+    fn clusters(&self) -> (Vec<Vec<&str>>, Vec<&str>) {
+        let mut group: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut members: Vec<Vec<&str>> = Vec::new();
+        for key in &self.keys {
+            let (a, b) = (key.table.as_str(), key.referenced_table.as_str());
+            match (group.get(a).copied(), group.get(b).copied()) {
+                (None, None) => {
+                    group.insert(a, members.len());
+                    group.insert(b, members.len());
+                    members.push(if a == b { vec![a] } else { vec![a, b] });
+                }
+                (Some(g), None) => {
+                    group.insert(b, g);
+                    members[g].push(b);
+                }
+                (None, Some(g)) => {
+                    group.insert(a, g);
+                    members[g].push(a);
+                }
+                (Some(ga), Some(gb)) if ga != gb => {
+                    // Merge the second group into the first
+                    let moved = std::mem::take(&mut members[gb]);
+                    for table in &moved {
+                        group.insert(table, ga);
+                    }
+                    members[ga].extend(moved);
+                }
+                _ => {}
+            }
+        }
+
+        let mut clusters: Vec<Vec<&str>> = members.into_iter().filter(|m| !m.is_empty()).collect();
+        for cluster in &mut clusters {
+            let mut depths = BTreeMap::new();
+            for table in cluster.iter() {
+                self.depth(table, &mut depths, &mut BTreeSet::new());
+            }
+            cluster.sort_by_key(|table| (depths[table], *table));
+        }
+        clusters.sort_by_key(|c| std::cmp::Reverse(c.len()));
+
+        let isolated = self
+            .tables
+            .iter()
+            .map(String::as_str)
+            .filter(|t| !group.contains_key(t))
+            .collect();
+        (clusters, isolated)
+    }
+
+    /// Length of the longest chain of references starting at `table`, so that
+    /// referenced tables come before the tables pointing at them. Cycles and
+    /// self-references are cut off.
+    fn depth<'a>(
+        &'a self,
+        table: &'a str,
+        depths: &mut BTreeMap<&'a str, usize>,
+        visiting: &mut BTreeSet<&'a str>,
+    ) -> usize {
+        if let Some(depth) = depths.get(table) {
+            return *depth;
+        }
+        if !visiting.insert(table) {
+            return 0;
+        }
+        let depth = self
+            .outgoing(table)
+            .filter(|k| k.referenced_table != table)
+            .map(|k| 1 + self.depth(&k.referenced_table, depths, visiting))
+            .max()
+            .unwrap_or(0);
+        visiting.remove(table);
+        depths.insert(table, depth);
+        depth
+    }
+
+    pub fn lines(&self, view: ErView) -> Vec<ErLine> {
+        let (clusters, isolated) = self.clusters();
+        let mut lines = Vec::new();
+        if clusters.is_empty() {
+            lines.push(ErLine::new(
+                "No foreign keys found".to_string(),
+                ErLineKind::Section,
+                None,
+            ));
+        }
+
+        for (n, cluster) in clusters.iter().enumerate() {
+            lines.push(ErLine::new(
+                format!("── Group {} ({} tables) ──", n + 1, cluster.len()),
+                ErLineKind::Section,
+                None,
+            ));
+            for table in cluster {
+                match view {
+                    ErView::Boxes => self.box_lines(table, &mut lines),
+                    ErView::Adjacency => self.adjacency_lines(table, &mut lines),
+                }
+            }
+            lines.push(ErLine::new(String::new(), ErLineKind::Detail, None));
+        }
+
+        if !isolated.is_empty() {
+            lines.push(ErLine::new(
+                format!("── Tables without foreign keys ({}) ──", isolated.len()),
+                ErLineKind::Section,
+                None,
+            ));
+            for chunk in isolated.chunks(ISOLATED_PER_LINE) {
+                lines.push(ErLine::new(chunk.join(", "), ErLineKind::Detail, None));
+            }
+        }
+        lines
+    }
+
+    fn box_lines(&self, table: &str, lines: &mut Vec<ErLine>) {
+        let outgoing: Vec<(String, String)> = self
+            .outgoing(table)
+            .map(|k| (column_list(&k.columns), target(k)))
+            .collect();
+        let width = outgoing
+            .iter()
+            .map(|(columns, _)| columns.chars().count())
+            .chain(std::iter::once(table.chars().count()))
+            .max()
+            .unwrap_or(0);
+        let pad = |text: &str| format!("{}{}", text, " ".repeat(width - text.chars().count()));
+        let border = "─".repeat(width + 2);
+
+        let line = |text: String, kind| ErLine::new(text, kind, Some(table));
+        lines.push(line(format!("┌{}┐", border), ErLineKind::Table));
+        lines.push(line(format!("│ {} │", pad(table)), ErLineKind::Detail));
+        if !outgoing.is_empty() {
+            lines.push(line(format!("├{}┤", border), ErLineKind::Detail));
+            for (columns, target) in &outgoing {
+                lines.push(line(
+                    format!("│ {} ├──▶ {}", pad(columns), target),
+                    ErLineKind::Detail,
+                ));
+            }
+        }
+        lines.push(line(format!("└{}┘", border), ErLineKind::Detail));
+
+        let referenced_by: Vec<String> = self
+            .incoming(table)
+            .filter(|k| k.table != table)
+            .map(|k| format!("{}.{}", k.table, column_list(&k.columns)))
+            .collect();
+        if !referenced_by.is_empty() {
+            lines.push(line(
+                format!("  ▲ referenced by {}", referenced_by.join(", ")),
+                ErLineKind::Detail,
+            ));
+        }
+    }
+
+    fn adjacency_lines(&self, table: &str, lines: &mut Vec<ErLine>) {
+        let line = |text: String, kind| ErLine::new(text, kind, Some(table));
+        lines.push(line(table.to_string(), ErLineKind::Table));
+        for key in self.outgoing(table) {
+            lines.push(line(
+                format!("  ──▶ {} ({})", key.referenced_table, mapping(key)),
+                ErLineKind::Detail,
+            ));
+        }
+        // Self-references are already listed as outgoing
+        for key in self.incoming(table).filter(|k| k.table != table) {
+            lines.push(line(
+                format!("  ◀── {} ({})", key.table, mapping(key)),
+                ErLineKind::Detail,
+            ));
+        }
+    }
+}
+
+/// `a` for one column, `(a, b)` for several.
+fn column_list(columns: &[String]) -> String {
+    if columns.len() == 1 {
+        columns[0].clone()
+    } else {
+        format!("({})", columns.join(", "))
+    }
+}
+
+/// `a, b → c, d`, from the referencing to the referenced columns.
+fn mapping(key: &ForeignKey) -> String {
+    let referenced = if key.referenced_columns.is_empty() {
+        "primary key".to_string()
+    } else {
+        key.referenced_columns.join(", ")
+    };
+    format!("{} → {}", key.columns.join(", "), referenced)
+}
+
+/// `table.column` the key points at.
+fn target(key: &ForeignKey) -> String {
+    if key.referenced_columns.is_empty() {
+        key.referenced_table.clone()
+    } else {
+        format!(
+            "{}.{}",
+            key.referenced_table,
+            column_list(&key.referenced_columns)
+        )
+    }
+}
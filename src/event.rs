@@ -1,14 +1,241 @@
-use crate::app::{App, AppScreen, ConnectionField};
+use crate::aggregate::Aggregate;
+use crate::altercolumn::ColumnField;
+use crate::app::{
+    App, AppScreen, ConnectionField, ConnectionForm, ConnectionListRow, ConnectionSettingField,
+    ConnectionTemplate, PendingConfirmation, PromptKind, RowStatement, StatisticsView,
+    TableListRow,
+};
+use crate::database::ConnectionColor;
+use crate::datepicker::{DatePickerTarget, PickerFocus};
+use crate::filebrowser::{BrowserFocus, FileBrowserTarget};
+use crate::lint::LintWarning;
+use crate::macros::{self, MacroPrompt};
+use crate::sql::find_destructive_statements;
+use crate::users::UserField;
+use crate::vim::{self, EditorMode};
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+pub async fn handle_key_event(app: &mut App, mut key_event: KeyEvent) -> Result<()> {
+    // Macro keys come first so every other key, popups included, can be recorded
+    if handle_macro_keys(app, key_event) {
+        return Ok(());
+    }
 
-pub async fn handle_key_event(app: &mut App, key_event: KeyEvent) -> Result<()> {
     // Clear messages on any key press when error is showing
     if app.error_message.is_some() {
         app.clear_messages();
         return Ok(());
     }
 
+    // Confirmation dialog captures all input until answered
+    if app.pending_confirmation.is_some() {
+        return handle_confirmation_keys(app, key_event);
+    }
+
+    // Last session's restore offer captures all input until answered
+    if app.session_offer.is_some() {
+        handle_session_offer_keys(app, key_event);
+        return Ok(());
+    }
+
+    // Help popup captures all input until closed; F1 opens it even while typing
+    if app.show_help {
+        handle_help_keys(app, key_event);
+        return Ok(());
+    }
+    if key_event.code == KeyCode::F(1) && !is_popup_open(app) {
+        app.toggle_help();
+        return Ok(());
+    }
+
+    // Text prompt captures all input until submitted or cancelled
+    if app.input_prompt.is_some() {
+        return handle_prompt_keys(app, key_event).await;
+    }
+
+    // Copy wizard captures all input until a connection is chosen or it is cancelled
+    if app.copy_wizard.is_some() {
+        handle_copy_wizard_keys(app, key_event);
+        return Ok(());
+    }
+
+    // Index manager captures all input until closed
+    if app.index_manager.is_some() {
+        handle_index_manager_keys(app, key_event).await;
+        return Ok(());
+    }
+
+    // Comment editor captures all input until closed
+    if app.comment_editor.is_some() {
+        handle_comment_editor_keys(app, key_event).await;
+        return Ok(());
+    }
+
+    // Column editor captures all input until closed
+    if app.column_editor.is_some() {
+        handle_column_editor_keys(app, key_event).await;
+        return Ok(());
+    }
+
+    // Seeding popup captures all input until closed
+    if app.seed_plan.is_some() {
+        handle_seed_plan_keys(app, key_event);
+        return Ok(());
+    }
+
+    // JSON viewer captures all input until closed
+    if app.json_viewer.is_some() {
+        handle_json_viewer_keys(app, key_event);
+        return Ok(());
+    }
+
+    // Assistant captures all input until closed
+    if app.assistant.is_some() {
+        handle_assistant_keys(app, key_event);
+        return Ok(());
+    }
+
+    // Column profile captures all input until closed
+    if app.profile_view.is_some() {
+        handle_profile_keys(app, key_event);
+        return Ok(());
+    }
+
+    // Recent tables popup captures all input until a table is picked or it is closed
+    if app.recent_tables_selected.is_some() {
+        handle_recent_tables_keys(app, key_event).await;
+        return Ok(());
+    }
+
+    // Layouts popup captures all input until one is restored or it is closed
+    if app.layout_picker.is_some() {
+        handle_layout_picker_keys(app, key_event).await;
+        return Ok(());
+    }
+
+    // Quick aggregate captures all input until run or closed
+    if app.aggregate_builder.is_some() {
+        handle_aggregate_keys(app, key_event);
+        return Ok(());
+    }
+
+    // GRANT/REVOKE builder captures all input until used or closed
+    if app.grant_builder.is_some() {
+        handle_grant_builder_keys(app, key_event);
+        return Ok(());
+    }
+
+    // User form captures all input until run or cancelled
+    if app.user_form.is_some() {
+        handle_user_form_keys(app, key_event).await;
+        return Ok(());
+    }
+
+    // Connection settings editor captures all input until saved or cancelled
+    if app.connection_settings_editor.is_some() {
+        handle_connection_settings_keys(app, key_event);
+        return Ok(());
+    }
+
+    // Date picker captures all input until confirmed or cancelled
+    if app.date_picker.is_some() {
+        handle_date_picker_keys(app, key_event);
+        return Ok(());
+    }
+
+    // File browser captures all input until a path is picked or it is cancelled
+    if app.file_browser.is_some() {
+        handle_file_browser_keys(app, key_event);
+        return Ok(());
+    }
+
+    // Hex viewer captures all input until closed; after the file browser, which
+    // saves its bytes
+    if app.hex_viewer.is_some() {
+        handle_hex_viewer_keys(app, key_event);
+        return Ok(());
+    }
+
+    // Jobs panel captures all input until closed
+    if app.show_jobs {
+        handle_jobs_panel_keys(app, key_event);
+        return Ok(());
+    }
+
+    // Message history captures all input until closed
+    if app.show_notifications {
+        handle_notifications_keys(app, key_event);
+        return Ok(());
+    }
+
+    if key_event.code == KeyCode::F(7) {
+        app.toggle_vim_mode();
+        return Ok(());
+    }
+    if key_event.code == KeyCode::F(8) {
+        app.cycle_theme();
+        return Ok(());
+    }
+    if app.settings.vim_mode {
+        key_event = vim_navigation_key(app, key_event);
+    }
+
+    // Session recording keys (available on all connected screens)
+    if is_connected_screen(app) {
+        match key_event.code {
+            KeyCode::F(2) => {
+                app.toggle_recording();
+                return Ok(());
+            }
+            KeyCode::F(3) => {
+                if app.recording.is_some() {
+                    app.open_prompt(PromptKind::RecordingNote, "Add Note", String::new());
+                } else {
+                    app.status_message = Some("Not recording. Press F2 to start".to_string());
+                }
+                return Ok(());
+            }
+            KeyCode::F(5) => {
+                app.open_statistics();
+                return Ok(());
+            }
+            KeyCode::F(6) => {
+                app.toggle_jobs_panel();
+                return Ok(());
+            }
+            KeyCode::F(9) => {
+                app.open_server_monitor().await;
+                return Ok(());
+            }
+            // The query editor keeps Ctrl+E for running the query
+            KeyCode::Char('e')
+                if key_event.modifiers.contains(KeyModifiers::CONTROL)
+                    && app.current_screen != AppScreen::QueryEditor =>
+            {
+                app.open_recent_tables();
+                return Ok(());
+            }
+            KeyCode::Char('w') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.open_layouts();
+                return Ok(());
+            }
+            KeyCode::F(4) => {
+                if app.exportable_recording().is_some() {
+                    let default_path = format!(
+                        "session-{}.md",
+                        chrono::Local::now().format("%Y%m%d-%H%M%S")
+                    );
+                    app.open_file_browser(FileBrowserTarget::RecordingExport, &default_path);
+                } else {
+                    app.status_message = Some("No session recording to export".to_string());
+                }
+                return Ok(());
+            }
+            _ => {}
+        }
+    }
+
     // Global key handlers (only when not in input fields)
     if !is_input_field_active(app) {
         match key_event.code {
@@ -16,15 +243,22 @@ pub async fn handle_key_event(app: &mut App, key_event: KeyEvent) -> Result<()>
                 app.should_quit = true;
                 return Ok(());
             }
-            KeyCode::Char('h') | KeyCode::F(1) => {
-                app.show_help = !app.show_help;
+            KeyCode::Char('h') => {
+                app.toggle_help();
                 return Ok(());
             }
-            KeyCode::Esc => {
-                if app.is_connecting {
-                    app.cancel_connection();
-                    return Ok(());
-                }
+            // `m` fetches more rows in the results view, so `M` opens it there
+            KeyCode::Char('m') if app.current_screen != AppScreen::QueryResults => {
+                app.toggle_notifications();
+                return Ok(());
+            }
+            KeyCode::Char('M') => {
+                app.toggle_notifications();
+                return Ok(());
+            }
+            KeyCode::Esc if app.is_connecting => {
+                app.cancel_connection();
+                return Ok(());
             }
             _ => {}
         }
@@ -37,7 +271,973 @@ pub async fn handle_key_event(app: &mut App, key_event: KeyEvent) -> Result<()>
         AppScreen::EditConnection => handle_edit_connection_keys(app, key_event),
         AppScreen::TableBrowser => handle_table_browser_keys(app, key_event).await,
         AppScreen::QueryEditor => handle_query_editor_keys(app, key_event).await,
-        AppScreen::QueryResults => handle_query_results_keys(app, key_event),
+        AppScreen::QueryResults => handle_query_results_keys(app, key_event).await,
+        AppScreen::Statistics => {
+            handle_statistics_keys(app, key_event);
+            Ok(())
+        }
+        AppScreen::ErMap => {
+            handle_er_map_keys(app, key_event).await;
+            Ok(())
+        }
+        AppScreen::ServerMonitor => {
+            handle_server_monitor_keys(app, key_event).await;
+            Ok(())
+        }
+        AppScreen::Routines => {
+            handle_routines_keys(app, key_event).await;
+            Ok(())
+        }
+        AppScreen::Chart => {
+            handle_chart_keys(app, key_event);
+            Ok(())
+        }
+        AppScreen::Pragmas => {
+            handle_pragma_keys(app, key_event).await;
+            Ok(())
+        }
+        AppScreen::Dashboard => {
+            handle_dashboard_keys(app, key_event);
+            Ok(())
+        }
+        AppScreen::Roles => {
+            handle_roles_keys(app, key_event).await;
+            Ok(())
+        }
+        AppScreen::Storage => {
+            handle_storage_keys(app, key_event).await;
+            Ok(())
+        }
+    }
+}
+
+/// F10 records a macro into a register (F10 again stops) and F12 replays one;
+/// both ask for the register with the next key. While recording, every other
+/// key is added to the macro. Returns whether the key was used up.
+fn handle_macro_keys(app: &mut App, key_event: KeyEvent) -> bool {
+    if let Some(prompt) = app.macro_prompt.take() {
+        app.choose_macro_register(prompt, key_event);
+        return true;
+    }
+    match key_event.code {
+        KeyCode::F(10) if app.macro_recording.is_some() => app.stop_macro_recording(),
+        KeyCode::F(10) => app.open_macro_prompt(MacroPrompt::Record),
+        KeyCode::F(12) if app.macro_recording.is_some() => {
+            app.status_message = Some("Stop recording with F10 before replaying".to_string());
+        }
+        KeyCode::F(12) => app.open_macro_prompt(MacroPrompt::Replay),
+        _ => {
+            if let Some(recording) = &mut app.macro_recording
+                && let Some(name) = macros::key_name(key_event)
+            {
+                recording.keys.push(name);
+            }
+            return false;
+        }
+    }
+    true
+}
+
+/// Popups that take every key, and the help popup.
+fn is_dialog_open(app: &App) -> bool {
+    app.pending_confirmation.is_some()
+        || app.session_offer.is_some()
+        || app.input_prompt.is_some()
+        || app.copy_wizard.is_some()
+        || app.index_manager.is_some()
+        || app.comment_editor.is_some()
+        || app.column_editor.is_some()
+        || app.seed_plan.is_some()
+        || app.profile_view.is_some()
+        || app.aggregate_builder.is_some()
+        || app.grant_builder.is_some()
+        || app.user_form.is_some()
+        || app.recent_tables_selected.is_some()
+        || app.layout_picker.is_some()
+        || app.json_viewer.is_some()
+        || app.assistant.is_some()
+        || app.connection_settings_editor.is_some()
+        || app.date_picker.is_some()
+        || app.show_help
+}
+
+/// Any popup over the screen, the panels included.
+fn is_popup_open(app: &App) -> bool {
+    is_dialog_open(app)
+        || app.file_browser.is_some()
+        || app.hex_viewer.is_some()
+        || app.show_jobs
+        || app.show_notifications
+}
+
+/// Pasted text (bracketed paste) goes into the focused text input as one edit:
+/// the prompt, a popup's field being typed, a connection form field, a list
+/// filter being typed, or the query editor, where it keeps its line breaks. It
+/// is dropped anywhere else.
+pub async fn handle_paste(app: &mut App, text: &str) -> Result<()> {
+    if let Some(prompt) = &mut app.input_prompt {
+        prompt.input.paste(text, false);
+        return Ok(());
+    }
+    if app.error_message.is_some()
+        || app.pending_confirmation.is_some()
+        || app.session_offer.is_some()
+        || app.show_help
+    {
+        return Ok(());
+    }
+    if paste_into_popup(app, text) || is_popup_open(app) {
+        return Ok(());
+    }
+    match app.current_screen {
+        AppScreen::NewConnection | AppScreen::EditConnection
+            if app.connection_form.template_picker.is_none() =>
+        {
+            app.connection_form.paste(text)
+        }
+        AppScreen::ConnectionList if app.connection_filter_active => {
+            app.connection_filter.paste(text, false);
+            app.apply_connection_filter();
+        }
+        AppScreen::TableBrowser if app.table_filter_active => {
+            app.table_filter.paste(text, false);
+            if app.apply_table_filter() {
+                refresh_selected_table_columns(app).await;
+            }
+        }
+        AppScreen::QueryEditor => {
+            app.query.paste(text, true);
+            app.editor_mode = EditorMode::Insert;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Pastes into the text field of the popup taking keys, in the order
+/// `handle_key_event` gives them keys. Returns false when none of them is open.
+fn paste_into_popup(app: &mut App, text: &str) -> bool {
+    // The file browser takes keys after the other popups
+    let dialog_open = is_dialog_open(app);
+    if let Some(manager) = &mut app.index_manager {
+        if let Some(form) = &mut manager.form
+            && form.editing_name
+            && manager.preview.is_none()
+        {
+            form.paste_name(text);
+        }
+    } else if let Some(editor) = &mut app.comment_editor {
+        if let Some(input) = &mut editor.input
+            && editor.preview.is_none()
+        {
+            input.paste(text, false);
+        }
+    } else if let Some(editor) = &mut app.column_editor {
+        if let Some(form) = &mut editor.form
+            && editor.preview.is_none()
+            && let Some(input) = form.selected_input()
+        {
+            input.paste(text, false);
+        }
+    } else if let Some(plan) = &mut app.seed_plan {
+        let digits: String = text.chars().filter(char::is_ascii_digit).collect();
+        plan.rows.insert_str(&digits);
+    } else if let Some(view) = &mut app.assistant {
+        if view.task.is_none() {
+            view.question.paste(text, false);
+        }
+    } else if let Some(form) = &mut app.user_form {
+        if !form.preview {
+            form.paste(text);
+        }
+    } else if let Some(browser) = &mut app.file_browser
+        && !dialog_open
+    {
+        if browser.focus == BrowserFocus::FileName {
+            browser.file_name.paste(text, false);
+        }
+    } else {
+        return false;
+    }
+    true
+}
+
+/// Mouse support: the wheel scrolls like the arrow keys and a left click
+/// selects the list item, grid cell or tab under the pointer. Clicking an
+/// already selected connection, file or job activates it like Enter.
+pub async fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> Result<()> {
+    let (column, row) = (mouse.column, mouse.row);
+    let scroll_key = match mouse.kind {
+        MouseEventKind::ScrollUp => KeyCode::Up,
+        MouseEventKind::ScrollDown => KeyCode::Down,
+        MouseEventKind::Down(MouseButton::Left) => {
+            return handle_mouse_click(app, column, row).await;
+        }
+        _ => return Ok(()),
+    };
+
+    // Popups that take text or answers ignore the wheel
+    if app.error_message.is_some()
+        || app.pending_confirmation.is_some()
+        || app.session_offer.is_some()
+        || app.input_prompt.is_some()
+        || app.copy_wizard.is_some()
+        || app.index_manager.is_some()
+        || app.comment_editor.is_some()
+        || app.column_editor.is_some()
+        || app.seed_plan.is_some()
+        || app.profile_view.is_some()
+        || app.aggregate_builder.is_some()
+        || app.grant_builder.is_some()
+        || app.user_form.is_some()
+        || app.recent_tables_selected.is_some()
+        || app.layout_picker.is_some()
+        || app.assistant.is_some()
+        || app.connection_settings_editor.is_some()
+        || app.date_picker.is_some()
+    {
+        return Ok(());
+    }
+    if let Some(browser) = &mut app.file_browser {
+        browser.move_selection(if scroll_key == KeyCode::Up { -1 } else { 1 });
+        return Ok(());
+    }
+    if let Some(viewer) = &mut app.json_viewer {
+        viewer.move_selection(if scroll_key == KeyCode::Up { -1 } else { 1 });
+        return Ok(());
+    }
+    if let Some(viewer) = &mut app.hex_viewer {
+        viewer.scroll_by(if scroll_key == KeyCode::Up { -1 } else { 1 });
+        return Ok(());
+    }
+    if app.show_jobs || app.show_notifications || app.current_screen != AppScreen::QueryEditor {
+        return handle_key_event(app, KeyEvent::new(scroll_key, KeyModifiers::NONE)).await;
+    }
+
+    // In the editor the wheel only scrolls the split view's results pane
+    if app
+        .mouse_areas
+        .results_pane
+        .is_some_and(|area| area.contains((column, row).into()))
+    {
+        handle_key_event(app, KeyEvent::new(scroll_key, KeyModifiers::ALT)).await?;
+    }
+    Ok(())
+}
+
+async fn handle_mouse_click(app: &mut App, column: u16, row: u16) -> Result<()> {
+    if app.error_message.is_some() {
+        app.clear_messages();
+        return Ok(());
+    }
+    // Dialogs are keyboard-only, and clicks don't reach through the help popup
+    if is_dialog_open(app) {
+        return Ok(());
+    }
+    let areas = app.mouse_areas.clone();
+
+    if let Some(browser) = &mut app.file_browser {
+        if let Some(index) = areas.file_browser.and_then(|a| a.index_at(column, row)) {
+            browser.focus = BrowserFocus::List;
+            if browser.selected == index {
+                handle_file_browser_keys(app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+            } else {
+                browser.selected = index;
+            }
+        }
+        return Ok(());
+    }
+    if app.hex_viewer.is_some() {
+        return Ok(());
+    }
+    if app.show_jobs {
+        if let Some(index) = areas.jobs.and_then(|a| a.index_at(column, row)) {
+            if app.selected_job_index == index {
+                app.view_selected_job_result();
+            } else {
+                app.selected_job_index = index;
+            }
+        }
+        return Ok(());
+    }
+
+    match app.current_screen {
+        AppScreen::ConnectionList => {
+            let rows = app.connection_list_rows();
+            // Group headings aren't selectable
+            if let Some(ConnectionListRow::Connection(index)) = areas
+                .connection_list
+                .and_then(|a| a.index_at(column, row))
+                .and_then(|i| rows.get(i))
+            {
+                if app.selected_connection_index == *index {
+                    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+                    handle_connection_list_keys(app, enter).await?;
+                } else {
+                    app.selected_connection_index = *index;
+                }
+            }
+        }
+        AppScreen::TableBrowser => {
+            let rows = app.table_list_rows();
+            let Some(row) = areas
+                .table_list
+                .and_then(|a| a.index_at(column, row))
+                .and_then(|position| rows.get(position))
+            else {
+                return Ok(());
+            };
+            match row {
+                // A click on the selected schema expands or collapses it
+                TableListRow::Schema(index) if app.selected_schema == Some(*index) => {
+                    if app.schema_tree.as_ref().is_some_and(|t| t[*index].expanded) {
+                        app.collapse_schema(*index);
+                    } else {
+                        let _ = app.expand_schema(*index).await;
+                    }
+                }
+                row => {
+                    if app.select_table_list_row(row) {
+                        refresh_selected_table_columns(app).await;
+                    }
+                }
+            }
+        }
+        AppScreen::QueryEditor | AppScreen::QueryResults => {
+            let Some(grid) = areas.results_grid else {
+                return Ok(());
+            };
+            let Some(column_index) = grid.column_at(column) else {
+                return Ok(());
+            };
+            if row == grid.header_y {
+                app.selected_column_index = column_index;
+            } else if let Some(row_index) = grid.rows.index_at(column, row) {
+                app.selected_column_index = column_index;
+                app.selected_row_index = row_index;
+            }
+        }
+        AppScreen::Statistics => {
+            if let Some((_, view)) = areas
+                .statistics_tabs
+                .iter()
+                .find(|(area, _)| area.contains((column, row).into()))
+                && app.statistics_view != *view
+            {
+                app.statistics_view = view.clone();
+                app.statistics_scroll = 0;
+            }
+        }
+        AppScreen::ErMap => {
+            if let Some((_, view)) = areas
+                .er_tabs
+                .iter()
+                .find(|(area, _)| area.contains((column, row).into()))
+                && app.er_view != *view
+            {
+                app.toggle_er_view();
+            }
+        }
+        AppScreen::ServerMonitor => {
+            if let Some(index) = areas.sessions.and_then(|a| a.index_at(column, row)) {
+                app.selected_session_index = index;
+            }
+        }
+        AppScreen::Routines => {
+            if let Some(index) = areas.routines.and_then(|a| a.index_at(column, row)) {
+                if app.selected_routine_index == index {
+                    app.scaffold_routine_call();
+                } else {
+                    app.select_routine(index);
+                }
+            }
+        }
+        AppScreen::Roles => {
+            if let Some(index) = areas.roles.and_then(|a| a.index_at(column, row))
+                && let Some(browser) = &mut app.role_browser
+            {
+                browser.select(index);
+            }
+        }
+        AppScreen::Storage => {
+            if let Some(index) = areas.tablespaces.and_then(|a| a.index_at(column, row))
+                && let Some(overview) = &mut app.storage
+            {
+                overview.select(index);
+            }
+        }
+        AppScreen::NewConnection
+        | AppScreen::EditConnection
+        | AppScreen::Chart
+        | AppScreen::Pragmas
+        | AppScreen::Dashboard => {}
+    }
+    Ok(())
+}
+
+/// In vim mode, hjkl act as the arrow keys on list and grid screens.
+fn vim_navigation_key(app: &App, key_event: KeyEvent) -> KeyEvent {
+    let navigable = matches!(
+        app.current_screen,
+        AppScreen::ConnectionList
+            | AppScreen::TableBrowser
+            | AppScreen::QueryResults
+            | AppScreen::Statistics
+            | AppScreen::ErMap
+            | AppScreen::ServerMonitor
+            | AppScreen::Routines
+            | AppScreen::Chart
+            | AppScreen::Pragmas
+            | AppScreen::Dashboard
+            | AppScreen::Roles
+            | AppScreen::Storage
+    );
+    let typing_filter = (app.current_screen == AppScreen::TableBrowser && app.table_filter_active)
+        || (app.current_screen == AppScreen::ConnectionList && app.connection_filter_active);
+    if !navigable || !key_event.modifiers.is_empty() || typing_filter {
+        return key_event;
+    }
+    let code = match key_event.code {
+        KeyCode::Char('h') => KeyCode::Left,
+        KeyCode::Char('j') => KeyCode::Down,
+        KeyCode::Char('k') => KeyCode::Up,
+        KeyCode::Char('l') => KeyCode::Right,
+        code => code,
+    };
+    KeyEvent::new(code, key_event.modifiers)
+}
+
+fn is_connected_screen(app: &App) -> bool {
+    matches!(
+        app.current_screen,
+        AppScreen::TableBrowser | AppScreen::QueryEditor | AppScreen::QueryResults
+    )
+}
+
+/// Opens the file browser for the focused field if it takes a file path (SQLite
+/// database file or SSL files). Returns false for any other field.
+fn open_field_file_browser(app: &mut App) -> bool {
+    let target = match app.connection_form.current_field {
+        ConnectionField::Host
+            if matches!(
+                app.connection_form.database_type,
+                crate::database::DatabaseType::SQLite
+            ) =>
+        {
+            FileBrowserTarget::SqliteFile
+        }
+        ConnectionField::SslCertFile => FileBrowserTarget::SslCertFile,
+        ConnectionField::SslKeyFile => FileBrowserTarget::SslKeyFile,
+        ConnectionField::SslCaFile => FileBrowserTarget::SslCaFile,
+        _ => return false,
+    };
+    let current_value = app.connection_form.get_current_field_value().to_string();
+    app.open_file_browser(target, &current_value);
+    true
+}
+
+async fn handle_prompt_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
+    let Some(prompt) = &mut app.input_prompt else {
+        return Ok(());
+    };
+
+    match key_event.code {
+        KeyCode::Esc => {
+            app.input_prompt = None;
+        }
+        KeyCode::Enter => {
+            if let Some(prompt) = app.input_prompt.take() {
+                submit_prompt(app, prompt.kind, prompt.input.text).await;
+            }
+        }
+        _ => {
+            prompt.input.handle_key(key_event);
+        }
+    }
+    Ok(())
+}
+
+fn handle_date_picker_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(picker) = &mut app.date_picker else {
+        return;
+    };
+    let on_calendar = picker.focus == PickerFocus::Calendar;
+
+    match key_event.code {
+        KeyCode::Esc => {
+            app.date_picker = None;
+        }
+        KeyCode::Enter => {
+            app.confirm_date_picker();
+        }
+        KeyCode::Char('/') if picker.target == DatePickerTarget::ColumnFilter => {
+            app.date_picker = None;
+            app.open_column_filter_prompt();
+        }
+        KeyCode::Tab => picker.next_focus(),
+        KeyCode::Char('t') => picker.toggle_time(),
+        KeyCode::PageUp => picker.move_months(-1),
+        KeyCode::PageDown => picker.move_months(1),
+        KeyCode::Left if on_calendar => picker.move_days(-1),
+        KeyCode::Right if on_calendar => picker.move_days(1),
+        KeyCode::Up if on_calendar => picker.move_days(-7),
+        KeyCode::Down if on_calendar => picker.move_days(7),
+        KeyCode::Up => picker.adjust_time(1),
+        KeyCode::Down => picker.adjust_time(-1),
+        _ => {}
+    }
+}
+
+fn handle_file_browser_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(browser) = &mut app.file_browser else {
+        return;
+    };
+
+    let result = if browser.focus == BrowserFocus::FileName {
+        match key_event.code {
+            KeyCode::Esc => {
+                app.file_browser = None;
+                return;
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                browser.focus = BrowserFocus::List;
+                Ok(())
+            }
+            KeyCode::Enter => {
+                if let Some(path) = browser.save_path() {
+                    app.confirm_file_browser(path);
+                }
+                return;
+            }
+            _ => {
+                browser.file_name.handle_key(key_event);
+                Ok(())
+            }
+        }
+    } else {
+        match key_event.code {
+            KeyCode::Esc => {
+                app.file_browser = None;
+                return;
+            }
+            KeyCode::Tab | KeyCode::BackTab if browser.target.takes_file_name() => {
+                browser.focus = BrowserFocus::FileName;
+                Ok(())
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                browser.move_selection(-1);
+                Ok(())
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                browser.move_selection(1);
+                Ok(())
+            }
+            KeyCode::PageUp => {
+                browser.move_selection(-10);
+                Ok(())
+            }
+            KeyCode::PageDown => {
+                browser.move_selection(10);
+                Ok(())
+            }
+            KeyCode::Home => {
+                browser.selected = 0;
+                Ok(())
+            }
+            KeyCode::End => {
+                browser.move_selection(isize::MAX);
+                Ok(())
+            }
+            KeyCode::Left | KeyCode::Backspace => browser.go_parent(),
+            KeyCode::Char('~') => match dirs::home_dir() {
+                Some(home) => browser.change_dir(home),
+                None => Ok(()),
+            },
+            KeyCode::Char('.') => browser.toggle_hidden(),
+            KeyCode::Char('f') => browser.toggle_extension_filter(),
+            KeyCode::Enter | KeyCode::Right => match browser.activate() {
+                Ok(Some(path)) => {
+                    app.confirm_file_browser(path);
+                    return;
+                }
+                Ok(None) => Ok(()),
+                Err(e) => Err(e),
+            },
+            _ => Ok(()),
+        }
+    };
+
+    if let Err(e) = result {
+        app.error_message = Some(format!("Cannot open directory: {}", e));
+    }
+}
+
+async fn submit_prompt(app: &mut App, kind: PromptKind, value: String) {
+    match kind {
+        PromptKind::RecordingNote => {
+            if !value.trim().is_empty() {
+                app.add_recording_note(value.trim().to_string());
+            }
+        }
+        PromptKind::CopyTableName => {
+            if value.trim().is_empty() {
+                // Back to choosing the connection
+                return;
+            }
+            app.start_copy(value.trim().to_string());
+        }
+        PromptKind::ConnectionSetting => app.set_connection_setting(&value),
+        PromptKind::ColumnFilter => app.apply_column_filter(value.trim()),
+        PromptKind::AttachSchema => {
+            if let Err(e) = app.attach_database(value.trim()).await {
+                app.error_message = Some(format!("Failed to attach the database: {}", e));
+            }
+        }
+        PromptKind::TileTitle => {
+            if !value.trim().is_empty() {
+                app.add_dashboard_tile(value.trim());
+            }
+        }
+        PromptKind::TileAlert => app.set_tile_alert(&value),
+        PromptKind::GrantObject => {
+            if let Some(builder) = &mut app.grant_builder {
+                builder.object = value.trim().to_string();
+            }
+        }
+        PromptKind::LayoutName => {
+            if !value.trim().is_empty() {
+                app.save_layout(value.trim());
+            }
+        }
+        PromptKind::RenameTable | PromptKind::TruncateTable | PromptKind::DropTable => {
+            app.run_table_action(kind, &value).await
+        }
+    }
+}
+
+fn handle_copy_wizard_keys(app: &mut App, key_event: KeyEvent) {
+    let count = app.connections.len();
+    let Some(wizard) = &mut app.copy_wizard else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Esc => app.copy_wizard = None,
+        KeyCode::Up if wizard.selected > 0 => wizard.selected -= 1,
+        KeyCode::Down if wizard.selected + 1 < count => wizard.selected += 1,
+        KeyCode::Enter => app.choose_copy_target(),
+        _ => {}
+    }
+}
+
+/// Digits pick an entry directly, 0 the tenth.
+async fn handle_recent_tables_keys(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.recent_tables_selected = None,
+        KeyCode::Char('e') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.recent_tables_selected = None;
+        }
+        KeyCode::Up => app.move_recent_tables_selection(-1),
+        KeyCode::Down => app.move_recent_tables_selection(1),
+        KeyCode::Enter => app.jump_to_recent_table().await,
+        KeyCode::Char(c @ '0'..='9') => {
+            let index = (c as usize - '0' as usize + 9) % 10;
+            if index < app.recent_tables().len() {
+                app.recent_tables_selected = Some(index);
+                app.jump_to_recent_table().await;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `s` saves the current layout, under a new name or the selected one's.
+async fn handle_layout_picker_keys(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.layout_picker = None,
+        KeyCode::Char('w') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.layout_picker = None;
+        }
+        KeyCode::Up => app.move_layout_selection(-1),
+        KeyCode::Down => app.move_layout_selection(1),
+        KeyCode::Enter => app.restore_selected_layout().await,
+        KeyCode::Char('s') => app.prompt_layout_name(),
+        KeyCode::Char('d') => app.delete_selected_layout(),
+        _ => {}
+    }
+}
+
+/// The index list, the new index form and the statement preview each take
+/// their own keys; Esc steps back out of them one at a time.
+async fn handle_index_manager_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(manager) = &mut app.index_manager else {
+        return;
+    };
+
+    if manager.preview.is_some() {
+        match key_event.code {
+            KeyCode::Enter | KeyCode::Char('y') => app.execute_index_statement().await,
+            KeyCode::Esc | KeyCode::Char('n') => manager.preview = None,
+            _ => {}
+        }
+        return;
+    }
+
+    if let Some(form) = &mut manager.form {
+        if form.editing_name {
+            match key_event.code {
+                KeyCode::Esc | KeyCode::Tab => form.editing_name = false,
+                KeyCode::Enter => app.preview_create_index(),
+                _ => form.handle_name_key(key_event),
+            }
+            return;
+        }
+        match key_event.code {
+            KeyCode::Esc => manager.form = None,
+            KeyCode::Up if form.selected > 0 => form.selected -= 1,
+            KeyCode::Down if form.selected + 1 < form.columns.len() => form.selected += 1,
+            KeyCode::Char(' ') => form.toggle_column(),
+            KeyCode::Char('u') => form.toggle_unique(),
+            KeyCode::Tab => form.editing_name = true,
+            KeyCode::Enter => app.preview_create_index(),
+            _ => {}
+        }
+        return;
+    }
+
+    match key_event.code {
+        KeyCode::Esc => app.index_manager = None,
+        KeyCode::Up if manager.selected > 0 => manager.selected -= 1,
+        KeyCode::Down if manager.selected + 1 < manager.indexes.len() => manager.selected += 1,
+        KeyCode::Char('n') => app.new_index_form(),
+        KeyCode::Char('d') | KeyCode::Delete => app.preview_drop_index(),
+        _ => {}
+    }
+}
+
+async fn handle_comment_editor_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(editor) = &mut app.comment_editor else {
+        return;
+    };
+
+    if editor.preview.is_some() {
+        match key_event.code {
+            KeyCode::Enter | KeyCode::Char('y') => app.execute_comment_statement().await,
+            KeyCode::Esc | KeyCode::Char('n') => editor.preview = None,
+            _ => {}
+        }
+        return;
+    }
+
+    if let Some(input) = &mut editor.input {
+        match key_event.code {
+            KeyCode::Esc => editor.input = None,
+            KeyCode::Enter => app.preview_comment(),
+            _ => {
+                input.handle_key(key_event);
+            }
+        }
+        return;
+    }
+
+    match key_event.code {
+        KeyCode::Esc => app.comment_editor = None,
+        KeyCode::Up => editor.move_selection(-1),
+        KeyCode::Down => editor.move_selection(1),
+        KeyCode::Home => editor.selected = 0,
+        KeyCode::End => editor.move_selection(isize::MAX),
+        KeyCode::Enter | KeyCode::Char('e') => editor.start_editing(),
+        _ => {}
+    }
+}
+
+async fn handle_column_editor_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(editor) = &mut app.column_editor else {
+        return;
+    };
+
+    if editor.preview.is_some() {
+        match key_event.code {
+            KeyCode::Enter | KeyCode::Char('y') => app.execute_column_change().await,
+            KeyCode::Esc | KeyCode::Char('n') => editor.preview = None,
+            _ => {}
+        }
+        return;
+    }
+
+    if let Some(form) = &mut editor.form {
+        match key_event.code {
+            KeyCode::Esc => editor.form = None,
+            KeyCode::Up | KeyCode::BackTab => form.move_selection(-1),
+            KeyCode::Down | KeyCode::Tab => form.move_selection(1),
+            KeyCode::Enter => app.preview_column_change(),
+            KeyCode::Char(' ') if form.selected_field() == ColumnField::NotNull => {
+                form.toggle_not_null()
+            }
+            _ => {
+                if let Some(input) = form.selected_input() {
+                    input.handle_key(key_event);
+                }
+            }
+        }
+        return;
+    }
+
+    match key_event.code {
+        KeyCode::Esc => app.column_editor = None,
+        KeyCode::Up => editor.move_selection(-1),
+        KeyCode::Down => editor.move_selection(1),
+        KeyCode::Home => editor.selected = 0,
+        KeyCode::End => editor.move_selection(isize::MAX),
+        KeyCode::Enter | KeyCode::Char('e') => app.edit_selected_column().await,
+        _ => {}
+    }
+}
+
+fn handle_seed_plan_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(plan) = &mut app.seed_plan else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Esc => app.seed_plan = None,
+        KeyCode::Up => plan.move_selection(-1),
+        KeyCode::Down => plan.move_selection(1),
+        KeyCode::Left | KeyCode::Right | KeyCode::Char(' ') => {
+            let delta = if key_event.code == KeyCode::Left {
+                -1
+            } else {
+                1
+            };
+            let selected = plan.selected;
+            if let Some(column) = plan.columns.get_mut(selected) {
+                column.cycle_generator(delta);
+            }
+        }
+        KeyCode::Char(c) if c.is_ascii_digit() => plan.rows.insert_char(c),
+        KeyCode::Backspace => plan.rows.delete_before(),
+        KeyCode::Enter => app.start_seed(),
+        _ => {}
+    }
+}
+
+fn handle_json_viewer_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(viewer) = &mut app.json_viewer else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.json_viewer = None,
+        KeyCode::Up | KeyCode::Char('k') => viewer.move_selection(-1),
+        KeyCode::Down | KeyCode::Char('j') => viewer.move_selection(1),
+        KeyCode::PageUp => viewer.move_selection(-10),
+        KeyCode::PageDown => viewer.move_selection(10),
+        KeyCode::Home => viewer.selected = 0,
+        KeyCode::End => viewer.move_selection(isize::MAX),
+        KeyCode::Enter | KeyCode::Char(' ') => viewer.toggle_selected(),
+        KeyCode::Char('c') => viewer.set_all_collapsed(true),
+        KeyCode::Char('e') => viewer.set_all_collapsed(false),
+        KeyCode::Char('y') => app.copy_json(),
+        _ => {}
+    }
+}
+
+fn handle_hex_viewer_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(viewer) = &mut app.hex_viewer else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.hex_viewer = None,
+        KeyCode::Up | KeyCode::Char('k') => viewer.scroll_by(-1),
+        KeyCode::Down | KeyCode::Char('j') => viewer.scroll_by(1),
+        KeyCode::PageUp => viewer.scroll_by(-16),
+        KeyCode::PageDown => viewer.scroll_by(16),
+        KeyCode::Home => viewer.scroll = 0,
+        KeyCode::End => viewer.scroll_by(isize::MAX),
+        KeyCode::Char('s') => app.prompt_save_blob(),
+        _ => {}
+    }
+}
+
+fn handle_assistant_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(view) = &mut app.assistant else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Esc => app.close_assistant(),
+        KeyCode::Enter => app.ask_assistant(),
+        // The question stays as it is while it is being answered
+        _ if view.task.is_some() => {}
+        _ => {
+            view.question.handle_key(key_event);
+        }
+    }
+}
+
+fn handle_profile_keys(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.close_profile(),
+        KeyCode::Left | KeyCode::BackTab => app.cycle_profile_column(false),
+        KeyCode::Right | KeyCode::Tab => app.cycle_profile_column(true),
+        KeyCode::Char('r') => app.start_profile(),
+        _ => {}
+    }
+}
+
+fn handle_aggregate_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(builder) = &mut app.aggregate_builder else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.aggregate_builder = None,
+        KeyCode::Up => builder.move_selection(-1),
+        KeyCode::Down => builder.move_selection(1),
+        KeyCode::Char('g') | KeyCode::Char(' ') => app.toggle_aggregate_group_by(),
+        KeyCode::Enter => app.run_aggregate(),
+        KeyCode::Char(c) => {
+            if let Some(aggregate) = Aggregate::from_key(c) {
+                app.toggle_aggregate(aggregate);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_grant_builder_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(builder) = &mut app.grant_builder else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.grant_builder = None,
+        KeyCode::Up => builder.move_selection(-1),
+        KeyCode::Down => builder.move_selection(1),
+        KeyCode::Char(' ') => builder.toggle_selected(),
+        KeyCode::Char('a') => builder.toggle_all(),
+        KeyCode::Tab => builder.cycle_target(),
+        KeyCode::Char('v') => builder.revoke = !builder.revoke,
+        KeyCode::Char('w') => builder.grant_option = !builder.grant_option,
+        KeyCode::Char('o') => app.prompt_grant_object(),
+        KeyCode::Enter => app.grant_statement_to_editor(),
+        _ => {}
+    }
+}
+
+async fn handle_user_form_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(form) = &mut app.user_form else {
+        return;
+    };
+    if form.preview {
+        match key_event.code {
+            KeyCode::Enter | KeyCode::Char('y') => app.execute_user_statement().await,
+            KeyCode::Esc | KeyCode::Char('n') => form.preview = false,
+            _ => {}
+        }
+        return;
+    }
+    match key_event.code {
+        KeyCode::Esc => app.user_form = None,
+        KeyCode::Up | KeyCode::BackTab => form.move_selection(-1),
+        KeyCode::Down | KeyCode::Tab => form.move_selection(1),
+        KeyCode::Enter => app.preview_user_statement(),
+        KeyCode::Char(' ') if form.selected_field() == UserField::Login => form.toggle_login(),
+        _ => form.handle_key(key_event),
     }
 }
 
@@ -45,17 +1245,70 @@ fn is_input_field_active(app: &App) -> bool {
     matches!(
         app.current_screen,
         AppScreen::NewConnection | AppScreen::EditConnection | AppScreen::QueryEditor
-    )
+    ) || (app.current_screen == AppScreen::TableBrowser && app.table_filter_active)
+        || (app.current_screen == AppScreen::ConnectionList && app.connection_filter_active)
+}
+
+fn handle_connection_settings_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(editor) = &mut app.connection_settings_editor else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Esc => app.connection_settings_editor = None,
+        KeyCode::Up if editor.selected > 0 => editor.selected -= 1,
+        KeyCode::Down if editor.selected + 1 < ConnectionSettingField::ALL.len() => {
+            editor.selected += 1
+        }
+        KeyCode::Enter => app.edit_connection_setting(),
+        KeyCode::Char('s') => app.save_connection_settings(),
+        _ => {}
+    }
 }
 
 async fn handle_connection_list_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
+    if app.connection_filter_active {
+        handle_connection_filter_keys(app, key_event);
+        return Ok(());
+    }
+
     match key_event.code {
         KeyCode::Char('q') => {
             app.should_quit = true;
         }
         KeyCode::Char('n') => {
             app.current_screen = AppScreen::NewConnection;
-            app.connection_form = Default::default();
+            app.connection_form = ConnectionForm::new_connection();
+        }
+        KeyCode::Char('s') => {
+            app.open_statistics();
+        }
+        KeyCode::Char('o') if !app.connections.is_empty() => {
+            app.open_connection_settings();
+        }
+        KeyCode::Char('g') => {
+            app.cycle_connection_group_filter();
+        }
+        KeyCode::Char('/') => {
+            app.connection_filter_active = true;
+        }
+        KeyCode::Char('x') if !app.connections.is_empty() => {
+            app.open_file_browser(
+                FileBrowserTarget::ConnectionsExport,
+                "connections-export.json",
+            );
+        }
+        KeyCode::Char('i') => {
+            app.open_file_browser(FileBrowserTarget::ConnectionsImport, "");
+        }
+        KeyCode::Char('r') => app.toggle_connection_recency_sort(),
+        KeyCode::Char('a') => app.toggle_auto_connect_last(),
+        KeyCode::Char('K') => app.move_connection(true),
+        KeyCode::Char('J') => app.move_connection(false),
+        KeyCode::Up if key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+            app.move_connection(true);
+        }
+        KeyCode::Down if key_event.modifiers.contains(KeyModifiers::SHIFT) => {
+            app.move_connection(false);
         }
         KeyCode::Up => {
             app.previous_connection();
@@ -64,35 +1317,37 @@ async fn handle_connection_list_keys(app: &mut App, key_event: KeyEvent) -> Resu
             app.next_connection();
         }
         KeyCode::Enter => {
-            if !app.connections.is_empty() && !app.is_connecting {
-                if let Err(e) = app.start_connection(app.selected_connection_index) {
-                    app.error_message = Some(format!("Failed to start connection: {}", e));
-                }
+            if !app.connections.is_empty()
+                && !app.is_connecting
+                && let Err(e) = app.start_connection(app.selected_connection_index)
+            {
+                app.error_message = Some(format!("Failed to start connection: {}", e));
             }
         }
         KeyCode::Char('e') => {
-            if !app.connections.is_empty() && !app.is_connecting {
-                if let Err(e) = app.start_editing_connection(app.selected_connection_index) {
-                    app.error_message = Some(format!("Failed to start editing connection: {}", e));
-                }
+            if !app.connections.is_empty()
+                && !app.is_connecting
+                && let Err(e) = app.start_editing_connection(app.selected_connection_index)
+            {
+                app.error_message = Some(format!("Failed to start editing connection: {}", e));
             }
         }
-        KeyCode::Char('d') => {
-            if !app.connections.is_empty() {
-                let index_to_remove = app.selected_connection_index;
-                let _ = app.remove_connection(index_to_remove).await;
-                // Adjust selected index if necessary
-                if app.selected_connection_index >= app.connections.len()
-                    && !app.connections.is_empty()
-                {
-                    app.selected_connection_index = app.connections.len() - 1;
-                }
-                // Save connections to disk
-                if let Err(e) = app.save_connections() {
-                    app.error_message = Some(format!("Failed to save connections: {}", e));
-                }
+        KeyCode::Char('d') if !app.connections.is_empty() => {
+            let index_to_remove = app.selected_connection_index;
+            let _ = app.remove_connection(index_to_remove).await;
+            // Adjust selected index if necessary
+            if app.selected_connection_index >= app.connections.len() && !app.connections.is_empty()
+            {
+                app.selected_connection_index = app.connections.len() - 1;
+            }
+            // Save connections to disk
+            if let Err(e) = app.save_connections() {
+                app.error_message = Some(format!("Failed to save connections: {}", e));
             }
         }
+        KeyCode::Esc if !app.connection_filter.is_empty() => {
+            app.clear_connection_filter();
+        }
         KeyCode::Esc => {
             app.should_quit = true;
         }
@@ -101,7 +1356,27 @@ async fn handle_connection_list_keys(app: &mut App, key_event: KeyEvent) -> Resu
     Ok(())
 }
 
+/// Typing into the connection filter, like the table filter: the list narrows
+/// with every keystroke, Enter keeps the filter and Esc clears it.
+fn handle_connection_filter_keys(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Esc => app.clear_connection_filter(),
+        KeyCode::Enter => app.connection_filter_active = false,
+        KeyCode::Up => app.previous_connection(),
+        KeyCode::Down => app.next_connection(),
+        _ => {
+            if app.connection_filter.handle_key(key_event) {
+                app.apply_connection_filter();
+            }
+        }
+    }
+}
+
 fn handle_new_connection_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
+    if app.connection_form.template_picker.is_some() {
+        handle_template_picker_keys(app, key_event);
+        return Ok(());
+    }
     match key_event.code {
         KeyCode::Tab => {
             if key_event.modifiers.contains(KeyModifiers::SHIFT) {
@@ -110,18 +1385,14 @@ fn handle_new_connection_keys(app: &mut App, key_event: KeyEvent) -> Result<()>
                 app.connection_form.next_field();
             }
         }
-        KeyCode::Enter => {
-            if !app.connection_form.name.is_empty() {
-                match app.save_edited_connection() {
-                    Ok(()) => {
-                        app.status_message = Some("Connection updated successfully".to_string());
-                    }
-                    Err(e) => {
-                        app.error_message = Some(format!("Failed to update connection: {}", e));
-                    }
-                }
+        KeyCode::Enter => match app.save_edited_connection() {
+            Ok(()) => {
+                app.status_message = Some("Connection added".to_string());
             }
-        }
+            Err(e) => {
+                app.error_message = Some(format!("Failed to add connection: {}", e));
+            }
+        },
         KeyCode::Esc => {
             app.current_screen = AppScreen::ConnectionList;
         }
@@ -129,85 +1400,66 @@ fn handle_new_connection_keys(app: &mut App, key_event: KeyEvent) -> Result<()>
             // Handle toggle fields
             if app.connection_form.is_toggle_field() {
                 match app.connection_form.current_field {
-                    ConnectionField::UseSsl => {
-                        if c == 'y' || c == 'Y' || c == ' ' || c == '\n' {
-                            app.connection_form.toggle_ssl();
-                        }
+                    ConnectionField::UseSsl if c == 'y' || c == 'Y' || c == ' ' || c == '\n' => {
+                        app.connection_form.toggle_ssl();
+                    }
+                    ConnectionField::SslMode if c == ' ' || c == '\n' => {
+                        app.connection_form.cycle_ssl_mode();
+                    }
+                    ConnectionField::DatabaseType if c == ' ' || c == '\n' => {
+                        app.connection_form.cycle_database_type();
                     }
-                    ConnectionField::SslMode => {
-                        if c == ' ' || c == '\n' {
-                            app.connection_form.cycle_ssl_mode();
-                        }
+                    ConnectionField::Color if c == ' ' => {
+                        app.connection_form.color =
+                            ConnectionColor::cycle(app.connection_form.color);
                     }
-                    ConnectionField::DatabaseType => {
-                        if c == ' ' || c == '\n' {
-                            app.connection_form.cycle_database_type();
-                        }
+                    ConnectionField::ReadOnly if c == 'y' || c == 'Y' || c == ' ' => {
+                        app.connection_form.read_only = !app.connection_form.read_only;
                     }
                     _ => {}
                 }
                 return Ok(());
             }
 
-            // Handle file selection shortcuts
-            #[cfg(not(target_arch = "wasm32"))]
-            match app.connection_form.current_field {
-                ConnectionField::SslCertFile => {
-                    if key_event.modifiers.contains(KeyModifiers::CONTROL) && c == 'o' {
-                        if let Some(path) = App::select_ssl_certificate_file() {
-                            app.connection_form.ssl_cert_file = path;
-                        }
-                        return Ok(());
-                    }
-                }
-                ConnectionField::SslKeyFile => {
-                    if key_event.modifiers.contains(KeyModifiers::CONTROL) && c == 'o' {
-                        if let Some(path) = App::select_ssl_key_file() {
-                            app.connection_form.ssl_key_file = path;
-                        }
-                        return Ok(());
-                    }
-                }
-                ConnectionField::SslCaFile => {
-                    if key_event.modifiers.contains(KeyModifiers::CONTROL) && c == 'o' {
-                        if let Some(path) = App::select_ssl_ca_file() {
-                            app.connection_form.ssl_ca_file = path;
-                        }
-                        return Ok(());
-                    }
-                }
-                _ => {}
+            // Ctrl+O on file path fields opens the file browser
+            if key_event.modifiers.contains(KeyModifiers::CONTROL)
+                && c == 'o'
+                && open_field_file_browser(app)
+            {
+                return Ok(());
             }
 
-            // Handle regular character input
-            if c.is_ascii_graphic() || c.is_ascii_whitespace() {
-                let mut current_value = app.connection_form.get_current_field_value().to_string();
-                current_value.push(c);
-                app.connection_form.set_current_field_value(current_value);
-            }
-        }
-        KeyCode::Backspace => {
-            if !app.connection_form.is_toggle_field() {
-                let mut current_value = app.connection_form.get_current_field_value().to_string();
-                current_value.pop();
-                app.connection_form.set_current_field_value(current_value);
+            // Ctrl+T starts over from a template
+            if key_event.modifiers.contains(KeyModifiers::CONTROL) && c == 't' {
+                app.connection_form.template_picker = Some(0);
+                return Ok(());
             }
+
+            app.connection_form.handle_key(key_event);
         }
-        KeyCode::Left => {
-            // Could add cursor position tracking for connection fields in the future
-        }
-        KeyCode::Right => {
-            // Could add cursor position tracking for connection fields in the future
-        }
-        KeyCode::Home => {
-            // Could add cursor position tracking for connection fields in the future
+        _ => {
+            app.connection_form.handle_key(key_event);
         }
-        KeyCode::End => {
-            // Could add cursor position tracking for connection fields in the future
+    }
+    Ok(())
+}
+
+/// Choosing the template a new connection starts from; Esc keeps the form as
+/// it is.
+fn handle_template_picker_keys(app: &mut App, key_event: KeyEvent) {
+    let form = &mut app.connection_form;
+    let Some(selected) = form.template_picker else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Up | KeyCode::Char('k') => form.template_picker = Some(selected.saturating_sub(1)),
+        KeyCode::Down | KeyCode::Char('j') => {
+            form.template_picker = Some((selected + 1).min(ConnectionTemplate::ALL.len() - 1))
         }
+        KeyCode::Enter => form.apply_template(ConnectionTemplate::ALL[selected]),
+        KeyCode::Esc => form.template_picker = None,
         _ => {}
     }
-    Ok(())
 }
 
 fn handle_edit_connection_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
@@ -220,18 +1472,14 @@ fn handle_edit_connection_keys(app: &mut App, key_event: KeyEvent) -> Result<()>
                 app.connection_form.next_field();
             }
         }
-        KeyCode::Enter => {
-            if !app.connection_form.name.is_empty() {
-                match app.save_edited_connection() {
-                    Ok(()) => {
-                        app.status_message = Some("Connection updated successfully".to_string());
-                    }
-                    Err(e) => {
-                        app.error_message = Some(format!("Failed to update connection: {}", e));
-                    }
-                }
+        KeyCode::Enter => match app.save_edited_connection() {
+            Ok(()) => {
+                app.status_message = Some("Connection updated successfully".to_string());
             }
-        }
+            Err(e) => {
+                app.error_message = Some(format!("Failed to update connection: {}", e));
+            }
+        },
         KeyCode::Esc => {
             app.current_screen = AppScreen::ConnectionList;
             app.editing_connection_index = None; // Reset editing state
@@ -240,101 +1488,159 @@ fn handle_edit_connection_keys(app: &mut App, key_event: KeyEvent) -> Result<()>
             // Handle toggle fields
             if app.connection_form.is_toggle_field() {
                 match app.connection_form.current_field {
-                    ConnectionField::UseSsl => {
-                        if c == 'y' || c == 'Y' || c == ' ' || c == '\n' {
-                            app.connection_form.toggle_ssl();
-                        }
+                    ConnectionField::UseSsl if c == 'y' || c == 'Y' || c == ' ' || c == '\n' => {
+                        app.connection_form.toggle_ssl();
+                    }
+                    ConnectionField::SslMode if c == ' ' || c == '\n' => {
+                        app.connection_form.cycle_ssl_mode();
                     }
-                    ConnectionField::SslMode => {
-                        if c == ' ' || c == '\n' {
-                            app.connection_form.cycle_ssl_mode();
-                        }
+                    ConnectionField::DatabaseType if c == ' ' || c == '\n' => {
+                        app.connection_form.cycle_database_type();
                     }
-                    ConnectionField::DatabaseType => {
-                        if c == ' ' || c == '\n' {
-                            app.connection_form.cycle_database_type();
-                        }
+                    ConnectionField::Color if c == ' ' => {
+                        app.connection_form.color =
+                            ConnectionColor::cycle(app.connection_form.color);
+                    }
+                    ConnectionField::ReadOnly if c == 'y' || c == 'Y' || c == ' ' => {
+                        app.connection_form.read_only = !app.connection_form.read_only;
                     }
                     _ => {}
                 }
                 return Ok(());
             }
 
-            // Handle file selection shortcuts
-            #[cfg(not(target_arch = "wasm32"))]
-            match app.connection_form.current_field {
-                ConnectionField::SslCertFile => {
-                    if key_event.modifiers.contains(KeyModifiers::CONTROL) && c == 'o' {
-                        if let Some(path) = crate::app::App::select_ssl_certificate_file() {
-                            app.connection_form.ssl_cert_file = path;
-                        }
-                        return Ok(());
-                    }
-                }
-                ConnectionField::SslKeyFile => {
-                    if key_event.modifiers.contains(KeyModifiers::CONTROL) && c == 'o' {
-                        if let Some(path) = crate::app::App::select_ssl_key_file() {
-                            app.connection_form.ssl_key_file = path;
-                        }
-                        return Ok(());
-                    }
-                }
-                ConnectionField::SslCaFile => {
-                    if key_event.modifiers.contains(KeyModifiers::CONTROL) && c == 'o' {
-                        if let Some(path) = crate::app::App::select_ssl_ca_file() {
-                            app.connection_form.ssl_ca_file = path;
-                        }
-                        return Ok(());
-                    }
-                }
-                _ => {}
+            // Ctrl+O on file path fields opens the file browser
+            if key_event.modifiers.contains(KeyModifiers::CONTROL)
+                && c == 'o'
+                && open_field_file_browser(app)
+            {
+                return Ok(());
             }
 
-            // Handle regular character input
-            if c.is_ascii_graphic() || c.is_ascii_whitespace() {
-                let mut current_value = app.connection_form.get_current_field_value().to_string();
-                current_value.push(c);
-                app.connection_form.set_current_field_value(current_value);
-            }
+            app.connection_form.handle_key(key_event);
         }
-        KeyCode::Backspace => {
-            if !app.connection_form.is_toggle_field() {
-                let mut current_value = app.connection_form.get_current_field_value().to_string();
-                current_value.pop();
-                app.connection_form.set_current_field_value(current_value);
-            }
+        _ => {
+            app.connection_form.handle_key(key_event);
         }
-        _ => {}
     }
     Ok(())
 }
 
 async fn handle_table_browser_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
+    if app.table_filter_active {
+        return handle_table_filter_keys(app, key_event).await;
+    }
+
     match key_event.code {
+        KeyCode::Esc if !app.table_filter.is_empty() => {
+            app.clear_table_filter();
+        }
         KeyCode::Esc => {
             app.current_screen = AppScreen::ConnectionList;
         }
-        KeyCode::Up => {
-            app.previous_table();
-            if let Err(e) = app.refresh_table_columns().await {
-                app.error_message = Some(format!("Failed to load columns: {}", e));
+        KeyCode::Char('/') => {
+            app.table_filter_active = true;
+        }
+        KeyCode::Up if app.previous_table() => {
+            refresh_selected_table_columns(app).await;
+        }
+        KeyCode::Down if app.next_table() => {
+            refresh_selected_table_columns(app).await;
+        }
+        // Schema tree: → or Enter expands (→ again enters it), ← collapses or
+        // goes up from a table to its schema
+        KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') if app.selected_schema.is_some() => {
+            let index = app.selected_schema.unwrap_or_default();
+            let expanded = app
+                .schema_tree
+                .as_ref()
+                .is_some_and(|tree| tree[index].expanded);
+            if !expanded {
+                let _ = app.expand_schema(index).await;
+            } else if key_event.code == KeyCode::Right {
+                if app.next_table() {
+                    refresh_selected_table_columns(app).await;
+                }
+            } else {
+                app.collapse_schema(index);
             }
         }
-        KeyCode::Down => {
-            app.next_table();
-            if let Err(e) = app.refresh_table_columns().await {
-                app.error_message = Some(format!("Failed to load columns: {}", e));
+        KeyCode::Left if app.table_filter.is_empty() => {
+            if let Some(index) = app.selected_schema {
+                app.collapse_schema(index);
+            } else if let Some(index) = app.selected_table_schema() {
+                app.select_schema(index);
             }
         }
         KeyCode::Char('s') => {
+            app.remember_recent_table();
             let query = app.generate_select_query();
-            app.query_input = query;
-            app.query_cursor_position = app.query_input.len();
+            app.query.set(query);
             app.current_screen = AppScreen::QueryEditor;
         }
         KeyCode::Char('q') => {
             app.current_screen = AppScreen::QueryEditor;
         }
+        KeyCode::Char('e') => {
+            app.open_er_map().await;
+        }
+        KeyCode::Char('f') => {
+            app.open_routines().await;
+        }
+        KeyCode::Char('i') => {
+            app.toggle_table_stats().await;
+        }
+        KeyCode::Char('c') => app.open_copy_wizard(),
+        KeyCode::Char('x') => app.open_index_manager().await,
+        KeyCode::Char('C') => app.open_comment_editor().await,
+        KeyCode::Char('L') => app.open_column_editor(),
+        KeyCode::Char('S') => app.open_seed_plan().await,
+        KeyCode::Char('R') => app.prompt_table_action(PromptKind::RenameTable),
+        KeyCode::Char('W') => app.prompt_table_action(PromptKind::TruncateTable),
+        KeyCode::Char('X') | KeyCode::Delete => app.prompt_table_action(PromptKind::DropTable),
+        KeyCode::Char('P') => app.open_table_profile(),
+        KeyCode::Char('a') => app.open_table_aggregate(),
+        KeyCode::Char('A') => app.open_attach_database(),
+        KeyCode::Char('U') => {
+            if let Err(e) = app.detach_selected_database().await {
+                app.error_message = Some(format!("Failed to detach the database: {}", e));
+            }
+        }
+        KeyCode::Char('n') => app.count_selected_table_rows(),
+        KeyCode::Char('*') => app.toggle_favorite_table(),
+        KeyCode::Char('o') => app.open_table_ddl_in_pager().await,
+        KeyCode::Char('!') => app.open_native_client(),
+        KeyCode::Char('B') => app.open_dashboard(),
+        KeyCode::Char('G') => app.open_roles().await,
+        KeyCode::Char('T') => app.open_storage().await,
+        KeyCode::Char('F') => app.toggle_favorites_only(),
+        KeyCode::Char('d') => {
+            if let Some(table) = app.get_selected_table() {
+                let default_path = format!("{}.sql", table.name);
+                app.open_file_browser(FileBrowserTarget::TableDump, &default_path);
+            }
+        }
+        KeyCode::Char('E') => {
+            if let Some(table) = app.get_selected_table() {
+                let default_path = format!("{}.csv", table.name);
+                app.open_file_browser(FileBrowserTarget::TableCsvExport, &default_path);
+            }
+        }
+        KeyCode::Char('I') if app.get_selected_table().is_some() => {
+            app.open_file_browser(FileBrowserTarget::TableCsvImport, "");
+        }
+        KeyCode::Char('D') => {
+            let name = app
+                .current_connection
+                .and_then(|i| app.connections.get(i))
+                .map_or("database".to_string(), |c| c.name.replace(' ', "_"));
+            let default_path = format!(
+                "{}-{}.sql",
+                name,
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            );
+            app.open_file_browser(FileBrowserTarget::DatabaseDump, &default_path);
+        }
         KeyCode::Char('r') => {
             if let Err(e) = app.refresh_tables().await {
                 app.error_message = Some(format!("Failed to refresh tables: {}", e));
@@ -345,57 +1651,81 @@ async fn handle_table_browser_keys(app: &mut App, key_event: KeyEvent) -> Result
     Ok(())
 }
 
+/// Typing into the table filter: the list narrows with every keystroke and the
+/// best match is selected. Enter keeps the filter, Esc clears it.
+async fn handle_table_filter_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
+    let changed = match key_event.code {
+        KeyCode::Esc => {
+            app.clear_table_filter();
+            false
+        }
+        KeyCode::Enter => {
+            app.table_filter_active = false;
+            false
+        }
+        KeyCode::Up => app.previous_table(),
+        KeyCode::Down => app.next_table(),
+        _ => app.table_filter.handle_key(key_event) && app.apply_table_filter(),
+    };
+    if changed {
+        refresh_selected_table_columns(app).await;
+    }
+    Ok(())
+}
+
+async fn refresh_selected_table_columns(app: &mut App) {
+    if let Err(e) = app.refresh_table_columns().await {
+        app.error_message = Some(format!("Failed to load columns: {}", e));
+    }
+}
+
 async fn handle_query_editor_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
+    // Vim mode: normal mode commands, Esc leaves insert mode. Ctrl/Alt shortcuts
+    // and non-character keys work the same in both modes
+    if app.settings.vim_mode
+        && !key_event
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    {
+        if app.editor_mode == EditorMode::Normal {
+            // Enter runs the query in normal mode
+            if key_event.code == KeyCode::Enter {
+                execute_query_input(app).await;
+                return Ok(());
+            }
+            if handle_vim_normal_keys(app, key_event) {
+                return Ok(());
+            }
+        } else if key_event.code == KeyCode::Esc {
+            app.editor_mode = EditorMode::Normal;
+            app.query.cursor = vim::left(&app.query.text, app.query.cursor);
+            return Ok(());
+        }
+    }
+
     match key_event.code {
         KeyCode::Esc => {
             app.current_screen = AppScreen::TableBrowser;
         }
         KeyCode::Enter if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
-            if !app.query_input.trim().is_empty() {
-                app.status_message = Some("Executing query...".to_string());
-                match app.execute_query(&app.query_input.clone()).await {
-                    Ok(_) => {
-                        app.status_message = Some("Query executed successfully!".to_string());
-                        // Force a small delay to show the success message
-                        tokio::time::timeout(
-                            tokio::time::Duration::from_millis(500),
-                            tokio::time::sleep(tokio::time::Duration::from_millis(500)),
-                        )
-                        .await
-                        .ok();
-                    }
-                    Err(e) => {
-                        app.error_message = Some(format!("Query execution failed: {}", e));
-                        app.status_message = None;
-                    }
-                }
+            execute_query_input(app).await;
+        }
+        KeyCode::Char('z') | KeyCode::Char('Z')
+            if key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            // Ctrl+Z undoes, Ctrl+Shift+Z redoes like Ctrl+Y
+            if key_event.modifiers.contains(KeyModifiers::SHIFT) {
+                app.redo_query_edit();
             } else {
-                app.error_message = Some("Cannot execute empty query".to_string());
+                app.undo_query_edit();
             }
         }
+        KeyCode::Char('y') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.redo_query_edit();
+        }
         KeyCode::Char('e') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
             // Alternative: Ctrl+E to execute query
-            if !app.query_input.trim().is_empty() {
-                app.status_message = Some("Executing query...".to_string());
-                match app.execute_query(&app.query_input.clone()).await {
-                    Ok(_) => {
-                        app.status_message = Some("Query executed successfully!".to_string());
-                        // Force a small delay to show the success message
-                        tokio::time::timeout(
-                            tokio::time::Duration::from_millis(500),
-                            tokio::time::sleep(tokio::time::Duration::from_millis(500)),
-                        )
-                        .await
-                        .ok();
-                    }
-                    Err(e) => {
-                        app.error_message = Some(format!("Query execution failed: {}", e));
-                        app.status_message = None;
-                    }
-                }
-            } else {
-                app.error_message = Some("Cannot execute empty query".to_string());
-            }
+            execute_query_input(app).await;
         }
 
         // SQL Generation Shortcuts (must come before general character handler)
@@ -403,61 +1733,57 @@ async fn handle_query_editor_keys(app: &mut App, key_event: KeyEvent) -> Result<
             if key_event.modifiers.contains(KeyModifiers::CONTROL) {
                 // Ctrl+S: Generate SELECT * for current table
                 if let Some(table) = app.get_selected_table() {
-                    let query = app.generate_select_star_statement(&table.name, Some(100));
-                    app.query_input = query;
-                    app.query_cursor_position = app.query_input.len();
+                    let query =
+                        app.generate_select_star_statement(&table.qualified_name(), Some(100));
+                    app.query.set(query);
                 }
             } else {
-                app.insert_char_in_query('s');
+                app.query.insert_char('s');
             }
         }
         KeyCode::Char('i') => {
             if key_event.modifiers.contains(KeyModifiers::CONTROL) {
                 // Ctrl+I: Generate INSERT statement
-                if let Some(table) = app.get_selected_table() {
-                    if !app.table_columns.is_empty() {
-                        let sample_values = vec!["'value1'".to_string(), "'value2'".to_string()];
-                        let column_names = app
-                            .table_columns
-                            .iter()
-                            .map(|c| c.name.clone())
-                            .collect::<Vec<_>>();
-                        let query = app.generate_insert_statement(
-                            &table.name,
-                            &column_names,
-                            &sample_values,
-                        );
-                        app.query_input = query;
-                        app.query_cursor_position = app.query_input.len();
-                    }
+                if let Some(table) = app.get_selected_table()
+                    && !app.table_columns.is_empty()
+                {
+                    let column_names = app
+                        .table_columns
+                        .iter()
+                        .map(|c| c.name.clone())
+                        .collect::<Vec<_>>();
+                    let query =
+                        app.generate_insert_statement(&table.qualified_name(), &column_names);
+                    app.query.set(query);
                 }
             } else {
-                app.insert_char_in_query('i');
+                app.query.insert_char('i');
             }
         }
         KeyCode::Char('d') => {
             if key_event.modifiers.contains(KeyModifiers::CONTROL) {
                 // Ctrl+D: Generate DELETE statement
                 if let Some(table) = app.get_selected_table() {
-                    let query = app.generate_delete_statement(&table.name, None);
-                    app.query_input = query;
-                    app.query_cursor_position = app.query_input.len();
+                    let query = app.generate_delete_statement(&table.qualified_name(), None);
+                    app.query.set(query);
                 }
             } else {
-                app.insert_char_in_query('d');
+                app.query.insert_char('d');
             }
         }
         KeyCode::Char('u') => {
             if key_event.modifiers.contains(KeyModifiers::CONTROL) {
                 // Ctrl+U: Generate UPDATE statement
                 if let Some(table) = app.get_selected_table() {
-                    let query =
-                        app.generate_update_statement(&table.name, "column1 = 'new_value'", None);
-                    app.query_input = query;
-                    app.query_cursor_position = app.query_input.len();
+                    let query = app.generate_update_statement(
+                        &table.qualified_name(),
+                        "column1 = 'new_value'",
+                        None,
+                    );
+                    app.query.set(query);
                 }
             } else {
-                app.insert_char_in_query('u');
+                app.query.insert_char('u');
             }
         }
         KeyCode::Char('c') => {
@@ -472,118 +1798,531 @@ async fn handle_query_editor_keys(app: &mut App, key_event: KeyEvent) -> Result<
                             &format!("{}_copy", table.name),
                             &app.table_columns,
                         );
-                        app.query_input = query;
-                        app.query_cursor_position = app.query_input.len();
+                        app.query.set(query);
                     }
                 }
             } else {
-                app.insert_char_in_query('c');
+                app.query.insert_char('c');
             }
         }
         KeyCode::Char('t') => {
             if key_event.modifiers.contains(KeyModifiers::CONTROL) {
                 // Ctrl+T: Generate TRUNCATE statement
                 if let Some(table) = app.get_selected_table() {
-                    let query = app.generate_truncate_statement(&table.name);
-                    app.query_input = query;
-                    app.query_cursor_position = app.query_input.len();
+                    let query = app.generate_truncate_statement(&table.qualified_name());
+                    app.query.set(query);
                 }
             } else {
                 // Regular 't': Test query
-                app.query_input = "SELECT 1 as test;".to_string();
-                app.query_cursor_position = app.query_input.len();
+                app.query.set("SELECT 1 as test;".to_string());
                 app.status_message =
                     Some("Test query loaded. Press Enter or Ctrl+Enter to execute".to_string());
             }
         }
-        KeyCode::Char(c) => {
-            // Only allow printable characters and common SQL characters
-            if c.is_ascii_graphic()
-                || c.is_ascii_whitespace()
-                || c == ';'
-                || c == ','
-                || c == '('
-                || c == ')'
-            {
-                app.insert_char_in_query(c);
-            }
+        KeyCode::Char('b') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            // Ctrl+B: Toggle split view (editor above results)
+            app.toggle_split_view();
         }
-        KeyCode::Backspace => {
-            app.delete_char_in_query();
+        KeyCode::Up
+            if app.settings.split_view && key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            app.resize_split_view(-5);
         }
-        KeyCode::Left => {
-            app.move_cursor_left();
+        KeyCode::Down
+            if app.settings.split_view && key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            app.resize_split_view(5);
         }
-        KeyCode::Right => {
-            app.move_cursor_right();
+        // Split view: navigate the results pane without leaving the editor
+        KeyCode::PageUp if app.settings.split_view => {
+            app.previous_page();
         }
-        KeyCode::Home => {
-            app.move_cursor_to_start();
+        KeyCode::PageDown if app.settings.split_view => {
+            app.load_rows_for_page(app.current_page + 1).await;
+            app.next_page();
         }
-        KeyCode::End => {
-            app.move_cursor_to_end();
+        KeyCode::Up
+            if app.settings.split_view && key_event.modifiers.contains(KeyModifiers::ALT) =>
+        {
+            app.previous_row();
+        }
+        KeyCode::Down
+            if app.settings.split_view && key_event.modifiers.contains(KeyModifiers::ALT) =>
+        {
+            app.next_row();
+        }
+        KeyCode::Left
+            if app.settings.split_view && key_event.modifiers.contains(KeyModifiers::ALT) =>
+        {
+            app.previous_column();
+        }
+        KeyCode::Right
+            if app.settings.split_view && key_event.modifiers.contains(KeyModifiers::ALT) =>
+        {
+            app.next_column();
+        }
+        KeyCode::Char('o') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            // Ctrl+O: Load a SQL file into the editor
+            app.open_file_browser(FileBrowserTarget::SqlFileOpen, "");
+        }
+        KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            // Ctrl+R: Run a SQL file statement by statement
+            app.open_file_browser(FileBrowserTarget::SqlFileRun, "");
+        }
+        KeyCode::Char('l') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            // Ctrl+L: Insert a date/time literal using the picker
+            app.open_date_picker(DatePickerTarget::QueryEditor, None);
+        }
+        KeyCode::Char('g') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            // Ctrl+G: Edit the query in $EDITOR
+            app.open_query_in_editor();
+        }
+        KeyCode::Char('k') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            // Ctrl+K: Generate SQL from a question with the assistant
+            app.open_assistant();
         }
         KeyCode::Enter => {
             // Check if this is a single line query (no newlines)
-            if !app.query_input.contains('\n') && !app.query_input.trim().is_empty() {
+            if !app.query.text.contains('\n') && !app.query.text.trim().is_empty() {
                 // Execute single-line query on Enter
-                app.status_message = Some("Executing query...".to_string());
-                match app.execute_query(&app.query_input.clone()).await {
-                    Ok(_) => {
-                        app.status_message = Some("Query executed successfully!".to_string());
-                        // Force a small delay to show the success message
-                        tokio::time::timeout(
-                            tokio::time::Duration::from_millis(500),
-                            tokio::time::sleep(tokio::time::Duration::from_millis(500)),
-                        )
-                        .await
-                        .ok();
-                    }
-                    Err(e) => {
-                        app.error_message = Some(format!("Query execution failed: {}", e));
-                        app.status_message = None;
-                    }
-                }
+                execute_query_input(app).await;
             } else {
                 // Insert newline for multi-line queries
-                app.insert_char_in_query('\n');
+                app.query.insert_char('\n');
             }
         }
         KeyCode::Tab => {
-            app.insert_char_in_query('\t');
+            app.query.insert_char('\t');
+        }
+        // Typing, Backspace/Delete and moving or selecting with ←/→/Home/End
+        _ => {
+            app.query.handle_key(key_event);
+        }
+    }
+    Ok(())
+}
+
+/// Handles a key in vim normal mode, returning false for keys left to the
+/// regular editor handler (Esc, arrows, paging).
+fn handle_vim_normal_keys(app: &mut App, key_event: KeyEvent) -> bool {
+    let c = match key_event.code {
+        KeyCode::Char(c) => c,
+        _ => return false,
+    };
+    // Motions and edits work on the cursor alone
+    app.query.anchor = None;
+    let text = &mut app.query.text;
+    let pos = app.query.cursor.min(text.len());
+
+    // Second key of a two-key command
+    if let Some(pending) = app.vim_pending.take() {
+        match (pending, c) {
+            ('d', 'd') => {
+                let (line, new_pos) = vim::delete_line(text, pos);
+                app.vim_register = line;
+                app.query.cursor = new_pos;
+            }
+            ('y', 'y') => {
+                app.vim_register = vim::yank_line(text, pos);
+                app.status_message = Some("Line yanked".to_string());
+            }
+            ('g', 'g') => app.query.cursor = 0,
+            _ => {}
+        }
+        return true;
+    }
+
+    app.query.cursor = match c {
+        'h' => vim::left(text, pos),
+        'l' => vim::right(text, pos),
+        'j' => vim::vertical(text, pos, false),
+        'k' => vim::vertical(text, pos, true),
+        'w' => vim::next_word(text, pos),
+        'b' => vim::previous_word(text, pos),
+        '0' => vim::line_start(text, pos),
+        '^' => vim::first_non_blank(text, pos),
+        '$' => vim::last_char(text, pos),
+        'G' => vim::line_start(text, text.len()),
+        'x' => vim::delete_char(text, pos),
+        'u' => {
+            app.undo_query_edit();
+            return true;
+        }
+        'p' | 'P' if !app.vim_register.is_empty() => {
+            vim::paste_line(text, pos, &app.vim_register, c == 'p')
         }
-        KeyCode::Delete => {
-            // Delete character at cursor position
-            if app.query_cursor_position < app.query_input.len() {
-                app.query_input.remove(app.query_cursor_position);
+        'd' | 'y' | 'g' => {
+            app.vim_pending = Some(c);
+            pos
+        }
+        'i' | 'a' | 'I' | 'A' | 'o' | 'O' => {
+            app.editor_mode = EditorMode::Insert;
+            match c {
+                'a' => vim::next_char_start(text, pos).min(vim::line_end(text, pos)),
+                'I' => vim::first_non_blank(text, pos),
+                'A' => vim::line_end(text, pos),
+                'o' => vim::open_line(text, pos, true),
+                'O' => vim::open_line(text, pos, false),
+                _ => pos,
+            }
+        }
+        _ => pos, // Other keys are ignored rather than typed
+    };
+    true
+}
+
+/// Executes the query in the editor, asking for confirmation first when it contains
+/// destructive statements and the guard is enabled.
+async fn execute_query_input(app: &mut App) {
+    let query = app.query.text.clone();
+    if query.trim().is_empty() {
+        app.error_message = Some("Cannot execute empty query".to_string());
+        return;
+    }
+
+    let statements = if app.settings.confirm_destructive_statements {
+        find_destructive_statements(&query, app.dialect())
+    } else {
+        Vec::new()
+    };
+    let mut warnings = if app.settings.lint_queries {
+        app.lint_query(&query).await
+    } else {
+        Vec::new()
+    };
+    // Already listed as destructive statements
+    if !statements.is_empty() {
+        warnings.retain(|w| !matches!(w, LintWarning::MissingWhere(..)));
+    }
+    if !statements.is_empty() || !warnings.is_empty() {
+        app.pending_confirmation = Some(PendingConfirmation {
+            query,
+            statements,
+            warnings,
+            script: None,
+        });
+        return;
+    }
+
+    run_query(app, &query);
+}
+
+fn run_query(app: &mut App, query: &str) {
+    if let Err(e) = app.execute_query(query) {
+        app.error_message = Some(format!("Query execution failed: {}", e));
+        app.status_message = None;
+    }
+}
+
+fn handle_confirmation_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
+    match key_event.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+            if let Some(confirmation) = app.pending_confirmation.take() {
+                match confirmation.script {
+                    Some((path, statements)) => app.start_script(path, statements),
+                    None => run_query(app, &confirmation.query),
+                }
             }
         }
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+            app.pending_confirmation = None;
+            app.status_message = Some("Query cancelled".to_string());
+        }
         _ => {}
     }
     Ok(())
 }
 
-fn handle_query_results_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
+fn handle_session_offer_keys(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => app.restore_session(),
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.decline_session_restore(),
+        _ => {}
+    }
+}
+
+fn handle_jobs_panel_keys(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Esc | KeyCode::F(6) => app.show_jobs = false,
+        KeyCode::Up | KeyCode::Char('k') => app.move_job_selection(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.move_job_selection(1),
+        KeyCode::Enter => app.view_selected_job_result(),
+        KeyCode::Char('e') => app.edit_selected_job_query(),
+        KeyCode::Char('x') => app.cancel_selected_job(),
+        KeyCode::Char('c') => app.clear_finished_jobs(),
+        _ => {}
+    }
+}
+
+/// Scrolls the help popup; the drawing keeps the scroll within its lines.
+fn handle_help_keys(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('h') | KeyCode::Char('q') | KeyCode::F(1) => app.toggle_help(),
+        KeyCode::Up | KeyCode::Char('k') => app.help_scroll = app.help_scroll.saturating_sub(1),
+        KeyCode::Down | KeyCode::Char('j') => app.help_scroll += 1,
+        KeyCode::PageUp => app.help_scroll = app.help_scroll.saturating_sub(10),
+        KeyCode::PageDown => app.help_scroll += 10,
+        KeyCode::Home => app.help_scroll = 0,
+        KeyCode::End => app.help_scroll = usize::MAX,
+        _ => {}
+    }
+}
+
+fn handle_notifications_keys(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('m') | KeyCode::Char('M') => app.show_notifications = false,
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.notification_scroll = app.notification_scroll.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Char('j')
+            if app.notification_scroll + 1 < app.notifications.len() =>
+        {
+            app.notification_scroll += 1;
+        }
+        KeyCode::Char('c') => {
+            app.notifications.clear();
+            app.notification_scroll = 0;
+        }
+        _ => {}
+    }
+}
+
+fn handle_statistics_keys(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Esc => app.close_statistics(),
+        KeyCode::Left => app.cycle_statistics_connection(false),
+        KeyCode::Right => app.cycle_statistics_connection(true),
+        KeyCode::Tab => {
+            app.statistics_view = match app.statistics_view {
+                StatisticsView::Slowest => StatisticsView::MostFrequent,
+                StatisticsView::MostFrequent => StatisticsView::Slowest,
+            };
+            app.statistics_scroll = 0;
+        }
+        KeyCode::Up => {
+            app.statistics_scroll = app.statistics_scroll.saturating_sub(1);
+        }
+        KeyCode::Down => {
+            let len = app
+                .statistics
+                .get(app.statistics_connection_index)
+                .map(|s| match app.statistics_view {
+                    StatisticsView::Slowest => s.slowest.len(),
+                    StatisticsView::MostFrequent => s.most_frequent.len(),
+                })
+                .unwrap_or(0);
+            if app.statistics_scroll + 1 < len {
+                app.statistics_scroll += 1;
+            }
+        }
+        KeyCode::Char('r') => app.open_statistics(),
+        _ => {}
+    }
+}
+
+async fn handle_er_map_keys(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Esc => app.current_screen = AppScreen::TableBrowser,
+        KeyCode::Tab => app.toggle_er_view(),
+        KeyCode::Up => app.scroll_er(-1),
+        KeyCode::Down => app.scroll_er(1),
+        KeyCode::PageUp => app.scroll_er(-20),
+        KeyCode::PageDown => app.scroll_er(20),
+        KeyCode::Home => app.er_scroll = 0,
+        KeyCode::End => app.scroll_er(isize::MAX),
+        KeyCode::Char('r') => app.open_er_map().await,
+        _ => {}
+    }
+}
+
+async fn handle_routines_keys(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Esc => app.current_screen = AppScreen::TableBrowser,
+        KeyCode::Up => app.select_routine(app.selected_routine_index.saturating_sub(1)),
+        KeyCode::Down => app.select_routine(app.selected_routine_index + 1),
+        KeyCode::Home => app.select_routine(0),
+        KeyCode::End => app.select_routine(usize::MAX),
+        KeyCode::PageUp => app.scroll_routine_source(-10),
+        KeyCode::PageDown => app.scroll_routine_source(10),
+        KeyCode::Enter | KeyCode::Char('c') => app.scaffold_routine_call(),
+        KeyCode::Char('r') => app.open_routines().await,
+        _ => {}
+    }
+}
+
+async fn handle_roles_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(browser) = &mut app.role_browser else {
+        return;
+    };
     match key_event.code {
+        KeyCode::Esc => app.close_roles(),
+        KeyCode::Up => browser.select(browser.selected.saturating_sub(1)),
+        KeyCode::Down => browser.select(browser.selected + 1),
+        KeyCode::Home => browser.select(0),
+        KeyCode::End => browser.select(usize::MAX),
+        KeyCode::PageUp => browser.detail_scroll = browser.detail_scroll.saturating_sub(10),
+        KeyCode::PageDown => browser.detail_scroll += 10,
+        KeyCode::Char('w') => app.toggle_role_writers_only(),
+        KeyCode::Char('g') => app.open_grant_builder(),
+        KeyCode::Char('n') => app.open_user_form(),
+        KeyCode::Char('p') => app.open_password_form(),
+        KeyCode::Char('r') => app.open_roles().await,
+        _ => {}
+    }
+}
+
+async fn handle_storage_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(overview) = &mut app.storage else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Esc => app.close_storage(),
+        KeyCode::Up => overview.select(overview.selected.saturating_sub(1)),
+        KeyCode::Down => overview.select(overview.selected + 1),
+        KeyCode::Home => overview.select(0),
+        KeyCode::End => overview.select(usize::MAX),
+        KeyCode::PageUp => overview.relation_scroll = overview.relation_scroll.saturating_sub(10),
+        KeyCode::PageDown => overview.relation_scroll += 10,
+        KeyCode::Char('r') => app.open_storage().await,
+        _ => {}
+    }
+}
+
+fn handle_chart_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(view) = &mut app.chart_view else {
+        return;
+    };
+    match key_event.code {
+        KeyCode::Esc => app.current_screen = AppScreen::QueryResults,
+        KeyCode::Up if view.selected > 0 => view.selected -= 1,
+        KeyCode::Down if view.selected + 1 < view.numeric.len() => view.selected += 1,
+        KeyCode::Char(' ') => app.toggle_chart_column(),
+        KeyCode::Char('x') => view.set_x_column(),
+        KeyCode::Char('t') => view.toggle_kind(),
+        _ => {}
+    }
+}
+
+async fn handle_server_monitor_keys(app: &mut App, key_event: KeyEvent) {
+    // Any key but a second x keeps the session marked for termination alive
+    if key_event.code != KeyCode::Char('x') && app.pending_terminate.take().is_some() {
+        app.status_message = Some("Terminate cancelled".to_string());
+    }
+    match key_event.code {
+        KeyCode::Esc => app.close_server_monitor(),
+        KeyCode::Up => {
+            app.selected_session_index = app.selected_session_index.saturating_sub(1);
+        }
+        KeyCode::Down if app.selected_session_index + 1 < app.server_sessions.len() => {
+            app.selected_session_index += 1;
+        }
+        KeyCode::Char('r') => {
+            app.refresh_server_sessions().await;
+        }
+        KeyCode::Char('a') => app.toggle_monitor_auto_refresh(),
+        KeyCode::Char('c') => app.cancel_selected_session().await,
+        KeyCode::Char('x') => app.terminate_selected_session().await,
+        _ => {}
+    }
+}
+
+/// Tiles are laid out in two columns, so ↑↓ move a row and ←→ a tile.
+fn handle_dashboard_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(dashboard) = &mut app.dashboard else {
+        return;
+    };
+    if key_event.code != KeyCode::Char('d') {
+        dashboard.confirming_delete = false;
+    }
+    match key_event.code {
+        KeyCode::Esc => app.close_dashboard(),
+        KeyCode::Up => dashboard.move_selection(-2),
+        KeyCode::Down => dashboard.move_selection(2),
+        KeyCode::Left => dashboard.move_selection(-1),
+        KeyCode::Right => dashboard.move_selection(1),
+        KeyCode::Char('r') => dashboard.refresh_all(),
+        KeyCode::Char('R') => app.reload_dashboard(),
+        KeyCode::Char('a') => app.prompt_tile_title(),
+        KeyCode::Char('t') => app.toggle_tile_kind(),
+        KeyCode::Char('!') => app.prompt_tile_alert(),
+        KeyCode::Char('+') | KeyCode::Char('=') => app.change_tile_refresh(5),
+        KeyCode::Char('-') => app.change_tile_refresh(-5),
+        KeyCode::Char('d') => app.delete_selected_tile(),
+        KeyCode::Enter => app.edit_tile_query(),
+        _ => {}
+    }
+}
+
+async fn handle_pragma_keys(app: &mut App, key_event: KeyEvent) {
+    let Some(dashboard) = &mut app.pragma_dashboard else {
+        return;
+    };
+    match key_event.code {
+        // Esc first backs out of a value waiting to be confirmed
+        KeyCode::Esc if dashboard.confirming || dashboard.proposed.is_some() => {
+            dashboard.proposed = None;
+            dashboard.confirming = false;
+            app.status_message = Some("Change cancelled".to_string());
+        }
+        KeyCode::Esc => app.close_pragma_dashboard(),
+        KeyCode::Up => dashboard.move_selection(-1),
+        KeyCode::Down => dashboard.move_selection(1),
+        KeyCode::Left => dashboard.cycle_proposed(false),
+        KeyCode::Right => dashboard.cycle_proposed(true),
+        KeyCode::Enter => app.confirm_pragma().await,
+        KeyCode::Char('i') => app.start_integrity_check(),
+        KeyCode::Char('r') => {
+            app.refresh_pragmas().await;
+        }
+        _ => {}
+    }
+}
+
+async fn handle_query_results_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
+    match key_event.code {
+        KeyCode::Char('b') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_split_view();
+        }
+        // Like the editor's generation shortcuts, but matching the selected row
+        KeyCode::Char('s') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.scaffold_row_statement(RowStatement::Select).await;
+        }
+        KeyCode::Char('u') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.scaffold_row_statement(RowStatement::Update).await;
+        }
+        KeyCode::Char('d') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.scaffold_row_statement(RowStatement::Delete).await;
+        }
         KeyCode::Esc => {
             app.current_screen = AppScreen::QueryEditor;
         }
-        KeyCode::Up => {
-            // First try to navigate rows, then scroll if at top
-            if app.selected_row_index > 0 {
-                app.previous_row();
-            } else if app.result_scroll_y > 0 {
-                app.result_scroll_y -= 1;
+        KeyCode::Char('d') => {
+            app.toggle_diff_mode();
+        }
+        KeyCode::Char('c') => {
+            app.status_message = Some("Counting rows...".to_string());
+            app.compute_exact_count().await;
+        }
+        KeyCode::Char('m') => {
+            app.fetch_more_rows().await;
+        }
+        KeyCode::Tab => app.cycle_result_set(true),
+        KeyCode::BackTab => app.cycle_result_set(false),
+        // In diff mode the arrows scroll through the changed rows
+        KeyCode::Up if app.diff_mode => {
+            app.diff_scroll = app.diff_scroll.saturating_sub(1);
+        }
+        KeyCode::Down if app.diff_mode => {
+            let change_count = app.current_diff().map(|d| d.changes.len()).unwrap_or(0);
+            if app.diff_scroll + 1 < change_count {
+                app.diff_scroll += 1;
             }
         }
+        KeyCode::Up => {
+            app.previous_row();
+        }
         KeyCode::Down => {
-            // First try to navigate rows, then scroll if at bottom
-            let current_results = app.get_current_page_results();
-            if app.selected_row_index < current_results.len().saturating_sub(1) {
-                app.next_row();
-            } else if app.result_scroll_y < current_results.len().saturating_sub(1) {
-                app.result_scroll_y += 1;
-            }
+            app.next_row();
         }
         KeyCode::Left => {
             app.previous_column();
@@ -595,6 +2334,7 @@ fn handle_query_results_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
             app.previous_page();
         }
         KeyCode::PageDown => {
+            app.load_rows_for_page(app.current_page + 1).await;
             app.next_page();
         }
         KeyCode::Home => {
@@ -610,17 +2350,61 @@ fn handle_query_results_keys(app: &mut App, key_event: KeyEvent) -> Result<()> {
                 app.current_page = app.get_total_pages().saturating_sub(1);
                 let current_results = app.get_current_page_results();
                 app.selected_row_index = current_results.len().saturating_sub(1);
-                app.result_scroll_y = current_results.len().saturating_sub(1);
+                app.scroll_to_selected_row();
             }
         }
-        KeyCode::Char('h') => {
+        KeyCode::Char('h') | KeyCode::Char('0') => {
             app.selected_column_index = 0;
         }
-        KeyCode::Char('l') => {
+        KeyCode::Char('l') | KeyCode::Char('$') => {
             if let Some(result) = &app.current_query_result {
                 app.selected_column_index = result.columns.len().saturating_sub(1);
             }
         }
+        KeyCode::Char('p') => {
+            app.toggle_privacy_mode();
+        }
+        KeyCode::Char('P') => app.open_result_profile(),
+        KeyCode::Char('a') => app.open_result_aggregate(),
+        KeyCode::Char('f') => app.open_column_filter(),
+        KeyCode::Char('F') => app.run_filter_on_server(),
+        KeyCode::Char('R') => app.rerun_last_query(),
+        KeyCode::Char('o') => app.open_cell_in_pager(),
+        KeyCode::Char('O') => app.open_result_in_pager(),
+        KeyCode::Char('g') => app.open_chart(),
+        KeyCode::Enter => app.open_cell_viewer(),
+        KeyCode::Char('v') => {
+            app.toggle_masked_values_revealed();
+        }
+        KeyCode::Char('u') => {
+            app.toggle_humanize_units();
+        }
+        KeyCode::Char('b') => {
+            app.pin_current_result();
+        }
+        KeyCode::Char('y') => {
+            app.copy_page_as_markdown();
+        }
+        KeyCode::Char('I') => {
+            app.copy_row_as_insert();
+        }
+        KeyCode::Char('e') if app.current_query_result.is_some() => {
+            let default_path = format!(
+                "results-{}.csv",
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            );
+            app.open_file_browser(FileBrowserTarget::ResultExport, &default_path);
+        }
+        KeyCode::Char('D') => match app.current_diff() {
+            Ok(_) => {
+                let default_path =
+                    format!("diff-{}.csv", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+                app.open_file_browser(FileBrowserTarget::DiffExport, &default_path);
+            }
+            Err(e) => {
+                app.error_message = Some(format!("Cannot export diff: {}", e));
+            }
+        },
         _ => {}
     }
     Ok(())
@@ -0,0 +1,335 @@
+use crate::database::DatabaseType;
+use crate::dialect::Dialect;
+use crate::text;
+use anyhow::{Result, anyhow};
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_xlsxwriter::{Format, Workbook};
+use std::path::Path;
+
+// Rows an Excel worksheet holds, header included
+const XLSX_MAX_ROWS: usize = 1_048_576;
+
+// Integers with more digits lose precision as Excel numbers
+const XLSX_MAX_INTEGER_DIGITS: usize = 15;
+
+/// Output format for exported tabular data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Markdown,
+    Ascii,
+    Xlsx,
+    Inserts,
+}
+
+impl ExportFormat {
+    /// Picks the format from the file extension, defaulting to CSV.
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .as_deref()
+        {
+            Some("md") | Some("markdown") => ExportFormat::Markdown,
+            Some("txt") => ExportFormat::Ascii,
+            Some("xlsx") => ExportFormat::Xlsx,
+            Some("sql") => ExportFormat::Inserts,
+            _ => ExportFormat::Csv,
+        }
+    }
+}
+
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// A CSV field for a cell: NULL is an unquoted empty field and an empty
+/// string is `""`, the convention of PostgreSQL's `COPY`.
+pub fn csv_cell(value: Option<&str>) -> String {
+    match value {
+        None => String::new(),
+        Some("") => "\"\"".to_string(),
+        Some(value) => csv_field(value),
+    }
+}
+
+pub fn to_csv(columns: &[String], rows: &[Vec<Option<String>>]) -> String {
+    let mut out = String::new();
+    let header: Vec<String> = columns.iter().map(|c| csv_field(c)).collect();
+    out.push_str(&header.join(","));
+    out.push('\n');
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|v| csv_cell(v.as_deref())).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// Renders rows as a GitHub-flavored Markdown table.
+pub fn to_markdown(columns: &[String], rows: &[Vec<String>]) -> String {
+    let mut out = String::new();
+    let header: Vec<String> = columns.iter().map(|c| markdown_cell(c)).collect();
+    out.push_str(&format!("| {} |\n", header.join(" | ")));
+    out.push_str(&format!("|{}\n", " --- |".repeat(columns.len())));
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|v| markdown_cell(v)).collect();
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    out
+}
+
+fn ascii_cell(value: &str) -> String {
+    value.replace('\r', "").replace('\n', "\\n")
+}
+
+/// Renders rows as a plain ASCII box table, columns padded to their widest value.
+pub fn to_ascii_table(columns: &[String], rows: &[Vec<String>]) -> String {
+    let header: Vec<String> = columns.iter().map(|c| ascii_cell(c)).collect();
+    let body: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|v| ascii_cell(v)).collect())
+        .collect();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            body.iter()
+                .filter_map(|row| row.get(i))
+                .chain(std::iter::once(&header[i]))
+                .map(|v| text::width(v))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let border: String = widths
+        .iter()
+        .map(|w| format!("+{}", "-".repeat(w + 2)))
+        .collect::<String>()
+        + "+\n";
+    let line = |cells: &[String]| {
+        let padded: String = widths
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                let cell = cells.get(i).map(String::as_str).unwrap_or("");
+                format!("| {} ", text::pad(cell, *w))
+            })
+            .collect();
+        padded + "|\n"
+    };
+
+    let mut out = border.clone();
+    out.push_str(&line(&header));
+    out.push_str(&border);
+    for row in &body {
+        out.push_str(&line(row));
+    }
+    if !body.is_empty() {
+        out.push_str(&border);
+    }
+    out
+}
+
+/// Where INSERT statements write to: the table as the query named it, used
+/// as is, and the dialect the columns and values are quoted for.
+#[derive(Debug, Clone, Copy)]
+pub struct InsertTarget<'a> {
+    pub database_type: &'a DatabaseType,
+    pub table: &'a str,
+}
+
+/// Renders one INSERT statement per row, keeping NULLs unquoted.
+pub fn to_inserts(
+    target: InsertTarget,
+    columns: &[String],
+    rows: &[Vec<Option<String>>],
+) -> String {
+    let dialect = Dialect::of(target.database_type);
+    let column_list = columns
+        .iter()
+        .map(|c| dialect.quote_identifier(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut out = String::new();
+    for row in rows {
+        let values: Vec<String> = row
+            .iter()
+            .map(|v| match v {
+                Some(v) => dialect.string_literal(v),
+                None => "NULL".to_string(),
+            })
+            .collect();
+        out.push_str(&format!(
+            "INSERT INTO {} ({}) VALUES ({});\n",
+            target.table,
+            column_list,
+            values.join(", ")
+        ));
+    }
+    out
+}
+
+/// How a column's values are written to a spreadsheet cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CellKind {
+    Number,
+    Date,
+    DateTime,
+    Text,
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    let digits = value.trim_start_matches('-');
+    let is_numeric = !digits.is_empty()
+        && digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'));
+    // Leading zeros (zip codes, ids) and long integers stay text
+    let leading_zero = digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+    let long_integer =
+        digits.chars().all(|c| c.is_ascii_digit()) && digits.len() > XLSX_MAX_INTEGER_DIGITS;
+    if !is_numeric || leading_zero || long_integer {
+        return None;
+    }
+    value.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f"))
+        .ok()
+}
+
+fn cell_kind(value: &str) -> CellKind {
+    if parse_number(value).is_some() {
+        CellKind::Number
+    } else if parse_date(value).is_some() {
+        CellKind::Date
+    } else if parse_datetime(value).is_some() {
+        CellKind::DateTime
+    } else {
+        CellKind::Text
+    }
+}
+
+/// The kind shared by every non-NULL value of a column; dates mixed with
+/// timestamps are written as timestamps, and anything else mixed is text.
+fn column_kind(rows: &[Vec<Option<String>>], column: usize) -> CellKind {
+    let mut kind = None;
+    for value in rows.iter().filter_map(|row| row.get(column)?.as_deref()) {
+        kind = match (kind, cell_kind(value)) {
+            (None, k) => Some(k),
+            (Some(a), b) if a == b => Some(a),
+            (Some(CellKind::Date), CellKind::DateTime)
+            | (Some(CellKind::DateTime), CellKind::Date) => Some(CellKind::DateTime),
+            _ => return CellKind::Text,
+        };
+    }
+    kind.unwrap_or(CellKind::Text)
+}
+
+/// Writes rows as an Excel workbook: numbers and dates as typed cells, a bold
+/// frozen header row, and columns sized to their contents. NULLs are left empty.
+pub fn write_xlsx(path: &Path, columns: &[String], rows: &[Vec<Option<String>>]) -> Result<()> {
+    if rows.len() + 1 > XLSX_MAX_ROWS {
+        return Err(anyhow!(
+            "{} rows don't fit in an Excel sheet (at most {})",
+            rows.len(),
+            XLSX_MAX_ROWS - 1
+        ));
+    }
+
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    let header = Format::new().set_bold();
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    let datetime = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+
+    for (col, name) in columns.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, name, &header)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+
+    for col in 0..columns.len() {
+        let kind = column_kind(rows, col);
+        for (i, row) in rows.iter().enumerate() {
+            let (r, c) = (i as u32 + 1, col as u16);
+            let Some(value) = row.get(col).and_then(Option::as_deref) else {
+                continue;
+            };
+            match kind {
+                CellKind::Number => {
+                    sheet.write_number(r, c, parse_number(value).unwrap_or_default())?;
+                }
+                CellKind::Date | CellKind::DateTime => match parse_datetime(value) {
+                    Some(dt) => {
+                        sheet.write_datetime_with_format(r, c, dt, &datetime)?;
+                    }
+                    None => {
+                        let day = parse_date(value).unwrap_or_default();
+                        let format = if kind == CellKind::Date {
+                            &date
+                        } else {
+                            &datetime
+                        };
+                        sheet.write_datetime_with_format(r, c, day, format)?;
+                    }
+                },
+                CellKind::Text => {
+                    sheet.write_string(r, c, value)?;
+                }
+            }
+        }
+    }
+    sheet.autofit();
+
+    workbook.save(path)?;
+    Ok(())
+}
+
+/// Cells as displayed, NULLs as `NULL`.
+fn display_rows(rows: &[Vec<Option<String>>]) -> Vec<Vec<String>> {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|v| v.clone().unwrap_or_else(|| "NULL".to_string()))
+                .collect()
+        })
+        .collect()
+}
+
+/// Writes rows to `path` in the format its extension names, with NULL
+/// cells as `None`. `.sql` files get INSERT statements, which need
+/// `inserts_into`.
+pub fn write(
+    path: &Path,
+    columns: &[String],
+    rows: &[Vec<Option<String>>],
+    inserts_into: Option<InsertTarget>,
+) -> Result<()> {
+    match ExportFormat::from_path(path) {
+        ExportFormat::Csv => std::fs::write(path, to_csv(columns, rows))?,
+        ExportFormat::Markdown => std::fs::write(path, to_markdown(columns, &display_rows(rows)))?,
+        ExportFormat::Ascii => std::fs::write(path, to_ascii_table(columns, &display_rows(rows)))?,
+        ExportFormat::Xlsx => write_xlsx(path, columns, rows)?,
+        ExportFormat::Inserts => {
+            let target = inserts_into
+                .ok_or_else(|| anyhow!("INSERT statements can only be exported from results"))?;
+            std::fs::write(path, to_inserts(target, columns, rows))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,416 @@
+// Programs the app hands the terminal to: a pager for text too long to read in
+// a popup, an editor for the query, and the database's own command-line client
+// (psql, mysql or sqlite3) connected like the current connection. The main
+// loop suspends the screen while one runs and draws it again from scratch once
+// it exits; text is passed through a temporary file, read back from the editor
+// and removed afterwards. Passwords go to the clients through PGPASSWORD or a
+// MySQL option file rather than the command line, where `ps` would show them.
+// Desktop notifications go through notify-send or osascript in the background,
+// without the terminal.
+
+use crate::database::{ConnectionConfig, DatabaseType, SslConfig, SslMode};
+use crate::interpolate;
+use anyhow::{Result, anyhow};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Stdio};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExternalKind {
+    Pager,
+    Editor, // The temp file is read back into the query editor
+    Client,
+}
+
+/// A program waiting for the main loop to run it in the terminal.
+#[derive(Debug, Clone)]
+pub struct ExternalCommand {
+    pub kind: ExternalKind,
+    pub program: String,
+    pub args: Vec<String>,
+    pub envs: Vec<(String, String)>,
+    pub temp_file: Option<PathBuf>,
+}
+
+impl ExternalCommand {
+    /// Shows `text` in `$PAGER`, or `less` without one. Wide text keeps its
+    /// lines whole in `less`, scrolled sideways instead of wrapped.
+    pub fn pager(name: &str, text: &str, wide: bool) -> Result<Self> {
+        let path = write_private_file(name, text)?;
+        let (program, mut args) = match std::env::var("PAGER") {
+            Ok(pager) if !pager.trim().is_empty() => split_command(&pager)?,
+            _ if cfg!(windows) => ("more".to_string(), Vec::new()),
+            _ => {
+                let args = if wide {
+                    vec!["-S".to_string()]
+                } else {
+                    Vec::new()
+                };
+                ("less".to_string(), args)
+            }
+        };
+        args.push(path.to_string_lossy().to_string());
+        Ok(Self {
+            kind: ExternalKind::Pager,
+            program,
+            args,
+            envs: Vec::new(),
+            temp_file: Some(path),
+        })
+    }
+
+    /// Edits `text` in `$VISUAL` or `$EDITOR`, or `vi` without either.
+    pub fn editor(name: &str, text: &str) -> Result<Self> {
+        let path = write_private_file(name, text)?;
+        let editor = ["VISUAL", "EDITOR"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|editor| !editor.trim().is_empty());
+        let (program, mut args) = match editor {
+            Some(editor) => split_command(&editor)?,
+            None if cfg!(windows) => ("notepad".to_string(), Vec::new()),
+            None => ("vi".to_string(), Vec::new()),
+        };
+        args.push(path.to_string_lossy().to_string());
+        Ok(Self {
+            kind: ExternalKind::Editor,
+            program,
+            args,
+            envs: Vec::new(),
+            temp_file: Some(path),
+        })
+    }
+
+    /// The database's command-line client for a connection: its host, user,
+    /// database, SSL settings and the session settings the app applies on
+    /// connect.
+    pub fn native_client(config: &ConnectionConfig) -> Result<Self> {
+        let connection_string = interpolate::resolve(&config.connection_string)?;
+        let password = password(&connection_string);
+        match config.database_type {
+            DatabaseType::PostgreSQL => psql(config, &connection_string, password),
+            DatabaseType::MySQL => mysql(config, &connection_string, password),
+            DatabaseType::SQLite => sqlite3(config, &connection_string),
+        }
+    }
+
+    /// Runs the program in the terminal and waits for it to exit.
+    pub fn run(&self) -> Result<ExitStatus> {
+        Command::new(&self.program)
+            .args(&self.args)
+            .envs(self.envs.iter().map(|(k, v)| (k, v)))
+            .status()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => {
+                    anyhow!(
+                        "{} wasn't found; is it installed and on the PATH?",
+                        self.program
+                    )
+                }
+                _ => anyhow!("Failed to run {}: {}", self.program, e),
+            })
+    }
+
+    pub fn remove_temp_file(&self) {
+        if let Some(path) = &self.temp_file {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Shows a desktop notification, on Linux and macOS. Failures, such as
+/// notify-send not being installed, are ignored: the bell and banner remain.
+pub fn notify(title: &str, body: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {} with title {}",
+            applescript_string(body),
+            applescript_string(title)
+        ));
+        command
+    } else if cfg!(unix) {
+        let mut command = Command::new("notify-send");
+        command.arg(title).arg(body);
+        command
+    } else {
+        return;
+    };
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Waited on in a thread so the child is reaped without blocking the app
+    std::thread::spawn(move || command.status());
+}
+
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Splits a command like `less -R` from an environment variable into the
+/// program and its arguments.
+fn split_command(command: &str) -> Result<(String, Vec<String>)> {
+    let mut words = command.split_whitespace().map(str::to_string);
+    let program = words
+        .next()
+        .ok_or_else(|| anyhow!("The command is empty"))?;
+    Ok((program, words.collect()))
+}
+
+/// The percent-decoded password in a connection URL's `user:password@`.
+fn password(connection_string: &str) -> Option<String> {
+    let userinfo = &connection_string[interpolate::userinfo_range(connection_string)?];
+    let (_, password) = userinfo.split_once(':')?;
+    let password = urlencoding::decode(password).ok()?;
+    (!password.is_empty()).then(|| password.into_owned())
+}
+
+fn psql(
+    config: &ConnectionConfig,
+    connection_string: &str,
+    password: Option<String>,
+) -> Result<ExternalCommand> {
+    use sqlx::postgres::{PgConnectOptions, PgSslMode};
+
+    let options: PgConnectOptions = connection_string.parse()?;
+    // A socket's directory goes where the host would
+    let host = match options.get_socket() {
+        Some(socket) => socket.to_string_lossy().to_string(),
+        None => options.get_host().to_string(),
+    };
+    let mut args = vec![
+        "-h".to_string(),
+        host,
+        "-p".to_string(),
+        options.get_port().to_string(),
+        "-U".to_string(),
+        options.get_username().to_string(),
+    ];
+    if let Some(database) = options.get_database() {
+        args.extend(["-d".to_string(), database.to_string()]);
+    }
+
+    let mut envs = Vec::new();
+    if let Some(password) = password {
+        envs.push(("PGPASSWORD".to_string(), password));
+    }
+    let ssl_mode = match &config.ssl_config {
+        Some(ssl_config) => match ssl_config.mode {
+            SslMode::Disable => "disable",
+            SslMode::Require => "require",
+            SslMode::VerifyCa => "verify-ca",
+            SslMode::VerifyFull => "verify-full",
+        },
+        None => match options.get_ssl_mode() {
+            PgSslMode::Disable => "disable",
+            PgSslMode::Allow => "allow",
+            PgSslMode::Prefer => "prefer",
+            PgSslMode::Require => "require",
+            PgSslMode::VerifyCa => "verify-ca",
+            PgSslMode::VerifyFull => "verify-full",
+        },
+    };
+    envs.push(("PGSSLMODE".to_string(), ssl_mode.to_string()));
+    if let Some(ssl_config) = &config.ssl_config {
+        for (name, file) in [
+            ("PGSSLROOTCERT", &ssl_config.ca_file),
+            ("PGSSLCERT", &ssl_config.cert_file),
+            ("PGSSLKEY", &ssl_config.key_file),
+        ] {
+            if let Some(file) = file.as_ref().filter(|f| !f.is_empty()) {
+                envs.push((name.to_string(), file.clone()));
+            }
+        }
+    }
+
+    // The statement timeout and default schema, as server settings of the
+    // session; PGOPTIONS escapes spaces with a backslash
+    let settings = &config.settings;
+    let mut session = Vec::new();
+    if settings.statement_timeout_seconds > 0 {
+        session.push(format!(
+            "-c statement_timeout={}",
+            settings.statement_timeout_seconds * 1000
+        ));
+    }
+    let schema = settings.default_schema.trim();
+    if !schema.is_empty() {
+        session.push(format!(
+            "-c search_path={},public",
+            schema.replace('\\', "\\\\").replace(' ', "\\ ")
+        ));
+    }
+    if !session.is_empty() {
+        envs.push(("PGOPTIONS".to_string(), session.join(" ")));
+    }
+
+    Ok(ExternalCommand {
+        kind: ExternalKind::Client,
+        program: "psql".to_string(),
+        args,
+        envs,
+        temp_file: None,
+    })
+}
+
+fn mysql(
+    config: &ConnectionConfig,
+    connection_string: &str,
+    password: Option<String>,
+) -> Result<ExternalCommand> {
+    use sqlx::mysql::{MySqlConnectOptions, MySqlSslMode};
+
+    let options: MySqlConnectOptions = connection_string.parse()?;
+    let mut args = Vec::new();
+    let mut temp_file = None;
+    // An option file with the password; it must come first
+    if let Some(password) = password {
+        let path = write_private_file(
+            "my.cnf",
+            &format!(
+                "[client]\npassword=\"{}\"\n",
+                password.replace('\\', "\\\\").replace('"', "\\\"")
+            ),
+        )?;
+        args.push(format!("--defaults-extra-file={}", path.to_string_lossy()));
+        temp_file = Some(path);
+    }
+    match options.get_socket() {
+        Some(socket) => args.push(format!("--socket={}", socket.to_string_lossy())),
+        // Without TCP the client would take `localhost` to mean its socket
+        None => args.extend([
+            "--protocol=TCP".to_string(),
+            format!("--host={}", options.get_host()),
+            format!("--port={}", options.get_port()),
+        ]),
+    }
+    args.push(format!("--user={}", options.get_username()));
+
+    // The client's default is the same as the driver's, and MariaDB's client
+    // doesn't know the option, so it's only passed when set
+    let ssl_mode = match &config.ssl_config {
+        Some(ssl_config) => Some(match ssl_config.mode {
+            SslMode::Disable => "DISABLED",
+            SslMode::Require => "REQUIRED",
+            SslMode::VerifyCa => "VERIFY_CA",
+            SslMode::VerifyFull => "VERIFY_IDENTITY",
+        }),
+        None => match options.get_ssl_mode() {
+            MySqlSslMode::Preferred => None,
+            MySqlSslMode::Disabled => Some("DISABLED"),
+            MySqlSslMode::Required => Some("REQUIRED"),
+            MySqlSslMode::VerifyCa => Some("VERIFY_CA"),
+            MySqlSslMode::VerifyIdentity => Some("VERIFY_IDENTITY"),
+        },
+    };
+    if let Some(ssl_mode) = ssl_mode {
+        args.push(format!("--ssl-mode={}", ssl_mode));
+    }
+    if let Some(ssl_config) = &config.ssl_config {
+        args.extend(ssl_files(ssl_config));
+    }
+
+    let settings = &config.settings;
+    if settings.statement_timeout_seconds > 0 {
+        args.push(format!(
+            "--init-command=SET SESSION max_execution_time = {}",
+            settings.statement_timeout_seconds * 1000
+        ));
+    }
+    // The default schema is the database the client starts in
+    let schema = settings.default_schema.trim();
+    let database = Some(schema)
+        .filter(|s| !s.is_empty())
+        .or(options.get_database());
+    if let Some(database) = database {
+        args.push(database.to_string());
+    }
+
+    Ok(ExternalCommand {
+        kind: ExternalKind::Client,
+        program: "mysql".to_string(),
+        args,
+        envs: Vec::new(),
+        temp_file,
+    })
+}
+
+fn ssl_files(ssl_config: &SslConfig) -> Vec<String> {
+    [
+        ("--ssl-ca", &ssl_config.ca_file),
+        ("--ssl-cert", &ssl_config.cert_file),
+        ("--ssl-key", &ssl_config.key_file),
+    ]
+    .into_iter()
+    .filter_map(|(option, file)| {
+        let file = file.as_ref().filter(|f| !f.is_empty())?;
+        Some(format!("{}={}", option, file))
+    })
+    .collect()
+}
+
+fn sqlite3(config: &ConnectionConfig, connection_string: &str) -> Result<ExternalCommand> {
+    let options: sqlx::sqlite::SqliteConnectOptions = connection_string.parse()?;
+    let path = options.get_filename();
+    if !path.is_file() {
+        return Err(anyhow!(
+            "sqlite3 can only open a database file, not an in-memory database"
+        ));
+    }
+    let mut args = Vec::new();
+    if config.settings.read_only {
+        args.push("-readonly".to_string());
+    }
+    // Attached databases, so their tables are there under the same names
+    for attached in &config.settings.attached_databases {
+        args.extend(["-cmd".to_string(), attached.attach_statement()]);
+    }
+    args.push(path.to_string_lossy().to_string());
+    Ok(ExternalCommand {
+        kind: ExternalKind::Client,
+        program: "sqlite3".to_string(),
+        args,
+        envs: Vec::new(),
+        temp_file: None,
+    })
+}
+
+/// Writes `content` to a new file in the temp directory that only its owner
+/// can read. The name is random and the file must not exist yet, so another
+/// user can't have put a file or symlink there to read it.
+fn write_private_file(name: &str, content: &str) -> Result<PathBuf> {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    for _ in 0..10 {
+        let path = std::env::temp_dir().join(format!(
+            "rata-db-{}-{:016x}-{}",
+            std::process::id(),
+            fastrand::u64(..),
+            name
+        ));
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        match options.open(&path) {
+            Ok(mut file) => {
+                std::io::Write::write_all(&mut file, content.as_bytes())?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(anyhow!("Could not create a temporary file for {}", name))
+}
@@ -0,0 +1,316 @@
+use crate::textinput::TextInput;
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where the picked path ends up once the browser is confirmed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileBrowserTarget {
+    SqliteFile,
+    SslCertFile,
+    SslKeyFile,
+    SslCaFile,
+    RecordingExport,
+    DiffExport,
+    ResultExport,
+    DatabaseDump,
+    TableDump,
+    TableCsvExport,
+    TableCsvImport,
+    SqlFileOpen,
+    SqlFileRun,
+    ConnectionsExport,
+    ConnectionsImport,
+    BlobSave,
+    AttachDatabase,
+}
+
+impl FileBrowserTarget {
+    pub fn title(&self) -> &'static str {
+        match self {
+            FileBrowserTarget::SqliteFile => "Select SQLite Database",
+            FileBrowserTarget::SslCertFile => "Select SSL Certificate",
+            FileBrowserTarget::SslKeyFile => "Select SSL Private Key",
+            FileBrowserTarget::SslCaFile => "Select SSL CA Certificate",
+            FileBrowserTarget::RecordingExport => "Export Recording (.md or .sql)",
+            FileBrowserTarget::DiffExport => "Export Diff (.csv, .md, .txt or .xlsx)",
+            FileBrowserTarget::ResultExport => "Export Results (.csv, .md, .txt, .xlsx or .sql)",
+            FileBrowserTarget::DatabaseDump => "Dump Database (.sql)",
+            FileBrowserTarget::TableDump => "Dump Table (.sql)",
+            FileBrowserTarget::TableCsvExport => "Export Table (.csv)",
+            FileBrowserTarget::TableCsvImport => "Import CSV into Table",
+            FileBrowserTarget::SqlFileOpen => "Open SQL File",
+            FileBrowserTarget::SqlFileRun => "Run SQL File",
+            FileBrowserTarget::ConnectionsExport => "Export Connections (.json)",
+            FileBrowserTarget::ConnectionsImport => "Import Connections (.json)",
+            FileBrowserTarget::BlobSave => "Save Binary Value",
+            FileBrowserTarget::AttachDatabase => "Attach SQLite Database",
+        }
+    }
+
+    /// Extensions shown while the extension filter is on.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            FileBrowserTarget::SqliteFile | FileBrowserTarget::AttachDatabase => {
+                &["db", "sqlite", "sqlite3", "db3"]
+            }
+            FileBrowserTarget::SslCertFile => &["crt", "pem", "cer", "der"],
+            FileBrowserTarget::SslKeyFile => &["key", "pem"],
+            FileBrowserTarget::SslCaFile => &["crt", "pem", "cer"],
+            FileBrowserTarget::RecordingExport => &["md", "sql"],
+            FileBrowserTarget::DiffExport => &["csv", "md", "txt", "xlsx"],
+            FileBrowserTarget::ResultExport => &["csv", "md", "txt", "xlsx", "sql"],
+            FileBrowserTarget::TableCsvExport | FileBrowserTarget::TableCsvImport => &["csv"],
+            FileBrowserTarget::DatabaseDump
+            | FileBrowserTarget::TableDump
+            | FileBrowserTarget::SqlFileOpen
+            | FileBrowserTarget::SqlFileRun => &["sql"],
+            FileBrowserTarget::ConnectionsExport | FileBrowserTarget::ConnectionsImport => {
+                &["json"]
+            }
+            FileBrowserTarget::BlobSave => &[],
+        }
+    }
+
+    /// Whether the browser chooses a file to write rather than an existing file.
+    pub fn is_save(&self) -> bool {
+        matches!(
+            self,
+            FileBrowserTarget::RecordingExport
+                | FileBrowserTarget::DiffExport
+                | FileBrowserTarget::ResultExport
+                | FileBrowserTarget::DatabaseDump
+                | FileBrowserTarget::TableDump
+                | FileBrowserTarget::TableCsvExport
+                | FileBrowserTarget::ConnectionsExport
+                | FileBrowserTarget::BlobSave
+        )
+    }
+
+    /// Whether a file name can be typed: always when saving, and for a new
+    /// SQLite database, which is created empty when picked.
+    pub fn takes_file_name(&self) -> bool {
+        self.is_save() || *self == FileBrowserTarget::SqliteFile
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BrowserFocus {
+    List,
+    FileName, // Only for targets that take a file name
+}
+
+/// Keyboard-driven file browser popup used for every file selection.
+#[derive(Debug, Clone)]
+pub struct FileBrowser {
+    pub target: FileBrowserTarget,
+    pub dir: PathBuf,
+    pub entries: Vec<FileEntry>,
+    pub selected: usize,
+    pub show_hidden: bool,
+    pub filter_extensions: bool,
+    pub file_name: TextInput,
+    pub focus: BrowserFocus,
+}
+
+impl FileBrowser {
+    /// Creates a browser starting at `initial`: the directory itself, or the
+    /// directory containing it (pre-filling the file name when saving).
+    /// Falls back to the current directory.
+    pub fn new(target: FileBrowserTarget, initial: &str) -> Self {
+        let initial = expand_home(initial.trim());
+        let (dir, file_name) = if initial.as_os_str().is_empty() {
+            (PathBuf::from("."), String::new())
+        } else if initial.is_dir() {
+            (initial, String::new())
+        } else {
+            let name = initial
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let parent = initial
+                .parent()
+                .filter(|p| p.is_dir())
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from("."));
+            (parent, name)
+        };
+        let dir = dir.canonicalize().unwrap_or(dir);
+        let file_name = if target.is_save() {
+            file_name
+        } else {
+            String::new()
+        };
+
+        let focus = if target.is_save() {
+            BrowserFocus::FileName
+        } else {
+            BrowserFocus::List
+        };
+        let mut browser = Self {
+            target,
+            dir,
+            entries: Vec::new(),
+            selected: 0,
+            show_hidden: false,
+            filter_extensions: true,
+            file_name: TextInput::new(file_name),
+            focus,
+        };
+        if browser.refresh().is_err() {
+            browser.dir = PathBuf::from(".");
+            let _ = browser.refresh();
+        }
+        browser
+    }
+
+    /// Re-reads the current directory. Directories are listed first, then files;
+    /// hidden entries and files outside the target's extensions are skipped
+    /// unless their toggles allow them.
+    pub fn refresh(&mut self) -> Result<()> {
+        let extensions = self.target.extensions();
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
+
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') && !self.show_hidden {
+                continue;
+            }
+            if entry.path().is_dir() {
+                dirs.push(FileEntry { name, is_dir: true });
+            } else if !self.filter_extensions
+                || extensions.is_empty()
+                || has_extension(&name, extensions)
+            {
+                files.push(FileEntry {
+                    name,
+                    is_dir: false,
+                });
+            }
+        }
+        dirs.sort_by_key(|e| e.name.to_lowercase());
+        files.sort_by_key(|e| e.name.to_lowercase());
+
+        self.entries.clear();
+        if self.dir.parent().is_some() {
+            self.entries.push(FileEntry {
+                name: "..".to_string(),
+                is_dir: true,
+            });
+        }
+        self.entries.extend(dirs);
+        self.entries.extend(files);
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+        Ok(())
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let max = self.entries.len() - 1;
+        self.selected = self.selected.saturating_add_signed(delta).min(max);
+    }
+
+    /// Changes to `dir`, keeping the current directory if it can't be read.
+    pub fn change_dir(&mut self, dir: PathBuf) -> Result<()> {
+        let previous = std::mem::replace(&mut self.dir, dir);
+        self.selected = 0;
+        if let Err(e) = self.refresh() {
+            self.dir = previous;
+            self.refresh()?;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Moves to the parent directory, selecting the directory we came from.
+    pub fn go_parent(&mut self) -> Result<()> {
+        let Some(parent) = self.dir.parent().map(Path::to_path_buf) else {
+            return Ok(());
+        };
+        let child = self
+            .dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string());
+        self.change_dir(parent)?;
+        if let Some(child) = child
+            && let Some(index) = self.entries.iter().position(|e| e.name == child)
+        {
+            self.selected = index;
+        }
+        Ok(())
+    }
+
+    pub fn toggle_hidden(&mut self) -> Result<()> {
+        self.show_hidden = !self.show_hidden;
+        self.refresh()
+    }
+
+    pub fn toggle_extension_filter(&mut self) -> Result<()> {
+        self.filter_extensions = !self.filter_extensions;
+        self.refresh()
+    }
+
+    /// Activates the selected entry. Directories are entered; a file is either
+    /// returned as the picked path or, when saving, copied into the file name.
+    pub fn activate(&mut self) -> Result<Option<PathBuf>> {
+        let Some(entry) = self.entries.get(self.selected).cloned() else {
+            return Ok(None);
+        };
+        if entry.name == ".." {
+            self.go_parent()?;
+            return Ok(None);
+        }
+        if entry.is_dir {
+            self.change_dir(self.dir.join(&entry.name))?;
+            return Ok(None);
+        }
+        if self.target.is_save() {
+            self.file_name.set(entry.name);
+            self.focus = BrowserFocus::FileName;
+            return Ok(None);
+        }
+        Ok(Some(self.dir.join(entry.name)))
+    }
+
+    /// The path of the typed file name. A file name containing `/` or starting
+    /// with `~` is treated as a path of its own.
+    pub fn save_path(&self) -> Option<PathBuf> {
+        let name = self.file_name.text.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let path = expand_home(name);
+        Some(if path.is_absolute() {
+            path
+        } else {
+            self.dir.join(path)
+        })
+    }
+}
+
+fn has_extension(name: &str, extensions: &[&str]) -> bool {
+    Path::new(name)
+        .extension()
+        .is_some_and(|ext| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
+
+/// Expands a leading `~` to the user's home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~")
+        && (rest.is_empty() || rest.starts_with('/'))
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(rest.trim_start_matches('/'));
+    }
+    PathBuf::from(path)
+}
@@ -0,0 +1,77 @@
+// Change detection for SQLite files written by another program. The database
+// file and its -wal journal are polled for a new size or modification time,
+// which costs nothing but a stat; a change is only reported once `PRAGMA
+// data_version` confirms another connection committed it, so the app's own
+// writes never raise it.
+
+use crate::database::DatabasePool;
+use sqlx::Row;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+
+#[derive(Debug)]
+pub struct FileWatch {
+    files: [PathBuf; 2],                    // Database file and its -wal journal
+    stamps: [Option<(SystemTime, u64)>; 2], // Modification time and size at the last poll
+    data_version: Option<i64>,              // Last value read; None until read once
+    pub polled_at: Instant,
+    unconfirmed: bool, // The files changed but data_version couldn't be read yet
+}
+
+impl FileWatch {
+    pub fn new(database: &Path) -> Self {
+        let mut wal = database.as_os_str().to_owned();
+        wal.push("-wal");
+        let mut watch = Self {
+            files: [database.to_path_buf(), PathBuf::from(wal)],
+            stamps: [None, None],
+            data_version: None,
+            polled_at: Instant::now(),
+            unconfirmed: true,
+        };
+        watch.stamps = watch.read_stamps();
+        watch
+    }
+
+    fn read_stamps(&self) -> [Option<(SystemTime, u64)>; 2] {
+        self.files.clone().map(|file| {
+            let metadata = std::fs::metadata(file).ok()?;
+            Some((metadata.modified().ok()?, metadata.len()))
+        })
+    }
+
+    /// Polls the files, and when they changed asks SQLite whether another
+    /// connection committed. Returns true once per outside change.
+    pub async fn poll(&mut self, pool: &DatabasePool) -> bool {
+        self.polled_at = Instant::now();
+        let stamps = self.read_stamps();
+        if stamps != self.stamps {
+            self.stamps = stamps;
+            self.unconfirmed = true;
+        }
+        if !self.unconfirmed {
+            return false;
+        }
+        // Busy with a query or an open cursor; asked again at the next poll
+        let Some(version) = data_version(pool).await else {
+            return false;
+        };
+        self.unconfirmed = false;
+        let changed = self.data_version.is_some_and(|last| last != version);
+        self.data_version = Some(version);
+        changed
+    }
+}
+
+/// `PRAGMA data_version`, only if SQLite's single connection is free.
+async fn data_version(pool: &DatabasePool) -> Option<i64> {
+    let DatabasePool::SQLite(pool) = pool else {
+        return None;
+    };
+    let mut connection = pool.try_acquire()?;
+    let row = sqlx::query("PRAGMA data_version")
+        .fetch_one(&mut *connection)
+        .await
+        .ok()?;
+    row.try_get(0).ok()
+}
@@ -0,0 +1,85 @@
+// Fuzzy matching for the table finder: the pattern's characters must appear
+// in order (case-insensitive), and matches at word starts and in runs score
+// higher, so `ordit` ranks `order_items` above `customer_order_history_item`.
+
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 12;
+const WORD_START_BONUS: i64 = 10;
+const GAP_PENALTY: i64 = 1;
+
+#[derive(Debug, Clone, Default)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub positions: Vec<usize>, // Char indices of the matched characters
+}
+
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    i == 0
+        || matches!(chars[i - 1], '.' | '_' | '-' | ' ')
+        || (chars[i].is_uppercase() && chars[i - 1].is_lowercase())
+}
+
+/// Matches `pattern` against `text`, ignoring whitespace in the pattern.
+/// Returns `None` when not every pattern character is found in order.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let chars: Vec<char> = text.chars().collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    // A plain left-to-right scan, and one where a character jumps ahead to the
+    // next word start unless it continues a run; the better scoring one wins
+    [false, true]
+        .into_iter()
+        .filter_map(|prefer_word_starts| scan(&pattern, &chars, prefer_word_starts))
+        .map(|positions| FuzzyMatch {
+            score: score(&chars, &positions),
+            positions,
+        })
+        .max_by_key(|m| m.score)
+}
+
+fn scan(pattern: &[char], chars: &[char], prefer_word_starts: bool) -> Option<Vec<usize>> {
+    let mut positions: Vec<usize> = Vec::with_capacity(pattern.len());
+    let mut i = 0;
+    for &wanted in pattern {
+        let matches_at = |j: usize| chars[j].to_lowercase().eq(std::iter::once(wanted));
+        let first = (i..chars.len()).find(|&j| matches_at(j))?;
+        let continues_run = positions.last().is_some_and(|&last| last + 1 == first);
+        let chosen = if prefer_word_starts && !continues_run {
+            (first..chars.len())
+                .find(|&j| matches_at(j) && is_word_start(chars, j))
+                .unwrap_or(first)
+        } else {
+            first
+        };
+        positions.push(chosen);
+        i = chosen + 1;
+    }
+    Some(positions)
+}
+
+fn score(chars: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    for (n, &pos) in positions.iter().enumerate() {
+        score += MATCH_SCORE;
+        if is_word_start(chars, pos) {
+            score += WORD_START_BONUS;
+        }
+        match n.checked_sub(1).map(|prev| positions[prev]) {
+            Some(prev) if pos == prev + 1 => score += CONSECUTIVE_BONUS,
+            Some(prev) => score -= GAP_PENALTY * (pos - prev - 1) as i64,
+            None => score -= GAP_PENALTY * pos as i64,
+        }
+    }
+    // Among equal matches, shorter names are closer to what was typed
+    score - (chars.len() - positions.len()) as i64 / 4
+}
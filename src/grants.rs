@@ -0,0 +1,233 @@
+// GRANT/REVOKE builder, opened on a role of the roles screen: pick what the
+// statement is about (a table, every table of a schema, a schema or the
+// database), the privileges and whether it grants or revokes, and the
+// statement is put into the editor to be read and run like any other query.
+// Nothing is executed from the builder itself.
+
+use crate::dialect::Dialect;
+use anyhow::{Result, anyhow};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GrantTarget {
+    Table,
+    SchemaTables, // Every table in a schema (PostgreSQL)
+    Schema,       // PostgreSQL
+    Database,     // `db.*` on MySQL
+}
+
+impl GrantTarget {
+    pub fn all(dialect: Dialect) -> &'static [GrantTarget] {
+        match dialect {
+            Dialect::MySQL => &[GrantTarget::Table, GrantTarget::Database],
+            _ => &[
+                GrantTarget::Table,
+                GrantTarget::SchemaTables,
+                GrantTarget::Schema,
+                GrantTarget::Database,
+            ],
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GrantTarget::Table => "Table",
+            GrantTarget::SchemaTables => "All tables in schema",
+            GrantTarget::Schema => "Schema",
+            GrantTarget::Database => "Database",
+        }
+    }
+
+    pub fn privileges(&self, dialect: Dialect) -> &'static [&'static str] {
+        match (dialect, self) {
+            (Dialect::MySQL, GrantTarget::Database) => &[
+                "SELECT",
+                "INSERT",
+                "UPDATE",
+                "DELETE",
+                "CREATE",
+                "DROP",
+                "ALTER",
+                "INDEX",
+                "CREATE VIEW",
+                "EXECUTE",
+                "TRIGGER",
+            ],
+            (Dialect::MySQL, _) => &[
+                "SELECT",
+                "INSERT",
+                "UPDATE",
+                "DELETE",
+                "REFERENCES",
+                "INDEX",
+                "ALTER",
+                "DROP",
+                "TRIGGER",
+            ],
+            (_, GrantTarget::Table | GrantTarget::SchemaTables) => &[
+                "SELECT",
+                "INSERT",
+                "UPDATE",
+                "DELETE",
+                "TRUNCATE",
+                "REFERENCES",
+                "TRIGGER",
+            ],
+            (_, GrantTarget::Schema) => &["USAGE", "CREATE"],
+            (_, GrantTarget::Database) => &["CONNECT", "CREATE", "TEMPORARY"],
+        }
+    }
+}
+
+/// The builder popup.
+#[derive(Debug, Clone)]
+pub struct GrantBuilder {
+    pub dialect: Dialect,
+    pub role: String, // `user@host` on MySQL
+    pub revoke: bool,
+    pub grant_option: bool, // WITH GRANT OPTION, or REVOKE GRANT OPTION FOR
+    pub target: GrantTarget,
+    pub object: String,        // Name of the table, schema or database
+    pub table: Option<String>, // Defaults of the object names
+    pub database: Option<String>,
+    pub picked: Vec<bool>, // Parallel to the target's privileges
+    pub selected: usize,
+}
+
+impl GrantBuilder {
+    pub fn new(
+        dialect: Dialect,
+        role: &str,
+        table: Option<String>,
+        database: Option<String>,
+    ) -> Self {
+        let mut builder = Self {
+            dialect,
+            role: role.to_string(),
+            revoke: false,
+            grant_option: false,
+            target: GrantTarget::Table,
+            object: String::new(),
+            table,
+            database,
+            picked: Vec::new(),
+            selected: 0,
+        };
+        builder.set_target(GrantTarget::Table);
+        builder
+    }
+
+    pub fn privileges(&self) -> &'static [&'static str] {
+        self.target.privileges(self.dialect)
+    }
+
+    /// Switches to the next kind of object, filling in its default name.
+    pub fn cycle_target(&mut self) {
+        let targets = GrantTarget::all(self.dialect);
+        let index = targets.iter().position(|t| *t == self.target).unwrap_or(0);
+        self.set_target(targets[(index + 1) % targets.len()]);
+    }
+
+    fn set_target(&mut self, target: GrantTarget) {
+        self.target = target;
+        self.picked = vec![false; self.privileges().len()];
+        self.selected = 0;
+        let schema = self
+            .table
+            .as_deref()
+            .and_then(|t| t.split_once('.'))
+            .map_or("public", |(schema, _)| schema)
+            .to_string();
+        self.object = match target {
+            GrantTarget::Table => self.table.clone().unwrap_or_default(),
+            GrantTarget::SchemaTables | GrantTarget::Schema => schema,
+            GrantTarget::Database => self.database.clone().unwrap_or_default(),
+        };
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.picked.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn toggle_selected(&mut self) {
+        if let Some(picked) = self.picked.get_mut(self.selected) {
+            *picked = !*picked;
+        }
+    }
+
+    /// Picks every privilege, or none when all are picked.
+    pub fn toggle_all(&mut self) {
+        let all = self.picked.iter().all(|p| *p);
+        self.picked.fill(!all);
+    }
+
+    /// The statement, e.g. `GRANT SELECT, INSERT ON TABLE public.t TO "app";`.
+    pub fn statement(&self) -> Result<String> {
+        let privileges: Vec<&str> = self
+            .privileges()
+            .iter()
+            .zip(&self.picked)
+            .filter(|(_, picked)| **picked)
+            .map(|(privilege, _)| *privilege)
+            .collect();
+        if privileges.is_empty() {
+            return Err(anyhow!("Pick the privileges with Space, or a for all"));
+        }
+        if self.object.trim().is_empty() {
+            return Err(anyhow!(
+                "Name the {} with o",
+                self.target.label().to_lowercase()
+            ));
+        }
+        // Listed rather than ALL PRIVILEGES, which covers more than is shown
+        let privileges = privileges.join(", ");
+        let object = self.object.trim();
+        let (object, role) = match self.dialect {
+            Dialect::MySQL => {
+                let object = match self.target {
+                    GrantTarget::Database => format!("{}.*", self.dialect.quote_identifier(object)),
+                    _ => self.dialect.quote_table(object),
+                };
+                let (user, host) = self.role.rsplit_once('@').unwrap_or((&self.role, "%"));
+                let role = format!(
+                    "'{}'@'{}'",
+                    user.replace('\'', "''"),
+                    host.replace('\'', "''")
+                );
+                (object, role)
+            }
+            _ => {
+                let object = match self.target {
+                    GrantTarget::Table => format!("TABLE {}", self.dialect.quote_table(object)),
+                    GrantTarget::SchemaTables => format!(
+                        "ALL TABLES IN SCHEMA {}",
+                        self.dialect.quote_identifier(object)
+                    ),
+                    GrantTarget::Schema => {
+                        format!("SCHEMA {}", self.dialect.quote_identifier(object))
+                    }
+                    GrantTarget::Database => {
+                        format!("DATABASE {}", self.dialect.quote_identifier(object))
+                    }
+                };
+                (object, self.dialect.quote_identifier(&self.role))
+            }
+        };
+        Ok(match (self.revoke, self.grant_option) {
+            (false, false) => format!("GRANT {} ON {} TO {};", privileges, object, role),
+            (false, true) => format!(
+                "GRANT {} ON {} TO {} WITH GRANT OPTION;",
+                privileges, object, role
+            ),
+            (true, false) => format!("REVOKE {} ON {} FROM {};", privileges, object, role),
+            // MySQL revokes the grant option on its own, not per privilege
+            (true, true) if self.dialect == Dialect::MySQL => {
+                format!("REVOKE GRANT OPTION ON {} FROM {};", object, role)
+            }
+            (true, true) => format!(
+                "REVOKE GRANT OPTION FOR {} ON {} FROM {};",
+                privileges, object, role
+            ),
+        })
+    }
+}
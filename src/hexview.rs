@@ -0,0 +1,60 @@
+// Viewer for binary cells: the bytes are laid out 16 to a row as an offset,
+// their hex digits and their printable ASCII characters, like `hexdump -C`.
+
+// Bytes shown per row
+pub const BYTES_PER_ROW: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct HexViewer {
+    pub column: String,
+    pub bytes: Vec<u8>,
+    pub scroll: usize, // First row shown
+}
+
+impl HexViewer {
+    pub fn new(column: &str, bytes: Vec<u8>) -> Self {
+        Self {
+            column: column.to_string(),
+            bytes,
+            scroll: 0,
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.bytes.len().div_ceil(BYTES_PER_ROW)
+    }
+
+    pub fn scroll_by(&mut self, delta: isize) {
+        let max = self.row_count().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+
+    /// Row `row` as its offset, hex digits (with an extra gap after the
+    /// eighth byte) and ASCII characters, non-printable bytes shown as `.`.
+    pub fn row(&self, row: usize) -> (String, String, String) {
+        let start = row * BYTES_PER_ROW;
+        let end = (start + BYTES_PER_ROW).min(self.bytes.len());
+        let chunk = &self.bytes[start.min(end)..end];
+        let mut hex = String::with_capacity(BYTES_PER_ROW * 3 + 1);
+        for i in 0..BYTES_PER_ROW {
+            if i == BYTES_PER_ROW / 2 {
+                hex.push(' ');
+            }
+            match chunk.get(i) {
+                Some(byte) => hex.push_str(&format!("{:02x} ", byte)),
+                None => hex.push_str("   "),
+            }
+        }
+        let ascii = chunk
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        (format!("{:08x}", start), hex, ascii)
+    }
+}
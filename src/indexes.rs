@@ -0,0 +1,120 @@
+// Index management from the table browser: the table's indexes are listed, a
+// form picks the columns, uniqueness and name of a new one, and the generated
+// CREATE INDEX or DROP INDEX statement is previewed before it runs.
+
+use crate::database::{IndexInfo, TableInfo};
+use crate::textinput::TextInput;
+use crossterm::event::{KeyCode, KeyEvent};
+
+// PostgreSQL truncates longer identifiers; MySQL allows 64
+const MAX_NAME_LENGTH: usize = 63;
+
+/// The index manager popup of one table.
+#[derive(Debug, Clone)]
+pub struct IndexManager {
+    pub table: TableInfo,
+    pub indexes: Vec<IndexInfo>,
+    pub selected: usize,         // Index into `indexes`
+    pub form: Option<IndexForm>, // Creating a new index
+    pub preview: Option<String>, // Statement waiting to be confirmed
+}
+
+impl IndexManager {
+    pub fn new(table: TableInfo, indexes: Vec<IndexInfo>) -> Self {
+        Self {
+            table,
+            indexes,
+            selected: 0,
+            form: None,
+            preview: None,
+        }
+    }
+
+    pub fn selected_index(&self) -> Option<&IndexInfo> {
+        self.indexes.get(self.selected)
+    }
+}
+
+/// Columns, uniqueness and name of an index being created.
+#[derive(Debug, Clone)]
+pub struct IndexForm {
+    pub columns: Vec<String>, // Table columns, in table order
+    pub chosen: Vec<usize>,   // Positions in `columns`, in key order
+    pub selected: usize,      // Cursor row in `columns`
+    pub unique: bool,
+    pub name: TextInput,
+    pub name_edited: bool,  // Typed by hand, so choosing columns keeps it
+    pub editing_name: bool, // Keys go to the name instead of the column list
+    table_name: String,
+}
+
+impl IndexForm {
+    pub fn new(table_name: &str, columns: Vec<String>) -> Self {
+        Self {
+            columns,
+            chosen: Vec::new(),
+            selected: 0,
+            unique: false,
+            name: TextInput::default(),
+            name_edited: false,
+            editing_name: false,
+            table_name: table_name.to_string(),
+        }
+    }
+
+    /// Adds the column under the cursor to the key, or removes it.
+    pub fn toggle_column(&mut self) {
+        match self.chosen.iter().position(|&c| c == self.selected) {
+            Some(position) => {
+                self.chosen.remove(position);
+            }
+            None if self.selected < self.columns.len() => self.chosen.push(self.selected),
+            None => {}
+        }
+        self.update_name();
+    }
+
+    pub fn toggle_unique(&mut self) {
+        self.unique = !self.unique;
+        self.update_name();
+    }
+
+    pub fn chosen_columns(&self) -> Vec<String> {
+        self.chosen
+            .iter()
+            .map(|&c| self.columns[c].clone())
+            .collect()
+    }
+
+    /// `idx_<table>_<columns>` (or `uq_` for a unique index) until a name is typed.
+    fn update_name(&mut self) {
+        if self.name_edited {
+            return;
+        }
+        let mut parts = vec![self.table_name.clone()];
+        parts.extend(self.chosen_columns());
+        let prefix = if self.unique { "uq" } else { "idx" };
+        let name: String = format!("{}_{}", prefix, parts.join("_"))
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .take(MAX_NAME_LENGTH)
+            .collect();
+        self.name.set(name);
+    }
+
+    /// Edits the name with a key; names take no whitespace.
+    pub fn handle_name_key(&mut self, key: KeyEvent) {
+        if matches!(key.code, KeyCode::Char(c) if c.is_whitespace()) {
+            return;
+        }
+        if self.name.handle_key(key) {
+            self.name_edited = true;
+        }
+    }
+
+    pub fn paste_name(&mut self, pasted: &str) {
+        let pasted: String = pasted.chars().filter(|c| !c.is_whitespace()).collect();
+        self.name.insert_str(&pasted);
+        self.name_edited = true;
+    }
+}
@@ -0,0 +1,167 @@
+// `${NAME}` placeholders in connection strings, resolved at connect time from
+// the environment or a `.env` file so secrets stay out of connections.json.
+
+use crate::settings::config_dir;
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Range;
+
+/// A `${NAME}` placeholder found in a string.
+struct Placeholder<'a> {
+    name: &'a str,
+    span: Range<usize>, // Byte range of the whole `${NAME}`
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Placeholders in `text`, in order. A `${` without a valid name and closing
+/// brace is left as literal text.
+fn placeholders(text: &str) -> Vec<Placeholder<'_>> {
+    let mut found = Vec::new();
+    let mut from = 0;
+    while let Some(offset) = text[from..].find("${") {
+        let start = from + offset;
+        let Some(length) = text[start + 2..].find('}') else {
+            break;
+        };
+        let name = &text[start + 2..start + 2 + length];
+        let end = start + 2 + length + 1;
+        if is_variable_name(name) {
+            found.push(Placeholder {
+                name,
+                span: start..end,
+            });
+            from = end;
+        } else {
+            from = start + 2;
+        }
+    }
+    found
+}
+
+/// Whether `value` is exactly one placeholder, such as a password field of
+/// `${PGPASSWORD}`.
+pub fn is_placeholder(value: &str) -> bool {
+    placeholders(value)
+        .first()
+        .is_some_and(|p| p.span == (0..value.len()))
+}
+
+/// Percent-encodes a connection form field for a URL, keeping placeholders as
+/// they are so they still resolve at connect time.
+pub fn encode_field(value: &str) -> String {
+    let mut encoded = String::new();
+    let mut from = 0;
+    for placeholder in placeholders(value) {
+        encoded.push_str(&urlencoding::encode(&value[from..placeholder.span.start]));
+        encoded.push_str(&value[placeholder.span.clone()]);
+        from = placeholder.span.end;
+    }
+    encoded.push_str(&urlencoding::encode(&value[from..]));
+    encoded
+}
+
+/// Byte range of the `user:password` part of a URL, if it has one.
+pub fn userinfo_range(url: &str) -> Option<Range<usize>> {
+    let start = url.find("://")? + 3;
+    let authority_end = url[start..]
+        .find(['/', '?'])
+        .map_or(url.len(), |i| start + i);
+    let at = url[start..authority_end].rfind('@')?;
+    Some(start..start + at)
+}
+
+/// `KEY=VALUE` lines of a `.env` file. Blank lines, `#` comments and an
+/// `export ` prefix are skipped, and matching quotes around values removed.
+fn parse_dotenv(content: &str) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        let value = ['"', '\'']
+            .iter()
+            .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+            .unwrap_or(value);
+        vars.insert(key.trim().to_string(), value.to_string());
+    }
+    vars
+}
+
+/// Variables from `.env` in the working directory, then from `.env` in the
+/// config directory for names the first doesn't set.
+fn dotenv_vars() -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    let mut paths = vec![std::path::PathBuf::from(".env")];
+    if let Ok(dir) = config_dir() {
+        paths.push(dir.join(".env"));
+    }
+    for path in paths {
+        if let Ok(content) = fs::read_to_string(&path) {
+            for (key, value) in parse_dotenv(&content) {
+                vars.entry(key).or_insert(value);
+            }
+        }
+    }
+    vars
+}
+
+/// Replaces each `${NAME}` in a connection string with the variable's value,
+/// taken from the environment first and `.env` files after that. Values in the
+/// `user:password@` part are percent-encoded so characters like `@` keep the
+/// URL intact; elsewhere they are inserted as they are.
+pub fn resolve(connection_string: &str) -> Result<String> {
+    let found = placeholders(connection_string);
+    if found.is_empty() {
+        return Ok(connection_string.to_string());
+    }
+
+    let dotenv = dotenv_vars();
+    let userinfo = userinfo_range(connection_string);
+    let mut resolved = String::new();
+    let mut missing: Vec<&str> = Vec::new();
+    let mut from = 0;
+    for placeholder in &found {
+        resolved.push_str(&connection_string[from..placeholder.span.start]);
+        from = placeholder.span.end;
+
+        let value = std::env::var(placeholder.name)
+            .ok()
+            .or_else(|| dotenv.get(placeholder.name).cloned());
+        match value {
+            Some(value)
+                if userinfo
+                    .as_ref()
+                    .is_some_and(|r| r.contains(&placeholder.span.start)) =>
+            {
+                resolved.push_str(&urlencoding::encode(&value))
+            }
+            Some(value) => resolved.push_str(&value),
+            None if !missing.contains(&placeholder.name) => missing.push(placeholder.name),
+            None => {}
+        }
+    }
+    resolved.push_str(&connection_string[from..]);
+
+    if !missing.is_empty() {
+        let names: Vec<String> = missing.iter().map(|n| format!("${{{}}}", n)).collect();
+        return Err(anyhow!(
+            "{} not set in the environment or a .env file",
+            names.join(", ")
+        ));
+    }
+    Ok(resolved)
+}
@@ -0,0 +1,141 @@
+use crate::database::{QueryResult, RowStream};
+use anyhow::{Result, anyhow};
+use std::time::{Duration, Instant};
+use tokio::task::{JoinError, JoinHandle};
+
+// Finished jobs kept in the jobs panel
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// How a paged SELECT was ordered for stable pagination.
+#[derive(Debug)]
+pub enum OrderNote {
+    OrderedBy(String),
+    Unstable(String),
+}
+
+/// Everything a background query produced, applied to the app once it finishes.
+#[derive(Debug)]
+pub struct QueryOutcome {
+    pub result: Result<QueryResult>,    // The first result set
+    pub more_results: Vec<QueryResult>, // Further sets of a multi-statement query
+    pub stream: Option<RowStream>,      // Cursor for the rows after the first chunk
+    pub total_count: usize,
+    pub total_count_estimated: bool,
+    pub order_note: Option<OrderNote>,
+    pub collation_warning: Option<String>,
+    pub elapsed: Duration,
+}
+
+impl QueryOutcome {
+    /// The outcome of a query abandoned after running for `limit`.
+    pub fn timed_out(limit: Duration) -> Self {
+        Self {
+            result: Err(anyhow!(
+                "Statement timeout: no result after {} seconds",
+                limit.as_secs()
+            )),
+            more_results: Vec::new(),
+            stream: None,
+            total_count: 0,
+            total_count_estimated: false,
+            order_note: None,
+            collation_warning: None,
+            elapsed: limit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobStatus {
+    Running,
+    Finished,
+    Failed(String),
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobStatus::Running => "running",
+            JobStatus::Finished => "finished",
+            JobStatus::Failed(_) => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// A query running (or run) in a spawned task, listed in the jobs panel.
+#[derive(Debug)]
+pub struct QueryJob {
+    pub id: usize,
+    pub query: String,
+    pub connection: String,
+    pub status: JobStatus,
+    pub result: Option<QueryResult>, // First chunk of a finished job, for viewing later
+    started: Instant,
+    duration: Option<Duration>,
+    handle: Option<JoinHandle<QueryOutcome>>,
+}
+
+impl QueryJob {
+    pub fn new(
+        id: usize,
+        query: String,
+        connection: String,
+        handle: JoinHandle<QueryOutcome>,
+    ) -> Self {
+        Self {
+            id,
+            query,
+            connection,
+            status: JobStatus::Running,
+            result: None,
+            started: Instant::now(),
+            duration: None,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status == JobStatus::Running
+    }
+
+    /// Time since the job started, frozen once it ends.
+    pub fn elapsed(&self) -> Duration {
+        self.duration.unwrap_or_else(|| self.started.elapsed())
+    }
+
+    /// Takes the task's outcome once it has finished; `None` while it's running.
+    pub async fn take_outcome(&mut self) -> Option<Result<QueryOutcome, JoinError>> {
+        if !self.handle.as_ref().is_some_and(|h| h.is_finished()) {
+            return None;
+        }
+        let handle = self.handle.take()?;
+        self.duration = Some(self.started.elapsed());
+        Some(handle.await)
+    }
+
+    /// Aborts the task. The database may keep working on the statement until
+    /// it notices the dropped connection.
+    pub fn cancel(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+            self.duration = Some(self.started.elapsed());
+            self.status = JobStatus::Cancelled;
+        }
+    }
+}
+
+/// Drops the oldest ended jobs beyond the panel's limit.
+pub fn prune(jobs: &mut Vec<QueryJob>) {
+    let ended = jobs.iter().filter(|j| !j.is_running()).count();
+    let mut excess = ended.saturating_sub(MAX_FINISHED_JOBS);
+    jobs.retain(|job| {
+        if excess > 0 && !job.is_running() {
+            excess -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
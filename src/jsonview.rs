@@ -0,0 +1,217 @@
+// Viewer for JSON cells: the value is parsed and laid out as indented lines,
+// one per scalar and one per opening and closing bracket, where objects and
+// arrays can be collapsed to a single summary line.
+
+use serde_json::Value;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JsonLineKind {
+    Open,      // `{` or `[` of an expanded object or array
+    Close,     // Its `}` or `]`
+    Collapsed, // `{…}` or `[…]` with a count
+    String,
+    Number,
+    Bool,
+    Null,
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonLine {
+    pub depth: usize,
+    pub key: Option<String>, // Object key, quoted
+    pub kind: JsonLineKind,
+    pub text: String,
+    pub comma: bool,
+    path: Vec<usize>, // Child positions from the root to the line's value
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonViewer {
+    pub column: String,
+    value: Value,
+    collapsed: HashSet<Vec<usize>>,
+    pub lines: Vec<JsonLine>,
+    pub selected: usize, // Index into `lines`
+}
+
+impl JsonViewer {
+    /// Parses `text` as a JSON object or array. Returns `None` for anything
+    /// else, scalars included, as they have nothing to expand.
+    pub fn parse(column: &str, text: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(text.trim()).ok()?;
+        if !value.is_object() && !value.is_array() {
+            return None;
+        }
+        let mut viewer = Self {
+            column: column.to_string(),
+            value,
+            collapsed: HashSet::new(),
+            lines: Vec::new(),
+            selected: 0,
+        };
+        viewer.layout();
+        Some(viewer)
+    }
+
+    /// The value indented with two spaces, keys in their original order.
+    pub fn pretty(&self) -> String {
+        serde_json::to_string_pretty(&self.value).unwrap_or_default()
+    }
+
+    fn layout(&mut self) {
+        let mut lines = Vec::new();
+        push_lines(
+            &self.value,
+            None,
+            0,
+            Vec::new(),
+            false,
+            &self.collapsed,
+            &mut lines,
+        );
+        self.lines = lines;
+        self.selected = self.selected.min(self.lines.len().saturating_sub(1));
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let max = self.lines.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(max);
+    }
+
+    /// Collapses the object or array the selected line opens, closes or
+    /// summarizes, or expands it if it is collapsed.
+    pub fn toggle_selected(&mut self) {
+        let Some(line) = self.lines.get(self.selected) else {
+            return;
+        };
+        if !matches!(
+            line.kind,
+            JsonLineKind::Open | JsonLineKind::Close | JsonLineKind::Collapsed
+        ) {
+            return;
+        }
+        let path = line.path.clone();
+        if !self.collapsed.remove(&path) {
+            self.collapsed.insert(path.clone());
+        }
+        self.layout();
+        // Stay on the node, which moved if its closing line was selected
+        if let Some(index) = self.lines.iter().position(|l| l.path == path) {
+            self.selected = index;
+        }
+    }
+
+    /// Collapses everything below the root, or expands everything.
+    pub fn set_all_collapsed(&mut self, collapsed: bool) {
+        self.collapsed.clear();
+        if collapsed {
+            collect_containers(&self.value, Vec::new(), &mut self.collapsed);
+            self.collapsed.remove(&Vec::new());
+        }
+        self.selected = 0;
+        self.layout();
+    }
+}
+
+fn collect_containers(value: &Value, path: Vec<usize>, out: &mut HashSet<Vec<usize>>) {
+    let children: Vec<&Value> = match value {
+        Value::Object(map) => map.values().collect(),
+        Value::Array(items) => items.iter().collect(),
+        _ => return,
+    };
+    for (i, child) in children.into_iter().enumerate() {
+        let mut child_path = path.clone();
+        child_path.push(i);
+        collect_containers(child, child_path, out);
+    }
+    out.insert(path);
+}
+
+fn push_lines(
+    value: &Value,
+    key: Option<String>,
+    depth: usize,
+    path: Vec<usize>,
+    comma: bool,
+    collapsed: &HashSet<Vec<usize>>,
+    lines: &mut Vec<JsonLine>,
+) {
+    let line = |kind, text: String, key, path| JsonLine {
+        depth,
+        key,
+        kind,
+        text,
+        comma,
+        path,
+    };
+    let (open, close, children): (char, char, Vec<(Option<String>, &Value)>) = match value {
+        Value::Object(map) => (
+            '{',
+            '}',
+            map.iter()
+                .map(|(k, v)| (Some(Value::String(k.clone()).to_string()), v))
+                .collect(),
+        ),
+        Value::Array(items) => ('[', ']', items.iter().map(|v| (None, v)).collect()),
+        Value::String(_) => {
+            lines.push(line(JsonLineKind::String, value.to_string(), key, path));
+            return;
+        }
+        Value::Number(n) => {
+            lines.push(line(JsonLineKind::Number, n.to_string(), key, path));
+            return;
+        }
+        Value::Bool(b) => {
+            lines.push(line(JsonLineKind::Bool, b.to_string(), key, path));
+            return;
+        }
+        Value::Null => {
+            lines.push(line(JsonLineKind::Null, "null".to_string(), key, path));
+            return;
+        }
+    };
+
+    if children.is_empty() {
+        lines.push(line(
+            JsonLineKind::Open,
+            format!("{}{}", open, close),
+            key,
+            path,
+        ));
+        return;
+    }
+    if collapsed.contains(&path) {
+        let count = match value {
+            Value::Object(_) => format!("{} keys", children.len()),
+            _ => format!("{} items", children.len()),
+        };
+        let text = format!("{}…{} {}", open, close, count);
+        lines.push(line(JsonLineKind::Collapsed, text, key, path));
+        return;
+    }
+
+    lines.push(JsonLine {
+        depth,
+        key,
+        kind: JsonLineKind::Open,
+        text: open.to_string(),
+        comma: false,
+        path: path.clone(),
+    });
+    let last = children.len() - 1;
+    for (i, (child_key, child)) in children.into_iter().enumerate() {
+        let mut child_path = path.clone();
+        child_path.push(i);
+        push_lines(
+            child,
+            child_key,
+            depth + 1,
+            child_path,
+            i < last,
+            collapsed,
+            lines,
+        );
+    }
+    lines.push(line(JsonLineKind::Close, close.to_string(), None, path));
+}
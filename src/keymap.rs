@@ -0,0 +1,444 @@
+// Key bindings listed in the help popup, grouped by where they work. The key
+// handlers in `event` do the work; this registry describes them so the help
+// shows only the current screen's bindings and the global ones, and stays one
+// list to update when a key is added.
+
+use crate::app::AppScreen;
+
+/// Bindings under one heading: the keys and what they do.
+pub struct Section {
+    pub title: &'static str,
+    pub bindings: &'static [(&'static str, &'static str)],
+}
+
+const GLOBAL: Section = Section {
+    title: "Global",
+    bindings: &[
+        ("Ctrl+Q", "Quit application"),
+        ("h/F1", "Toggle this help (F1 only while typing)"),
+        ("Esc", "Go back/Cancel"),
+        ("F2", "Start/stop session recording"),
+        ("F3", "Add narration note to recording"),
+        ("F4", "Export recording (.md or .sql)"),
+        ("F5", "Query statistics (also 's' on connection list)"),
+        ("F6", "Jobs panel (Enter view, e edit, x cancel, c clear)"),
+        (
+            "F7",
+            "Toggle vim mode (hjkl in lists/results; help on F1 only)",
+        ),
+        ("F8", "Switch color theme (dark, light, solarized, custom)"),
+        (
+            "F9",
+            "Server activity, or PRAGMAs on SQLite (c cancel query, x x terminate, a pause refresh)",
+        ),
+        (
+            "m",
+            "Message history (M in results, where m fetches more rows)",
+        ),
+        ("F10 + a-z", "Record keyboard macro (F10 stops)"),
+        ("F12 + a-z", "Replay keyboard macro (any key stops)"),
+        (
+            "Ctrl+E",
+            "Jump to a recently opened table (not in the editor)",
+        ),
+        ("Ctrl+W", "Saved layouts: restore, save (s) or delete (d)"),
+        (
+            "Mouse",
+            "Click to select, click again to open; wheel scrolls",
+        ),
+    ],
+};
+
+const CONNECTION_LIST: Section = Section {
+    title: "Connection List",
+    bindings: &[
+        ("n", "New connection"),
+        ("e", "Edit connection"),
+        ("Enter", "Connect to selected"),
+        ("d", "Delete connection"),
+        (
+            "o",
+            "Connection settings (page size, row limit, timeouts, schema)",
+        ),
+        ("g", "Filter by group (cycles through groups)"),
+        ("/", "Filter connections by name, group or tag"),
+        ("Shift+↑/↓ or K/J", "Move connection up/down"),
+        ("x/i", "Export/import connections (without passwords)"),
+        ("r", "Sort by most recently used / saved order"),
+        ("a", "Toggle auto-connect to the last used connection"),
+        ("Esc", "Cancel connection (when connecting)"),
+        ("q", "Quit"),
+    ],
+};
+
+const CONNECTION_FORM: Section = Section {
+    title: "Connection Form",
+    bindings: &[
+        ("Tab/Shift+Tab", "Next/previous field"),
+        ("←/→ Home/End", "Move the cursor (Shift selects)"),
+        ("Ctrl+O", "Browse for SQLite/SSL file"),
+        ("Ctrl+T", "Start a new connection over from a template"),
+        ("Enter", "Save the connection"),
+        ("Esc", "Cancel"),
+    ],
+};
+
+const FILE_BROWSER: Section = Section {
+    title: "File Browser",
+    bindings: &[
+        ("Enter/←", "Open directory or pick file / parent"),
+        ("f/.", "Toggle extension filter / hidden files"),
+        ("Tab", "Switch to file name (when saving)"),
+    ],
+};
+
+const TABLE_BROWSER: Section = Section {
+    title: "Table Browser",
+    bindings: &[
+        ("↑↓", "Navigate tables"),
+        (
+            "/",
+            "Fuzzy filter by table/schema name (Enter keep, Esc clear)",
+        ),
+        ("→/← Enter", "Expand/collapse schema (PostgreSQL)"),
+        ("s", "Generate SELECT query"),
+        ("e", "Foreign key map (Tab switches boxes/adjacency list)"),
+        (
+            "f",
+            "Functions and procedures (Enter puts a call in the editor)",
+        ),
+        (
+            "i",
+            "Show/hide table statistics (size, indexes, vacuum/analyze)",
+        ),
+        ("d/D", "Dump selected table / whole database to a .sql file"),
+        (
+            "E/I",
+            "Export selected table to / import it from a .csv file",
+        ),
+        (
+            "*",
+            "Star/unstar selected table (starred tables are listed first)",
+        ),
+        ("F", "List only starred tables / all tables"),
+        ("o", "Open the table's DDL in $PAGER (less by default)"),
+        ("!", "Open psql, mysql or sqlite3 on this connection"),
+        ("B", "Dashboard of pinned queries"),
+        (
+            "G",
+            "Users and roles with their grants (w: only those that can write)",
+        ),
+        (
+            "T",
+            "Tablespaces and the tables and indexes in each (PostgreSQL)",
+        ),
+        ("c", "Copy table (schema and rows) to another connection"),
+        (
+            "x",
+            "Manage indexes (n new, d drop, SQL shown before it runs)",
+        ),
+        (
+            "C",
+            "Table and column comments (Enter edits one, SQL shown first)",
+        ),
+        (
+            "L",
+            "Change a column's name, type, NOT NULL or default (ALTER TABLE shown first)",
+        ),
+        ("S", "Seed the table with generated rows"),
+        ("R", "Rename the table"),
+        (
+            "W / X",
+            "Truncate / drop the table (type its name to confirm)",
+        ),
+        (
+            "P",
+            "Profile columns (nulls, distinct, min/max, top values)",
+        ),
+        ("a", "Quick aggregate (GROUP BY with COUNT/SUM/AVG/MIN/MAX)"),
+        (
+            "A / U",
+            "Attach another SQLite file / detach the selected table's file",
+        ),
+        ("n", "Count the table's rows exactly (in the background)"),
+        ("q", "Open query editor"),
+    ],
+};
+
+const QUERY_EDITOR: Section = Section {
+    title: "Query Editor",
+    bindings: &[
+        ("Ctrl+Enter", "Execute query"),
+        ("Ctrl+Shift+C", "Clear query"),
+        ("Ctrl+Z/Ctrl+Y", "Undo/redo edits"),
+        ("Shift+←/→/Home/End", "Select text"),
+        ("Ctrl+L", "Insert date/time literal (picker)"),
+        (
+            "Ctrl+G",
+            "Edit the query in $EDITOR (saved changes replace it)",
+        ),
+        ("Ctrl+K", "Generate SQL from a question (assistant)"),
+        ("Ctrl+O", "Open SQL file in the editor"),
+        ("Ctrl+R", "Run SQL file statement by statement"),
+        ("Ctrl+B", "Toggle split view (editor + results)"),
+        ("Ctrl+↑/↓", "Move the split view's divider"),
+        ("Alt+arrows", "Move through the split view's results"),
+        ("Ctrl+S", "SELECT * from current table"),
+        ("Ctrl+I", "INSERT statement"),
+        ("Ctrl+D", "DELETE statement"),
+        ("Ctrl+U", "UPDATE statement"),
+        (
+            "Ctrl+C",
+            "CREATE TABLE statement (a copy of the current table)",
+        ),
+        ("Ctrl+T", "TRUNCATE statement (DELETE on SQLite)"),
+    ],
+};
+
+const VIM: Section = Section {
+    title: "Vim Mode (normal)",
+    bindings: &[
+        ("hjkl, w/b", "Move by character, line or word"),
+        ("0/^/$, gg/G", "Line start/first word/end, first/last line"),
+        ("x, dd, yy, p/P", "Delete character or line, yank line, put"),
+        ("i/a/I/A/o/O", "Insert mode (Esc back to normal)"),
+        ("u", "Undo"),
+        ("Enter", "Execute query"),
+    ],
+};
+
+const QUERY_RESULTS: Section = Section {
+    title: "Query Results",
+    bindings: &[
+        ("↑↓←→", "Navigate/scroll results"),
+        ("PgUp/PgDn", "Change pages"),
+        ("Home/End", "First/Last page"),
+        ("0", "First column"),
+        ("$/l", "Last column (l without vim mode)"),
+        ("Ctrl+B", "Toggle split view (editor + results)"),
+        ("Esc", "Back to the query editor"),
+        ("p", "Toggle privacy mode (mask sensitive columns)"),
+        ("P", "Profile selected column (reruns the query)"),
+        ("a", "Quick aggregate over all the rows of the query"),
+        ("g", "Chart loaded rows (bar/line, pick X and Y columns)"),
+        (
+            "f",
+            "Filter loaded rows by selected column (=exact, ~regex, text; date picker on dates)",
+        ),
+        ("F", "Run the filter on the server as a WHERE clause"),
+        (
+            "R",
+            "Run the query again (e.g. after the file changed on disk)",
+        ),
+        (
+            "o / O",
+            "Open the selected cell / the whole result in $PAGER",
+        ),
+        (
+            "Enter",
+            "View JSON cell as a foldable tree (y copies it) or binary cell as hex (s saves it to a file)",
+        ),
+        ("v", "Reveal/hide masked values"),
+        ("u", "Toggle byte/duration humanization"),
+        ("b", "Pin result as diff baseline"),
+        ("d", "Toggle diff vs pinned result or previous run"),
+        ("c", "Compute exact row count (replaces estimate)"),
+        ("m", "Fetch more rows past the row cap"),
+        ("Tab/Shift+Tab", "Next/previous result set"),
+        (
+            "e",
+            "Export loaded rows (.csv/.md/.txt ASCII table/.xlsx/.sql INSERTs)",
+        ),
+        ("y", "Copy current page as a Markdown table"),
+        ("I", "Copy selected row as an INSERT statement"),
+        (
+            "Ctrl+S/U/D",
+            "SELECT/UPDATE/DELETE of selected row in editor",
+        ),
+        ("D", "Export diff (CSV/Markdown/ASCII/Excel)"),
+    ],
+};
+
+const STATISTICS: Section = Section {
+    title: "Query Statistics",
+    bindings: &[
+        ("←→", "Previous/next connection"),
+        ("Tab", "Slowest/most frequent queries"),
+        ("↑↓", "Scroll"),
+        ("r", "Reload"),
+    ],
+};
+
+const ER_MAP: Section = Section {
+    title: "Foreign Key Map",
+    bindings: &[
+        ("Tab", "Boxes/adjacency list"),
+        ("↑↓ PgUp/PgDn Home/End", "Scroll"),
+        ("r", "Reload"),
+    ],
+};
+
+const SERVER_MONITOR: Section = Section {
+    title: "Server Activity",
+    bindings: &[
+        ("↑↓", "Select a session"),
+        ("c", "Cancel its query"),
+        ("x x", "Terminate the session"),
+        ("r", "Refresh"),
+        ("a", "Pause/resume automatic refresh"),
+    ],
+};
+
+const ROUTINES: Section = Section {
+    title: "Functions and Procedures",
+    bindings: &[
+        ("↑↓", "Select a routine"),
+        ("PgUp/PgDn", "Scroll its source"),
+        ("Enter/c", "Put a call in the editor"),
+        ("r", "Reload"),
+    ],
+};
+
+const CHART: Section = Section {
+    title: "Chart",
+    bindings: &[
+        ("↑↓", "Select a column"),
+        ("Space", "Plot/remove the column"),
+        ("x", "Use the column as the X axis"),
+        ("t", "Bar/line chart"),
+    ],
+};
+
+const PRAGMAS: Section = Section {
+    title: "PRAGMAs (SQLite)",
+    bindings: &[
+        ("↑↓", "Select a PRAGMA"),
+        ("←→", "Choose a value"),
+        ("Enter", "Set it (press twice to confirm)"),
+        ("i", "Integrity check"),
+        ("r", "Reload"),
+    ],
+};
+
+const DASHBOARD: Section = Section {
+    title: "Dashboard",
+    bindings: &[
+        ("Arrow keys", "Select a tile"),
+        ("a", "Pin the editor's query as a tile"),
+        ("t", "Show the tile as a table or a single stat"),
+        (
+            "!",
+            "Alert when the tile's first value crosses a threshold, e.g. > 100",
+        ),
+        ("+/-", "Refresh interval"),
+        ("Enter", "Edit the tile's query"),
+        ("d d", "Delete the tile"),
+        ("r / R", "Refresh / reload the dashboard file"),
+    ],
+};
+
+const ROLES: Section = Section {
+    title: "Users and Roles",
+    bindings: &[
+        ("↑↓", "Select a role"),
+        ("PgUp/PgDn", "Scroll its grants"),
+        ("w", "Only roles that can write to the selected table"),
+        ("g", "Build a GRANT or REVOKE into the editor"),
+        ("n", "New user or role"),
+        ("p", "Change the selected role's password"),
+        ("r", "Reload"),
+    ],
+};
+
+const STORAGE: Section = Section {
+    title: "Tablespaces",
+    bindings: &[
+        ("↑↓", "Select a tablespace"),
+        ("PgUp/PgDn", "Scroll its tables and indexes"),
+        ("r", "Reload"),
+    ],
+};
+
+/// The sections for `screen`, then the global bindings.
+pub fn sections(screen: &AppScreen, vim_mode: bool) -> Vec<&'static Section> {
+    let mut sections = match screen {
+        AppScreen::ConnectionList => vec![&CONNECTION_LIST],
+        AppScreen::NewConnection | AppScreen::EditConnection => {
+            vec![&CONNECTION_FORM, &FILE_BROWSER]
+        }
+        AppScreen::TableBrowser => vec![&TABLE_BROWSER, &FILE_BROWSER],
+        AppScreen::QueryEditor if vim_mode => vec![&QUERY_EDITOR, &VIM, &FILE_BROWSER],
+        AppScreen::QueryEditor => vec![&QUERY_EDITOR, &FILE_BROWSER],
+        AppScreen::QueryResults => vec![&QUERY_RESULTS, &FILE_BROWSER],
+        AppScreen::Statistics => vec![&STATISTICS],
+        AppScreen::ErMap => vec![&ER_MAP],
+        AppScreen::ServerMonitor => vec![&SERVER_MONITOR],
+        AppScreen::Routines => vec![&ROUTINES],
+        AppScreen::Chart => vec![&CHART],
+        AppScreen::Pragmas => vec![&PRAGMAS],
+        AppScreen::Dashboard => vec![&DASHBOARD],
+        AppScreen::Roles => vec![&ROLES],
+        AppScreen::Storage => vec![&STORAGE],
+    };
+    sections.push(&GLOBAL);
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    /// Every key in `bindings`, with `Ctrl+` carried over to the single
+    /// letters after it (`Ctrl+S/U/D`) and arrow runs split up.
+    fn listed_keys(bindings: &[(&str, &str)]) -> Vec<String> {
+        let mut keys = Vec::new();
+        for (label, _) in bindings {
+            let mut ctrl = false;
+            for part in label.split('/').map(str::trim) {
+                if part.chars().all(|c| "↑↓←→".contains(c)) {
+                    keys.extend(part.chars().map(String::from));
+                } else if ctrl && part.chars().count() == 1 {
+                    keys.push(format!("Ctrl+{}", part));
+                } else {
+                    ctrl = part.starts_with("Ctrl+");
+                    keys.push(part.to_string());
+                }
+            }
+        }
+        keys
+    }
+
+    #[test]
+    fn query_results_lists_every_handled_key() {
+        let source = include_str!("event.rs");
+        let start = source
+            .find("async fn handle_query_results_keys")
+            .expect("results key handler");
+        let body = &source[start..];
+        let body = &body[..body[1..].find("\nasync fn ").map_or(body.len(), |i| i + 1)];
+
+        let mut listed = listed_keys(QUERY_RESULTS.bindings);
+        listed.extend(listed_keys(GLOBAL.bindings));
+        let arm = Regex::new(r"KeyCode::(\w+)(?:\('(.)'\))?").unwrap();
+        for line in body.lines() {
+            for key in arm.captures_iter(line) {
+                let name = match (&key[1], key.get(2)) {
+                    ("Char", Some(c)) if line.contains("CONTROL") => {
+                        format!("Ctrl+{}", c.as_str().to_uppercase())
+                    }
+                    ("Char", Some(c)) => c.as_str().to_string(),
+                    ("Up", _) => "↑".to_string(),
+                    ("Down", _) => "↓".to_string(),
+                    ("Left", _) => "←".to_string(),
+                    ("Right", _) => "→".to_string(),
+                    ("PageUp", _) => "PgUp".to_string(),
+                    ("PageDown", _) => "PgDn".to_string(),
+                    ("BackTab", _) => "Shift+Tab".to_string(),
+                    (other, _) => other.to_string(),
+                };
+                assert!(listed.contains(&name), "{} is not in the help", name);
+            }
+        }
+    }
+}
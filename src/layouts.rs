@@ -0,0 +1,55 @@
+// Saved layouts: named snapshots of a connection's workspace (the screen, the
+// split view and its sizes, the selected table and the query in the editor),
+// saved with the connection and restored from a list. A layout saved on the
+// results screen runs its query again when restored, so a layout can open
+// straight onto a go-to health query.
+
+/// Screen a layout was saved on.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutScreen {
+    Tables,
+    Editor,
+    Results,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SavedLayout {
+    pub name: String,
+    pub screen: LayoutScreen,
+    pub split_view: bool,
+    pub split_editor_percent: u16, // Editor's share of the split view
+    pub table: Option<String>,     // Qualified name of the selected table
+    #[serde(default)]
+    pub schemas: Vec<String>, // PostgreSQL schemas that were expanded
+    #[serde(default)]
+    pub query: String,
+}
+
+impl SavedLayout {
+    /// What restoring the layout does, e.g. `results of the query, split 40%`.
+    pub fn describe(&self) -> String {
+        let mut parts = vec![match self.screen {
+            LayoutScreen::Tables => match &self.table {
+                Some(table) => format!("table {}", table),
+                None => "tables".to_string(),
+            },
+            LayoutScreen::Editor => "query editor".to_string(),
+            LayoutScreen::Results => "runs the query".to_string(),
+        }];
+        if self.split_view {
+            parts.push(format!("split {}%", self.split_editor_percent));
+        }
+        if let Some(line) = self.query.lines().find(|l| !l.trim().is_empty()) {
+            parts.push(line.trim().to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+/// The layouts popup.
+#[derive(Debug, Clone, Default)]
+pub struct LayoutPicker {
+    pub selected: usize,
+    pub confirming_delete: bool, // `d` was pressed once on the selected layout
+}
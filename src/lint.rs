@@ -0,0 +1,423 @@
+// Pre-flight checks of a query against the schema the app has loaded: tables
+// and columns that don't exist, UPDATE/DELETE without WHERE, and joins with no
+// join condition. Names the app can't verify (other databases, schemas not
+// loaded yet, system catalogs, derived tables) are never reported, so every
+// warning points at a likely mistake.
+
+use crate::database::TableInfo;
+use crate::dialect::Dialect;
+use sqlparser::ast::{
+    AssignmentTarget, Expr, FromTable, Ident, JoinConstraint, JoinOperator, ObjectName, Query,
+    SelectItem, SetExpr, Statement, TableFactor, TableWithJoins, Visit, Visitor,
+};
+use sqlparser::parser::Parser;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::ControlFlow;
+
+const SYSTEM_SCHEMAS: [&str; 5] = [
+    "information_schema",
+    "pg_catalog",
+    "mysql",
+    "performance_schema",
+    "sys",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintWarning {
+    UnknownTable(String),
+    UnknownColumn(String),
+    CrossJoin(String, String),
+    MissingWhere(&'static str, String), // Statement kind and its table
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LintWarning::UnknownTable(table) => write!(f, "Unknown table {}", table),
+            LintWarning::UnknownColumn(column) => write!(f, "Unknown column {}", column),
+            LintWarning::CrossJoin(left, right) => write!(
+                f,
+                "{} and {} are joined without a join condition (cross join)",
+                left, right
+            ),
+            LintWarning::MissingWhere(kind, table) => {
+                write!(
+                    f,
+                    "{} on {} has no WHERE clause and affects every row",
+                    kind, table
+                )
+            }
+        }
+    }
+}
+
+/// The tables the app knows and the columns it has looked up.
+pub struct Catalog<'a> {
+    pub tables: &'a [TableInfo],
+    // Schemas whose tables are all in `tables`; `None` when every table is
+    // (PostgreSQL loads a schema's tables when it is first expanded)
+    pub loaded_schemas: Option<Vec<String>>,
+    // Schema unqualified names are looked for in on PostgreSQL
+    pub default_schema: String,
+    pub columns: &'a HashMap<String, Vec<String>>, // By qualified table name
+}
+
+enum Lookup<'a> {
+    Found(&'a TableInfo),
+    Missing,
+    Unknowable,
+}
+
+impl Catalog<'_> {
+    fn lookup(&self, name: &ObjectName) -> Lookup<'_> {
+        let (schema, table) = match name.0.as_slice() {
+            [table] => (None, &table.value),
+            [schema, table] => (Some(&schema.value), &table.value),
+            _ => return Lookup::Unknowable,
+        };
+        let table_lower = table.to_lowercase();
+        if table_lower.starts_with("pg_")
+            || table_lower.starts_with("sqlite_")
+            || schema.is_some_and(|s| SYSTEM_SCHEMAS.contains(&s.to_lowercase().as_str()))
+        {
+            return Lookup::Unknowable;
+        }
+
+        let found = self.tables.iter().find(|t| {
+            t.name.eq_ignore_ascii_case(table)
+                && schema.is_none_or(|s| {
+                    t.schema
+                        .as_ref()
+                        .is_some_and(|ts| ts.eq_ignore_ascii_case(s))
+                })
+        });
+        if let Some(found) = found {
+            return Lookup::Found(found);
+        }
+        let searched = match (&self.loaded_schemas, schema) {
+            // Another database (MySQL) or attached file (SQLite)
+            (None, Some(_)) => false,
+            (None, None) => true,
+            (Some(loaded), schema) => {
+                let schema = schema.unwrap_or(&self.default_schema);
+                loaded.iter().any(|l| l.eq_ignore_ascii_case(schema))
+            }
+        };
+        if searched {
+            Lookup::Missing
+        } else {
+            Lookup::Unknowable
+        }
+    }
+
+    fn has_column(&self, table: &TableInfo, column: &str) -> Option<bool> {
+        let columns = self.columns.get(&table.qualified_name())?;
+        Some(columns.iter().any(|c| c.eq_ignore_ascii_case(column)))
+    }
+}
+
+/// Names of the tables the query refers to, for looking up their columns
+/// before [`lint`] runs. Unparsed queries refer to none.
+pub fn referenced_tables(query: &str, dialect: Dialect, catalog: &Catalog) -> Vec<TableInfo> {
+    let mut tables: Vec<TableInfo> = Vec::new();
+    for statement in parse(query, dialect) {
+        let mut names = Names::default();
+        let _ = statement.visit(&mut names);
+        for (name, _) in &names.relations {
+            if let Lookup::Found(table) = catalog.lookup(name)
+                && !tables
+                    .iter()
+                    .any(|t| t.qualified_name() == table.qualified_name())
+            {
+                tables.push(table.clone());
+            }
+        }
+    }
+    tables
+}
+
+/// Warnings for every statement of `query` that reads or changes data.
+/// Queries that don't parse get none; the database reports their errors.
+pub fn lint(query: &str, dialect: Dialect, catalog: &Catalog) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    for statement in parse(query, dialect) {
+        for warning in lint_statement(&statement, catalog) {
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+    }
+    warnings
+}
+
+fn parse(query: &str, dialect: Dialect) -> Vec<Statement> {
+    let parser = dialect.parser();
+    Parser::parse_sql(parser.as_ref(), query)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| {
+            matches!(
+                s,
+                Statement::Query(_)
+                    | Statement::Insert(_)
+                    | Statement::Update { .. }
+                    | Statement::Delete(_)
+            )
+        })
+        .collect()
+}
+
+/// What a statement names, gathered by walking it.
+#[derive(Default)]
+struct Names {
+    relations: Vec<(ObjectName, Option<String>)>, // Table and alias
+    ctes: Vec<String>,
+    aliases: Vec<String>,     // Output column names given with AS
+    opaque: bool,             // Reads something whose columns aren't known
+    columns: Vec<Vec<Ident>>, // Column references, qualified or not
+    cross_joins: Vec<(String, String)>,
+}
+
+impl Visitor for Names {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<()> {
+        if let Some(with) = &query.with {
+            self.ctes.extend(
+                with.cte_tables
+                    .iter()
+                    .map(|cte| cte.alias.name.value.clone()),
+            );
+        }
+        self.visit_set_expr(&query.body);
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table_factor(&mut self, factor: &TableFactor) -> ControlFlow<()> {
+        match factor {
+            TableFactor::Table {
+                name,
+                alias,
+                args: None,
+                ..
+            } => self
+                .relations
+                .push((name.clone(), alias.as_ref().map(|a| a.name.value.clone()))),
+            _ => self.opaque = true,
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+        match expr {
+            Expr::Identifier(ident) => self.columns.push(vec![ident.clone()]),
+            Expr::CompoundIdentifier(parts) => self.columns.push(parts.clone()),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_statement(&mut self, statement: &Statement) -> ControlFlow<()> {
+        match statement {
+            Statement::Insert(insert) => {
+                self.relations.push((insert.table_name.clone(), None));
+                let table = insert.table_name.0.clone();
+                for column in &insert.columns {
+                    let mut parts = table.clone();
+                    parts.push(column.clone());
+                    self.columns.push(parts);
+                }
+            }
+            Statement::Update {
+                table, assignments, ..
+            } => {
+                let target = relation_name(table);
+                for assignment in assignments {
+                    let names = match &assignment.target {
+                        AssignmentTarget::ColumnName(name) => vec![name],
+                        AssignmentTarget::Tuple(names) => names.iter().collect(),
+                    };
+                    for name in names {
+                        if let (Some(target), Some(column)) = (&target, name.0.last()) {
+                            let mut parts = target.0.clone();
+                            parts.push(column.clone());
+                            self.columns.push(parts);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+impl Names {
+    fn visit_set_expr(&mut self, body: &SetExpr) {
+        match body {
+            SetExpr::Select(select) => {
+                for item in &select.projection {
+                    if let SelectItem::ExprWithAlias { alias, .. } = item {
+                        self.aliases.push(alias.value.clone());
+                    }
+                }
+                for table in &select.from {
+                    self.find_cross_joins(table);
+                }
+                let joined_by_condition = select.selection.as_ref().is_some_and(compares_columns);
+                if select.from.len() > 1 && !joined_by_condition {
+                    let names: Vec<String> =
+                        select.from.iter().map(|t| t.relation.to_string()).collect();
+                    self.cross_joins
+                        .push((names[0].clone(), names[1..].join(", ")));
+                }
+            }
+            SetExpr::SetOperation { left, right, .. } => {
+                self.visit_set_expr(left);
+                self.visit_set_expr(right);
+            }
+            _ => {}
+        }
+    }
+
+    fn find_cross_joins(&mut self, table: &TableWithJoins) {
+        let mut left = table.relation.to_string();
+        for join in &table.joins {
+            let right = join.relation.to_string();
+            if matches!(
+                join.join_operator,
+                JoinOperator::CrossJoin | JoinOperator::Inner(JoinConstraint::None)
+            ) {
+                self.cross_joins.push((left.clone(), right.clone()));
+            }
+            left = right;
+        }
+    }
+}
+
+/// The table an UPDATE changes.
+fn relation_name(table: &TableWithJoins) -> Option<ObjectName> {
+    match &table.relation {
+        TableFactor::Table { name, .. } => Some(name.clone()),
+        _ => None,
+    }
+}
+
+/// Whether a WHERE clause compares two columns somewhere, as a join
+/// condition written in WHERE does.
+fn compares_columns(expr: &Expr) -> bool {
+    let is_column = |e: &Expr| matches!(e, Expr::Identifier(_) | Expr::CompoundIdentifier(_));
+    match expr {
+        Expr::BinaryOp { left, right, .. } => {
+            (is_column(left) && is_column(right))
+                || compares_columns(left)
+                || compares_columns(right)
+        }
+        Expr::Nested(inner) => compares_columns(inner),
+        _ => false,
+    }
+}
+
+fn lint_statement(statement: &Statement, catalog: &Catalog) -> Vec<LintWarning> {
+    let mut names = Names::default();
+    let _ = statement.visit(&mut names);
+    let mut warnings = Vec::new();
+
+    match statement {
+        Statement::Update {
+            table,
+            selection: None,
+            ..
+        } => warnings.push(LintWarning::MissingWhere(
+            "UPDATE",
+            table.relation.to_string(),
+        )),
+        Statement::Delete(delete) if delete.selection.is_none() => {
+            let tables = match &delete.from {
+                FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables) => tables,
+            };
+            let names: Vec<String> = tables.iter().map(|t| t.relation.to_string()).collect();
+            warnings.push(LintWarning::MissingWhere("DELETE", names.join(", ")));
+        }
+        _ => {}
+    }
+
+    // Resolve each table and the names it can be referred to by
+    let mut known: Vec<(&TableInfo, String)> = Vec::new();
+    let mut all_known = !names.opaque;
+    for (name, alias) in &names.relations {
+        let is_cte = name.0.len() == 1
+            && names
+                .ctes
+                .iter()
+                .any(|cte| cte.eq_ignore_ascii_case(&name.0[0].value));
+        if is_cte {
+            all_known = false;
+            continue;
+        }
+        match catalog.lookup(name) {
+            Lookup::Found(table) => {
+                let reference = alias
+                    .clone()
+                    .unwrap_or_else(|| name.0.last().map(|i| i.value.clone()).unwrap_or_default());
+                known.push((table, reference));
+            }
+            Lookup::Missing => {
+                warnings.push(LintWarning::UnknownTable(name.to_string()));
+                all_known = false;
+            }
+            Lookup::Unknowable => all_known = false,
+        }
+    }
+
+    for parts in &names.columns {
+        let Some((column, qualifier)) = parts.split_last() else {
+            continue;
+        };
+        if column.value.starts_with(['@', ':', '$']) || column.value.eq_ignore_ascii_case("DEFAULT")
+        {
+            continue;
+        }
+        let found = match qualifier.last() {
+            // `alias.column` or `schema.table.column`: only the named table
+            Some(qualifier) => {
+                let Some((table, _)) = known.iter().find(|(table, reference)| {
+                    reference.eq_ignore_ascii_case(&qualifier.value)
+                        || table.name.eq_ignore_ascii_case(&qualifier.value)
+                }) else {
+                    continue;
+                };
+                catalog.has_column(table, &column.value)
+            }
+            None if all_known && !known.is_empty() => {
+                if names
+                    .aliases
+                    .iter()
+                    .any(|a| a.eq_ignore_ascii_case(&column.value))
+                {
+                    continue;
+                }
+                // Unknown until every table's columns have been looked up
+                known
+                    .iter()
+                    .map(|(table, _)| catalog.has_column(table, &column.value))
+                    .try_fold(false, |any, has| has.map(|has| any || has))
+            }
+            None => continue,
+        };
+        if found == Some(false) {
+            let name = parts
+                .iter()
+                .map(|p| p.value.as_str())
+                .collect::<Vec<_>>()
+                .join(".");
+            warnings.push(LintWarning::UnknownColumn(name));
+        }
+    }
+
+    for (left, right) in names.cross_joins {
+        warnings.push(LintWarning::CrossJoin(left, right));
+    }
+    warnings
+}
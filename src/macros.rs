@@ -0,0 +1,122 @@
+// Keyboard macros: keystrokes recorded into a register (a-z) and replayed later,
+// saved to macros.json in the config directory. Keys are stored by name
+// ("Enter", "Ctrl+r", "F5") so the file can be read and edited by hand.
+
+use crate::settings::config_dir;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+
+/// Recorded keys by register.
+pub type MacroRegisters = BTreeMap<char, Vec<String>>;
+
+/// What the next key chooses a register for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MacroPrompt {
+    Record,
+    Replay,
+}
+
+/// A macro being recorded.
+#[derive(Debug, Clone)]
+pub struct MacroRecording {
+    pub register: char,
+    pub keys: Vec<String>,
+}
+
+/// A macro being replayed, one key at a time.
+#[derive(Debug, Clone)]
+pub struct MacroReplay {
+    pub register: char,
+    pub keys: VecDeque<KeyEvent>,
+}
+
+pub fn is_register(c: char) -> bool {
+    c.is_ascii_lowercase()
+}
+
+pub fn load() -> Result<MacroRegisters> {
+    let file = config_dir()?.join("macros.json");
+    if file.exists() {
+        Ok(serde_json::from_str(&fs::read_to_string(file)?)?)
+    } else {
+        Ok(MacroRegisters::new())
+    }
+}
+
+pub fn save(registers: &MacroRegisters) -> Result<()> {
+    let file = config_dir()?.join("macros.json");
+    fs::write(file, serde_json::to_string_pretty(registers)?)?;
+    Ok(())
+}
+
+const MODIFIER_NAMES: [(KeyModifiers, &str); 3] = [
+    (KeyModifiers::CONTROL, "Ctrl+"),
+    (KeyModifiers::ALT, "Alt+"),
+    (KeyModifiers::SHIFT, "Shift+"),
+];
+
+const KEY_NAMES: [(KeyCode, &str); 15] = [
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::Esc, "Esc"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::BackTab, "BackTab"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Delete, "Delete"),
+    (KeyCode::Insert, "Insert"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::End, "End"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+    (KeyCode::Up, "Up"),
+    (KeyCode::Down, "Down"),
+    (KeyCode::Left, "Left"),
+    (KeyCode::Right, "Right"),
+];
+
+/// Name of a key as stored in `macros.json`, e.g. `Ctrl+r` or `F5`. `None` for
+/// keys a macro can't hold.
+pub fn key_name(key: KeyEvent) -> Option<String> {
+    let code = match key.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        code => KEY_NAMES
+            .iter()
+            .find(|(known, _)| *known == code)?
+            .1
+            .to_string(),
+    };
+    let modifiers: String = MODIFIER_NAMES
+        .iter()
+        .filter(|(modifier, _)| key.modifiers.contains(*modifier))
+        .map(|(_, name)| *name)
+        .collect();
+    Some(modifiers + &code)
+}
+
+/// Reverse of `key_name`.
+pub fn parse_key(name: &str) -> Option<KeyEvent> {
+    let mut rest = name;
+    let mut modifiers = KeyModifiers::NONE;
+    // A lone "+" is the plus key, not a modifier separator
+    while let Some((modifier, prefix)) = MODIFIER_NAMES
+        .iter()
+        .find(|(_, prefix)| rest.len() > prefix.len() && rest.starts_with(prefix))
+    {
+        modifiers |= *modifier;
+        rest = &rest[prefix.len()..];
+    }
+
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ if rest == "Space" => KeyCode::Char(' '),
+        _ => match rest.strip_prefix('F').and_then(|n| n.parse().ok()) {
+            Some(n) => KeyCode::F(n),
+            None => KEY_NAMES.iter().find(|(_, known)| *known == rest)?.0,
+        },
+    };
+    Some(KeyEvent::new(code, modifiers))
+}
@@ -1,16 +1,66 @@
+mod aggregate;
+mod altercolumn;
 mod app;
+mod assistant;
+mod catalog;
+mod chart;
+mod clipboard;
+mod colfilter;
+mod comments;
+mod dashboard;
 mod database;
+mod datepicker;
 mod demo;
+mod dialect;
+mod diff;
+mod doctor;
+mod draft;
+mod dump;
+mod er;
 mod event;
+mod export;
+mod external;
+mod filebrowser;
+mod filewatch;
+mod fuzzy;
+mod grants;
+mod hexview;
+mod indexes;
+mod interpolate;
+mod jobs;
+mod jsonview;
+mod keymap;
+mod layouts;
+mod lint;
+mod macros;
+mod mouse;
+mod notifications;
+mod pragma;
+mod profile;
+mod querylog;
+mod recording;
+mod roles;
+mod rowcount;
+mod script;
+mod session;
+mod settings;
+mod sql;
+mod storage;
+mod tablecopy;
+mod terminal;
+mod text;
+mod textinput;
+mod theme;
+mod transfer;
 mod ui;
+mod undo;
+mod units;
+mod users;
+mod vim;
 
 use anyhow::Result;
 use app::App;
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture, Event},
-    execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
-};
+use crossterm::event::Event;
 use ratatui::{
     Terminal,
     backend::{Backend, CrosstermBackend},
@@ -19,22 +69,30 @@ use std::{
     io,
     time::{Duration, Instant},
 };
+use terminal::TerminalGuard;
+
+// Pause between replayed macro keys, so each step is drawn
+const MACRO_KEY_INTERVAL: Duration = Duration::from_millis(10);
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Check if we should create demo database
     let args: Vec<String> = std::env::args().collect();
+    // `--create-demo [connection string]`: demo.db, or the tables on that database
     if args.len() > 1 && args[1] == "--create-demo" {
         println!("Creating demo database...");
-        demo::create_demo_database().await?;
+        demo::create_demo_database(args.get(2).map(String::as_str)).await?;
         return Ok(());
     }
+    if args.len() > 1 && args[1] == "doctor" {
+        let healthy = doctor::run().await?;
+        std::process::exit(if healthy { 0 } else { 1 });
+    }
 
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+    // Setup terminal, restored when the guard drops or the app panics
+    terminal::install_panic_hook();
+    let guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
     // Create app and run it
@@ -48,16 +106,16 @@ async fn main() -> Result<()> {
         );
     }
 
+    // Open the last used connection straight away if auto-connect is on; it
+    // waits until a saved session is restored or declined
+    if !app.offer_session_restore() {
+        app.auto_connect();
+    }
+
     let res = run_app(&mut terminal, app).await;
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    drop(guard);
 
     if let Err(err) = res {
         println!("{:?}", err)
@@ -66,21 +124,57 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Hands the terminal to an external program and takes it back once it exits,
+/// redrawing the whole screen.
+fn run_external<B: Backend>(
+    terminal: &mut Terminal<B>,
+    command: &external::ExternalCommand,
+) -> Result<std::process::ExitStatus> {
+    terminal::suspend();
+    let status = command.run();
+    terminal::resume()?;
+    terminal.clear()?;
+    status
+}
+
 async fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<()> {
     let mut last_tick = Instant::now();
     let tick_rate = Duration::from_millis(250);
 
     loop {
+        app.track_notifications();
+        app.track_query_edits();
         terminal.draw(|f| ui::draw(f, &mut app))?;
 
-        let timeout = tick_rate
+        let mut timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
+        if app.macro_replay.is_some() {
+            timeout = timeout.min(MACRO_KEY_INTERVAL);
+        }
 
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = crossterm::event::read()? {
-                event::handle_key_event(&mut app, key).await?;
-            }
+            // Errors from handling input are shown in the error popup
+            let handled = match crossterm::event::read()? {
+                // Any key pressed during a replay stops it
+                Event::Key(_) if app.macro_replay.is_some() => {
+                    app.stop_macro_replay();
+                    Ok(())
+                }
+                Event::Key(key) => event::handle_key_event(&mut app, key).await,
+                Event::Mouse(mouse) => event::handle_mouse_event(&mut app, mouse).await,
+                Event::Paste(text) => event::handle_paste(&mut app, &text).await,
+                _ => Ok(()),
+            };
+            app.report_error(handled);
+        } else if let Some(key) = app.next_macro_key() {
+            let handled = event::handle_key_event(&mut app, key).await;
+            app.report_error(handled);
+        }
+
+        if let Some(command) = app.external_command.take() {
+            let status = run_external(terminal, &command);
+            app.finish_external_command(command, status);
         }
 
         if last_tick.elapsed() >= tick_rate {
@@ -89,9 +183,26 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result
 
             // Check if connection task has completed
             app.check_connection_task().await;
+            app.check_row_count_task();
+            app.check_query_jobs().await;
+            app.check_server_monitor_refresh().await;
+            app.check_dashboard().await;
+            app.check_dump_task().await;
+            app.check_transfer_task().await;
+            app.check_script_task().await;
+            app.check_copy_task().await;
+            app.check_seed_task().await;
+            app.check_profile_task().await;
+            app.check_integrity_task().await;
+            app.check_assistant_task().await;
+            app.check_file_watch().await;
+            app.autosave_draft();
         }
 
         if app.should_quit {
+            // Reported once the terminal is restored
+            app.discard_draft();
+            app.save_session()?;
             break;
         }
     }
@@ -0,0 +1,57 @@
+// Screen regions recorded while drawing a frame, so mouse events can be mapped
+// back to the list item, grid cell or tab under the pointer. Keyboard scrolling
+// reads the results grid's height from them too.
+
+use crate::app::StatisticsView;
+use crate::er::ErView;
+use ratatui::layout::{Position, Rect};
+
+/// Rows of a rendered list or table body.
+#[derive(Debug, Clone, Copy)]
+pub struct ListArea {
+    pub area: Rect,    // One line per item, inside the borders
+    pub offset: usize, // Index of the first visible item
+    pub len: usize,
+}
+
+impl ListArea {
+    pub fn index_at(&self, column: u16, row: u16) -> Option<usize> {
+        if !self.area.contains(Position::new(column, row)) {
+            return None;
+        }
+        let index = self.offset + (row - self.area.y) as usize;
+        (index < self.len).then_some(index)
+    }
+}
+
+/// The results grid: a header line above the rows, split into columns.
+#[derive(Debug, Clone)]
+pub struct GridArea {
+    pub header_y: u16,
+    pub rows: ListArea,           // Offset is the grid's vertical scroll
+    pub columns: Vec<(u16, u16)>, // Absolute x and width of each column
+}
+
+impl GridArea {
+    pub fn column_at(&self, column: u16) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|&(x, width)| column >= x && column < x + width)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MouseAreas {
+    pub connection_list: Option<ListArea>,
+    pub table_list: Option<ListArea>,
+    pub results_pane: Option<Rect>, // Whole results widget, including borders
+    pub results_grid: Option<GridArea>,
+    pub statistics_tabs: Vec<(Rect, StatisticsView)>,
+    pub er_tabs: Vec<(Rect, ErView)>,
+    pub file_browser: Option<ListArea>,
+    pub jobs: Option<ListArea>,
+    pub sessions: Option<ListArea>,
+    pub routines: Option<ListArea>,
+    pub roles: Option<ListArea>,
+    pub tablespaces: Option<ListArea>,
+}
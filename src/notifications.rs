@@ -0,0 +1,116 @@
+// Notification history: every status and error message is kept with its time
+// and level, so messages dismissed by the next keypress can be read again, and
+// recent ones are shown as toasts for a few seconds.
+
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+
+// Messages kept in the history
+const HISTORY_LIMIT: usize = 200;
+
+// How long a toast stays on screen
+const TOAST_SECONDS: i64 = 5;
+
+// Toasts shown at once
+const MAX_TOASTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotificationLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+impl NotificationLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationLevel::Info => "info",
+            NotificationLevel::Warn => "warn",
+            NotificationLevel::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub level: NotificationLevel,
+    pub message: String,
+    pub at: DateTime<Local>,
+    progress: bool, // Progress of a background task, replaced by the next update
+}
+
+#[derive(Debug, Default)]
+pub struct Notifications {
+    history: VecDeque<Notification>, // Oldest first
+    last_status: Option<String>,
+    last_error: Option<String>,
+}
+
+impl Notifications {
+    /// Records the status and error messages if they changed since the last
+    /// call. While `in_progress`, consecutive status updates (a dump's row
+    /// count, say) replace each other instead of filling the history.
+    pub fn observe(&mut self, status: &Option<String>, error: &Option<String>, in_progress: bool) {
+        if *status != self.last_status {
+            self.last_status = status.clone();
+            if let Some(message) = status {
+                // Statuses carry warnings inline, e.g. "... | Warning: ..."
+                let level = if message.to_lowercase().contains("warning") {
+                    NotificationLevel::Warn
+                } else {
+                    NotificationLevel::Info
+                };
+                if in_progress && self.history.back().is_some_and(|last| last.progress) {
+                    self.history.pop_back();
+                }
+                self.push(level, message, in_progress);
+            }
+        }
+        if *error != self.last_error {
+            self.last_error = error.clone();
+            if let Some(message) = error {
+                self.push(NotificationLevel::Error, message, false);
+            }
+        }
+    }
+
+    fn push(&mut self, level: NotificationLevel, message: &str, progress: bool) {
+        self.history.push_back(Notification {
+            level,
+            message: message.to_string(),
+            at: Local::now(),
+            progress,
+        });
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+    }
+
+    /// Notifications, newest first.
+    pub fn newest_first(&self) -> impl Iterator<Item = &Notification> {
+        self.history.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    /// Recent notifications to show as toasts, newest first. Errors get their
+    /// own popup and `shown` is already in the status bar, so both are left out.
+    pub fn toasts(&self, shown: Option<&str>) -> Vec<&Notification> {
+        let now = Local::now();
+        self.newest_first()
+            .take_while(|n| (now - n.at).num_seconds() < TOAST_SECONDS)
+            .filter(|n| n.level != NotificationLevel::Error && Some(n.message.as_str()) != shown)
+            .take(MAX_TOASTS)
+            .collect()
+    }
+}
@@ -0,0 +1,157 @@
+// PRAGMA dashboard of a SQLite connection, its counterpart of the server
+// monitor: the settings that matter most when inspecting a database file,
+// changed only after a second press, and an integrity check run in the
+// background since it reads the whole file.
+
+use crate::database::DatabasePool;
+use anyhow::{Result, anyhow};
+use sqlx::Row;
+use tokio::task::JoinHandle;
+
+// Problems listed by the integrity check
+const INTEGRITY_MAX_ERRORS: usize = 100;
+
+/// A PRAGMA shown on the dashboard.
+#[derive(Debug)]
+pub struct Pragma {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub values: &'static [&'static str], // What it can be set to
+    pub caution: &'static str,           // Shown before it is set
+}
+
+pub const PRAGMAS: [Pragma; 4] = [
+    Pragma {
+        name: "journal_mode",
+        description: "How transactions are journaled",
+        values: &["DELETE", "TRUNCATE", "PERSIST", "MEMORY", "WAL", "OFF"],
+        caution: "saved in the file for WAL; MEMORY and OFF can corrupt the database on a crash",
+    },
+    Pragma {
+        name: "synchronous",
+        description: "How often SQLite waits for writes to reach the disk",
+        values: &["OFF", "NORMAL", "FULL", "EXTRA"],
+        caution: "lasts for this connection; OFF can corrupt the database on power loss",
+    },
+    Pragma {
+        name: "foreign_keys",
+        description: "Whether foreign key constraints are enforced",
+        values: &["OFF", "ON"],
+        caution: "lasts for this connection; rows already stored aren't checked",
+    },
+    Pragma {
+        name: "page_size",
+        description: "Bytes per database page",
+        values: &["1024", "2048", "4096", "8192", "16384", "32768", "65536"],
+        caution: "only applies to a new database or after VACUUM, and never in WAL mode",
+    },
+];
+
+/// State of the PRAGMA screen.
+#[derive(Debug, Default)]
+pub struct PragmaDashboard {
+    pub values: Vec<Result<String, String>>, // Current value of each of `PRAGMAS`
+    pub selected: usize,
+    pub proposed: Option<usize>, // Index into the selected PRAGMA's values
+    pub confirming: bool,        // The proposed value waits for a second Enter
+    pub integrity: Option<Result<Vec<String>, String>>, // Problems found; "ok" alone when none
+    pub integrity_task: Option<JoinHandle<Result<Vec<String>>>>,
+}
+
+impl PragmaDashboard {
+    pub fn pragma(&self) -> &'static Pragma {
+        &PRAGMAS[self.selected]
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(PRAGMAS.len() - 1);
+        self.proposed = None;
+        self.confirming = false;
+    }
+
+    /// Proposes the next or previous value of the selected PRAGMA, starting
+    /// from its current one.
+    pub fn cycle_proposed(&mut self, forward: bool) {
+        let values = self.pragma().values;
+        let current = self.proposed.or_else(|| {
+            let value = self.values.get(self.selected)?.as_ref().ok()?;
+            values.iter().position(|v| v.eq_ignore_ascii_case(value))
+        });
+        self.proposed = Some(match current {
+            Some(i) if forward => (i + 1) % values.len(),
+            Some(i) => (i + values.len() - 1) % values.len(),
+            None => 0,
+        });
+        self.confirming = false;
+    }
+
+    pub fn proposed_value(&self) -> Option<&'static str> {
+        self.proposed.map(|i| self.pragma().values[i])
+    }
+}
+
+fn sqlite_pool(pool: &DatabasePool) -> Result<&sqlx::SqlitePool> {
+    match pool {
+        DatabasePool::SQLite(pool) => Ok(pool),
+        _ => Err(anyhow!("PRAGMAs are only shown for SQLite")),
+    }
+}
+
+/// Reads every PRAGMA of the dashboard, with the numeric ones named.
+pub async fn read_all(pool: &DatabasePool) -> Result<Vec<Result<String, String>>> {
+    let pool = sqlite_pool(pool)?;
+    let mut values = Vec::new();
+    for pragma in &PRAGMAS {
+        let value = match sqlx::query(&format!("PRAGMA {}", pragma.name))
+            .fetch_one(pool)
+            .await
+        {
+            Ok(row) => Ok(match pragma.name {
+                "synchronous" => match row.get::<i64, _>(0) {
+                    0 => "OFF".to_string(),
+                    1 => "NORMAL".to_string(),
+                    2 => "FULL".to_string(),
+                    3 => "EXTRA".to_string(),
+                    n => n.to_string(),
+                },
+                "foreign_keys" => if row.get::<i64, _>(0) == 0 {
+                    "OFF"
+                } else {
+                    "ON"
+                }
+                .to_string(),
+                "page_size" => row.get::<i64, _>(0).to_string(),
+                _ => row.get::<String, _>(0).to_uppercase(),
+            }),
+            Err(e) => Err(e.to_string()),
+        };
+        values.push(value);
+    }
+    Ok(values)
+}
+
+/// Sets a PRAGMA of the dashboard to one of its listed values.
+pub async fn set(pool: &DatabasePool, pragma: &Pragma, value: &str) -> Result<()> {
+    if !pragma.values.contains(&value) {
+        return Err(anyhow!("{} can't be set to {}", pragma.name, value));
+    }
+    let pool = sqlite_pool(pool)?;
+    sqlx::raw_sql(&format!("PRAGMA {} = {}", pragma.name, value))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Runs `PRAGMA integrity_check` in the background.
+pub fn start_integrity_check(pool: DatabasePool) -> JoinHandle<Result<Vec<String>>> {
+    tokio::spawn(async move {
+        let pool = sqlite_pool(&pool)?;
+        let rows = sqlx::query(&format!("PRAGMA integrity_check({})", INTEGRITY_MAX_ERRORS))
+            .fetch_all(pool)
+            .await?;
+        Ok(rows.iter().map(|row| row.get::<String, _>(0)).collect())
+    })
+}
@@ -0,0 +1,157 @@
+// Quick profile of one column: row and null counts, distinct values, the
+// smallest and largest value and the most frequent values. The database does
+// the work, over a whole table or over the result of the last query, in a
+// spawned task so a large table doesn't freeze the screen.
+
+use crate::database::DatabasePool;
+use crate::dialect::Dialect;
+use anyhow::{Result, anyhow};
+use tokio::task::JoinHandle;
+
+// Most frequent values listed
+const TOP_VALUES: usize = 10;
+
+/// What a column is profiled over.
+#[derive(Debug, Clone)]
+pub enum ProfileSource {
+    Table(String), // Qualified table name
+    Query(String), // SELECT whose result is profiled
+}
+
+impl ProfileSource {
+    pub fn describe(&self) -> String {
+        match self {
+            ProfileSource::Table(name) => format!("table {}", name),
+            ProfileSource::Query(_) => "result of the last query".to_string(),
+        }
+    }
+
+    /// The source for a FROM clause; `alias` names the derived table of a
+    /// query.
+    pub fn relation(&self, dialect: Dialect, alias: &str) -> String {
+        match self {
+            ProfileSource::Table(name) => dialect.quote_table(name),
+            // PostgreSQL and MySQL require an alias on a derived table
+            ProfileSource::Query(query) => {
+                format!("({}) AS {}", query.trim().trim_end_matches(';'), alias)
+            }
+        }
+    }
+}
+
+/// Statistics of one column. The parts after the counts are `None` when the
+/// database can't compute them for the column's type (MIN of a JSON column,
+/// say).
+#[derive(Debug, Clone)]
+pub struct ColumnProfile {
+    pub rows: usize,
+    pub non_null: usize,
+    pub distinct: Option<usize>,
+    pub min_max: Option<(String, String)>,
+    pub top_values: Option<Vec<(Option<String>, usize)>>, // Value (None for NULL) and its count, most frequent first
+}
+
+impl ColumnProfile {
+    pub fn null_ratio(&self) -> f64 {
+        if self.rows == 0 {
+            0.0
+        } else {
+            (self.rows - self.non_null) as f64 / self.rows as f64
+        }
+    }
+}
+
+/// The profile popup: the columns that can be profiled, the one shown, and
+/// its profile once computed.
+#[derive(Debug)]
+pub struct ProfileView {
+    pub source: ProfileSource,
+    pub columns: Vec<String>,
+    pub selected: usize, // Index into `columns`
+    pub profile: Option<Result<ColumnProfile, String>>,
+    pub task: Option<JoinHandle<Result<ColumnProfile>>>, // Profile being computed
+}
+
+impl ProfileView {
+    pub fn column(&self) -> &str {
+        &self.columns[self.selected]
+    }
+}
+
+/// Runs the profiling queries for `column` of `source`.
+pub async fn profile_column(
+    pool: DatabasePool,
+    source: ProfileSource,
+    column: String,
+) -> Result<ColumnProfile> {
+    let dialect = Dialect::of(&pool.database_type());
+    let from = source.relation(dialect, "profiled");
+    let quoted = dialect.quote_identifier(&column);
+
+    let counts = pool
+        .execute_query(&format!("SELECT COUNT(*), COUNT({}) FROM {}", quoted, from))
+        .await?;
+    let count = |row: usize, i: usize| -> Result<usize> {
+        counts
+            .rows
+            .get(row)
+            .and_then(|r| r.get(i))
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| anyhow!("Unexpected COUNT result"))
+    };
+    let rows = count(0, 0)?;
+    let non_null = count(0, 1)?;
+
+    // The rest can fail on types without equality or ordering, which only
+    // leaves that part out
+    let distinct = pool
+        .execute_query(&format!("SELECT COUNT(DISTINCT {}) FROM {}", quoted, from))
+        .await
+        .ok()
+        .and_then(|r| r.rows.first()?.first()?.parse().ok());
+
+    let min_max = pool
+        .execute_query(&format!(
+            "SELECT {}, {} FROM {}",
+            dialect.cast_to_text(&format!("MIN({})", quoted)),
+            dialect.cast_to_text(&format!("MAX({})", quoted)),
+            from
+        ))
+        .await
+        .ok()
+        .and_then(|r| {
+            let row = r.rows.into_iter().next()?;
+            Some((row.first()?.clone(), row.get(1)?.clone()))
+        });
+
+    // The NULL group is told apart by counting no values, as some drivers
+    // read NULL as an empty string
+    let top_values = pool
+        .execute_query(&format!(
+            "SELECT {}, COUNT(*), COUNT({}) FROM {} GROUP BY {} ORDER BY COUNT(*) DESC, 1{}",
+            dialect.cast_to_text(&quoted),
+            quoted,
+            from,
+            quoted,
+            dialect.limit_clause(TOP_VALUES)
+        ))
+        .await
+        .ok()
+        .map(|r| {
+            r.rows
+                .into_iter()
+                .filter_map(|row| {
+                    let value = (row.get(2)? != "0").then(|| row[0].clone());
+                    Some((value, row.get(1)?.parse().ok()?))
+                })
+                .collect()
+        });
+
+    Ok(ColumnProfile {
+        rows,
+        non_null,
+        distinct,
+        min_max,
+        top_values,
+    })
+}
@@ -0,0 +1,137 @@
+use crate::settings::config_dir;
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+const LOG_FILE: &str = "query_log.jsonl";
+
+// Only the most recent entries are kept when the log is loaded
+const MAX_LOADED_ENTRIES: usize = 10_000;
+
+// Number of queries shown in each statistics list
+const TOP_QUERIES: usize = 50;
+
+/// One executed query, persisted as a line of `query_log.jsonl`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct QueryLogEntry {
+    pub executed_at: DateTime<Local>,
+    pub connection: String,
+    pub query: String,
+    pub execution_ms: u64,
+    pub row_count: Option<usize>,
+    pub error: Option<String>,
+}
+
+/// Appends an entry to the query log.
+pub fn append(entry: &QueryLogEntry) -> Result<()> {
+    let path = config_dir()?.join(LOG_FILE);
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Loads the most recent log entries, skipping lines that fail to parse.
+pub fn load() -> Result<Vec<QueryLogEntry>> {
+    let path = config_dir()?.join(LOG_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    let mut entries: Vec<QueryLogEntry> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    if entries.len() > MAX_LOADED_ENTRIES {
+        entries.drain(..entries.len() - MAX_LOADED_ENTRIES);
+    }
+    Ok(entries)
+}
+
+/// Aggregated timings for one distinct query text.
+#[derive(Debug, Clone)]
+pub struct QueryStats {
+    pub query: String,
+    pub runs: usize,
+    pub errors: usize,
+    pub total_ms: u64,
+    pub max_ms: u64,
+}
+
+impl QueryStats {
+    pub fn avg_ms(&self) -> u64 {
+        self.total_ms / self.runs.max(1) as u64
+    }
+}
+
+/// Statistics for all logged queries of one connection.
+#[derive(Debug, Clone)]
+pub struct ConnectionStats {
+    pub connection: String,
+    pub total_runs: usize,
+    pub slowest: Vec<QueryLogEntry>,
+    pub most_frequent: Vec<QueryStats>,
+}
+
+/// Collapses whitespace and drops a trailing `;` so reformatted runs of the
+/// same query are counted together.
+fn normalize(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(';')
+        .trim()
+        .to_string()
+}
+
+/// Groups log entries per connection (sorted by name) and computes the slowest
+/// individual runs and the most frequently run queries.
+pub fn connection_stats(entries: &[QueryLogEntry]) -> Vec<ConnectionStats> {
+    let mut by_connection: HashMap<&str, Vec<&QueryLogEntry>> = HashMap::new();
+    for entry in entries {
+        by_connection
+            .entry(entry.connection.as_str())
+            .or_default()
+            .push(entry);
+    }
+
+    let mut stats: Vec<ConnectionStats> = by_connection
+        .into_iter()
+        .map(|(connection, entries)| {
+            let mut slowest: Vec<QueryLogEntry> = entries.iter().map(|e| (*e).clone()).collect();
+            slowest.sort_by_key(|e| std::cmp::Reverse(e.execution_ms));
+            slowest.truncate(TOP_QUERIES);
+
+            let mut grouped: HashMap<String, QueryStats> = HashMap::new();
+            for entry in &entries {
+                let query = normalize(&entry.query);
+                let stats = grouped.entry(query.clone()).or_insert(QueryStats {
+                    query,
+                    runs: 0,
+                    errors: 0,
+                    total_ms: 0,
+                    max_ms: 0,
+                });
+                stats.runs += 1;
+                stats.errors += entry.error.is_some() as usize;
+                stats.total_ms += entry.execution_ms;
+                stats.max_ms = stats.max_ms.max(entry.execution_ms);
+            }
+            let mut most_frequent: Vec<QueryStats> = grouped.into_values().collect();
+            most_frequent.sort_by(|a, b| b.runs.cmp(&a.runs).then(b.total_ms.cmp(&a.total_ms)));
+            most_frequent.truncate(TOP_QUERIES);
+
+            ConnectionStats {
+                connection: connection.to_string(),
+                total_runs: entries.len(),
+                slowest,
+                most_frequent,
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| a.connection.cmp(&b.connection));
+    stats
+}
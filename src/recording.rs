@@ -0,0 +1,144 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use std::fs;
+use std::path::Path;
+
+/// One step of a recorded session.
+#[derive(Debug, Clone)]
+pub enum RecordingEntry {
+    Query {
+        sql: String,
+        executed_at: DateTime<Local>,
+        execution_time: std::time::Duration,
+        row_count: Option<usize>,
+        error: Option<String>,
+    },
+    Note(String),
+}
+
+/// A replayable log of queries and narration markers, exportable as a runbook.
+#[derive(Debug, Clone)]
+pub struct SessionRecording {
+    pub started_at: DateTime<Local>,
+    pub connection_name: Option<String>,
+    pub entries: Vec<RecordingEntry>,
+}
+
+impl SessionRecording {
+    pub fn new(connection_name: Option<String>) -> Self {
+        Self {
+            started_at: Local::now(),
+            connection_name,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn query_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| matches!(e, RecordingEntry::Query { .. }))
+            .count()
+    }
+
+    fn query_outcome(
+        execution_time: &std::time::Duration,
+        row_count: &Option<usize>,
+        error: &Option<String>,
+    ) -> String {
+        match (error, row_count) {
+            (Some(e), _) => format!("failed: {}", e),
+            (None, Some(rows)) => format!("{} rows in {:?}", rows, execution_time),
+            (None, None) => format!("ok in {:?}", execution_time),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Session Recording\n\n");
+        out.push_str(&format!(
+            "- Started: {}\n",
+            self.started_at.format("%Y-%m-%d %H:%M:%S")
+        ));
+        if let Some(name) = &self.connection_name {
+            out.push_str(&format!("- Connection: {}\n", name));
+        }
+        out.push('\n');
+
+        let mut step = 0;
+        for entry in &self.entries {
+            match entry {
+                RecordingEntry::Note(note) => {
+                    out.push_str(&format!("> {}\n\n", note));
+                }
+                RecordingEntry::Query {
+                    sql,
+                    executed_at,
+                    execution_time,
+                    row_count,
+                    error,
+                } => {
+                    step += 1;
+                    out.push_str(&format!(
+                        "## Step {} ({})\n\n```sql\n{}\n```\n\n_{}_\n\n",
+                        step,
+                        executed_at.format("%H:%M:%S"),
+                        sql.trim().trim_end_matches(';'),
+                        Self::query_outcome(execution_time, row_count, error)
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    pub fn to_sql(&self) -> String {
+        let mut out = format!(
+            "-- Session recording started {}\n",
+            self.started_at.format("%Y-%m-%d %H:%M:%S")
+        );
+        if let Some(name) = &self.connection_name {
+            out.push_str(&format!("-- Connection: {}\n", name));
+        }
+        out.push('\n');
+
+        for entry in &self.entries {
+            match entry {
+                RecordingEntry::Note(note) => {
+                    for line in note.lines() {
+                        out.push_str(&format!("-- NOTE: {}\n", line));
+                    }
+                    out.push('\n');
+                }
+                RecordingEntry::Query {
+                    sql,
+                    executed_at,
+                    execution_time,
+                    row_count,
+                    error,
+                } => {
+                    out.push_str(&format!(
+                        "-- [{}] {}\n{};\n\n",
+                        executed_at.format("%H:%M:%S"),
+                        Self::query_outcome(execution_time, row_count, error),
+                        sql.trim().trim_end_matches(';')
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    /// Writes the recording to `path`, as Markdown for `.md` files and as a
+    /// commented SQL script otherwise.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let is_markdown = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("md"));
+        let content = if is_markdown {
+            self.to_markdown()
+        } else {
+            self.to_sql()
+        };
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,329 @@
+// Role and privilege viewer: the connection's users and roles with their
+// attributes, the roles they belong to and what they were granted, read-only.
+// PostgreSQL reads pg_roles and the ACLs of tables and schemas; a role's access
+// to the table selected in the browser comes from has_table_privilege, which
+// counts superusers, owners, inherited roles and grants to PUBLIC. MySQL lists
+// mysql.user (or only the current account without access to it) and reads
+// each account's SHOW GRANTS, working out table access from the grant lines.
+
+use crate::database::{DatabasePool, TableInfo};
+use crate::dialect::Dialect;
+use anyhow::{Result, anyhow};
+use sqlx::Row;
+
+// Privileges that change a table's rows
+const WRITE_PRIVILEGES: [&str; 4] = ["INSERT", "UPDATE", "DELETE", "TRUNCATE"];
+
+// Privileges shown for the selected table, in this order
+const TABLE_PRIVILEGES: [&str; 5] = ["SELECT", "INSERT", "UPDATE", "DELETE", "TRUNCATE"];
+
+#[derive(Debug, Clone)]
+pub struct RoleInfo {
+    pub name: String,
+    pub attributes: Vec<String>, // e.g. "superuser", "login"
+    pub member_of: Vec<String>,
+    pub grants: Vec<String>, // One line per object, e.g. "SELECT, INSERT on public.t"
+    pub table_access: Vec<String>, // Privileges on the browser's selected table
+}
+
+impl RoleInfo {
+    pub fn can_write(&self) -> bool {
+        self.table_access
+            .iter()
+            .any(|p| WRITE_PRIVILEGES.contains(&p.as_str()))
+    }
+}
+
+/// State of the roles screen.
+#[derive(Debug, Default)]
+pub struct RoleBrowser {
+    pub roles: Vec<RoleInfo>,
+    pub table: Option<String>, // Table the access column is for
+    pub selected: usize,       // Index into `visible()`
+    pub writers_only: bool,    // Only roles that can write to `table`
+    pub detail_scroll: usize,
+    pub database: Option<String>, // Current database, for the GRANT builder
+}
+
+impl RoleBrowser {
+    pub fn visible(&self) -> Vec<&RoleInfo> {
+        self.roles
+            .iter()
+            .filter(|role| !self.writers_only || role.can_write())
+            .collect()
+    }
+
+    pub fn selected_role(&self) -> Option<&RoleInfo> {
+        self.visible().get(self.selected).copied()
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.selected = index.min(self.visible().len().saturating_sub(1));
+        self.detail_scroll = 0;
+    }
+}
+
+/// The roles of the connection, with their access to `table` when given.
+pub async fn get_roles(pool: &DatabasePool, table: Option<&TableInfo>) -> Result<Vec<RoleInfo>> {
+    match pool {
+        DatabasePool::SQLite(_) => Err(anyhow!("SQLite has no users or privileges")),
+        DatabasePool::PostgreSQL(pool) => {
+            let table = table.map(|t| Dialect::PostgreSQL.quote_table(&t.qualified_name()));
+            let rows = sqlx::query(
+                "WITH user_objects AS (
+                     SELECT c.oid, c.relowner, c.relacl, c.oid::regclass::text AS name
+                     FROM pg_class c
+                     JOIN pg_namespace n ON n.oid = c.relnamespace
+                     WHERE c.relkind IN ('r', 'v', 'm', 'p', 'f')
+                       AND n.nspname <> 'information_schema' AND n.nspname NOT LIKE 'pg\\_%'
+                 )
+                 SELECT r.rolname::text AS name,
+                        array_remove(ARRAY[
+                            CASE WHEN r.rolsuper THEN 'superuser' END,
+                            CASE WHEN r.rolcanlogin THEN 'login' END,
+                            CASE WHEN r.rolcreatedb THEN 'create database' END,
+                            CASE WHEN r.rolcreaterole THEN 'create role' END,
+                            CASE WHEN r.rolreplication THEN 'replication' END,
+                            CASE WHEN r.rolbypassrls THEN 'bypass RLS' END,
+                            CASE WHEN NOT r.rolinherit THEN 'no inherit' END,
+                            'valid until ' || r.rolvaliduntil::text
+                        ], NULL)::text[] AS attributes,
+                        ARRAY(SELECT g.rolname::text
+                              FROM pg_auth_members m JOIN pg_roles g ON g.oid = m.roleid
+                              WHERE m.member = r.oid ORDER BY 1) AS member_of,
+                        ARRAY(SELECT privileges || ' on ' || object FROM (
+                                  SELECT 'schema ' || n.nspname AS object,
+                                         string_agg(a.privilege_type, ', ') AS privileges
+                                  FROM pg_namespace n CROSS JOIN LATERAL aclexplode(n.nspacl) a
+                                  WHERE a.grantee = r.oid AND n.nspname <> 'information_schema'
+                                    AND n.nspname NOT LIKE 'pg\\_%'
+                                  GROUP BY n.nspname
+                                  UNION ALL
+                                  SELECT o.name, 'ALL (owner)' FROM user_objects o
+                                  WHERE o.relowner = r.oid
+                                  UNION ALL
+                                  SELECT o.name, string_agg(a.privilege_type, ', ')
+                                  FROM user_objects o CROSS JOIN LATERAL aclexplode(o.relacl) a
+                                  WHERE a.grantee = r.oid AND o.relowner <> r.oid
+                                  GROUP BY o.name
+                              ) grants ORDER BY object) AS grants,
+                        CASE WHEN $1::text IS NULL THEN ARRAY[]::text[]
+                             ELSE array_remove(ARRAY[
+                                 CASE WHEN has_table_privilege(r.oid, $1, 'SELECT') THEN 'SELECT' END,
+                                 CASE WHEN has_table_privilege(r.oid, $1, 'INSERT') THEN 'INSERT' END,
+                                 CASE WHEN has_table_privilege(r.oid, $1, 'UPDATE') THEN 'UPDATE' END,
+                                 CASE WHEN has_table_privilege(r.oid, $1, 'DELETE') THEN 'DELETE' END,
+                                 CASE WHEN has_table_privilege(r.oid, $1, 'TRUNCATE') THEN 'TRUNCATE' END
+                             ], NULL)::text[] END AS table_access
+                 FROM pg_roles r
+                 WHERE r.rolname NOT LIKE 'pg\\_%'
+                 ORDER BY r.rolname",
+            )
+            .bind(table)
+            .fetch_all(pool)
+            .await?;
+            Ok(rows
+                .iter()
+                .map(|row| RoleInfo {
+                    name: row.get("name"),
+                    attributes: row.get("attributes"),
+                    member_of: row.get("member_of"),
+                    grants: row.get("grants"),
+                    table_access: row.get("table_access"),
+                })
+                .collect())
+        }
+        DatabasePool::MySQL(pool) => {
+            let text = |row: &sqlx::mysql::MySqlRow, i: usize| -> String {
+                row.try_get::<String, _>(i).unwrap_or_else(|_| {
+                    row.try_get::<Vec<u8>, _>(i)
+                        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                        .unwrap_or_default()
+                })
+            };
+            // Reading mysql.user takes a privilege most accounts lack
+            let accounts: Vec<(String, String)> = match sqlx::query(
+                "SELECT CAST(User AS CHAR), CAST(Host AS CHAR) FROM mysql.user ORDER BY 1, 2",
+            )
+            .fetch_all(pool)
+            .await
+            {
+                Ok(rows) => rows
+                    .iter()
+                    .map(|row| (text(row, 0), text(row, 1)))
+                    .collect(),
+                Err(_) => {
+                    let row = sqlx::query("SELECT CAST(CURRENT_USER() AS CHAR)")
+                        .fetch_one(pool)
+                        .await?;
+                    let current = text(&row, 0);
+                    let (user, host) = current.rsplit_once('@').unwrap_or((&current, "%"));
+                    vec![(user.to_string(), host.to_string())]
+                }
+            };
+            let database: Option<String> = sqlx::query_scalar("SELECT CAST(DATABASE() AS CHAR)")
+                .fetch_one(pool)
+                .await?;
+
+            let mut roles = Vec::new();
+            for (user, host) in accounts {
+                let statement = format!(
+                    "SHOW GRANTS FOR '{}'@'{}'",
+                    user.replace('\'', "''"),
+                    host.replace('\'', "''")
+                );
+                let lines: Vec<String> = match sqlx::raw_sql(&statement).fetch_all(pool).await {
+                    Ok(rows) => rows.iter().map(|row| text(row, 0)).collect(),
+                    Err(e) => vec![format!("(grants not readable: {})", e)],
+                };
+                let mut role = RoleInfo {
+                    name: format!("{}@{}", user, host),
+                    attributes: Vec::new(),
+                    member_of: Vec::new(),
+                    grants: Vec::new(),
+                    table_access: Vec::new(),
+                };
+                for line in &lines {
+                    match parse_mysql_grant(line) {
+                        Some(grant) => {
+                            if let (Some(table), Some(database)) = (table, &database)
+                                && grant.covers(
+                                    table.schema.as_deref().unwrap_or(database),
+                                    &table.name,
+                                )
+                            {
+                                for privilege in TABLE_PRIVILEGES {
+                                    if grant.grants(privilege)
+                                        && !role.table_access.iter().any(|p| p == privilege)
+                                    {
+                                        role.table_access.push(privilege.to_string());
+                                    }
+                                }
+                            }
+                            if grant
+                                .privileges
+                                .iter()
+                                .any(|p| p == "SUPER" || p == "ALL PRIVILEGES")
+                                && grant.object == "*.*"
+                            {
+                                role.attributes.push("superuser".to_string());
+                            }
+                            role.grants.push(format!(
+                                "{} on {}",
+                                grant.privileges.join(", "),
+                                grant.object
+                            ));
+                        }
+                        // `GRANT role1, role2 TO user` grants roles rather than privileges
+                        None => match grant_roles(line) {
+                            Some(granted) => role.member_of.extend(granted),
+                            None => role.grants.push(line.clone()),
+                        },
+                    }
+                }
+                role.table_access
+                    .sort_by_key(|p| TABLE_PRIVILEGES.iter().position(|t| t == p));
+                roles.push(role);
+            }
+            Ok(roles)
+        }
+    }
+}
+
+/// Name of the database the connection is using.
+pub async fn current_database(pool: &DatabasePool) -> Result<Option<String>> {
+    Ok(match pool {
+        DatabasePool::SQLite(_) => None,
+        DatabasePool::PostgreSQL(pool) => {
+            sqlx::query_scalar("SELECT current_database()::text")
+                .fetch_one(pool)
+                .await?
+        }
+        DatabasePool::MySQL(pool) => {
+            sqlx::query_scalar("SELECT CAST(DATABASE() AS CHAR)")
+                .fetch_one(pool)
+                .await?
+        }
+    })
+}
+
+/// A `GRANT ... ON ... TO ...` line of SHOW GRANTS.
+struct MySqlGrant {
+    privileges: Vec<String>, // Upper case, column lists left out
+    object: String,          // `*.*`, `db.*` or `db.table`, unquoted
+}
+
+impl MySqlGrant {
+    fn covers(&self, database: &str, table: &str) -> bool {
+        let (grant_database, grant_table) = self.object.split_once('.').unwrap_or(("*", "*"));
+        (grant_database == "*" || grant_database == database)
+            && (grant_table == "*" || grant_table == table)
+    }
+
+    fn grants(&self, privilege: &str) -> bool {
+        self.privileges
+            .iter()
+            .any(|p| p == privilege || p == "ALL PRIVILEGES" || p == "ALL")
+    }
+}
+
+fn parse_mysql_grant(line: &str) -> Option<MySqlGrant> {
+    let rest = line.strip_prefix("GRANT ")?;
+    let on = rest.find(" ON ")?;
+    let (privileges, rest) = (&rest[..on], &rest[on + 4..]);
+    let object = rest.split(" TO ").next()?.trim();
+    // `PROCEDURE db.p` and `FUNCTION db.f` name routines, not tables
+    let object = object
+        .strip_prefix("TABLE ")
+        .unwrap_or(object)
+        .replace('`', "");
+    Some(MySqlGrant {
+        privileges: split_top_level(privileges)
+            .into_iter()
+            .map(|p| {
+                p.split('(')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_uppercase()
+            })
+            .collect(),
+        object,
+    })
+}
+
+/// The roles granted by a `GRANT role1, role2 TO user` line.
+fn grant_roles(line: &str) -> Option<Vec<String>> {
+    let rest = line.strip_prefix("GRANT ")?;
+    let (roles, _) = rest.split_once(" TO ")?;
+    Some(
+        split_top_level(roles)
+            .into_iter()
+            .map(|role| role.replace('`', ""))
+            .collect(),
+    )
+}
+
+/// Splits at commas outside parentheses, e.g. `SELECT (a, b), INSERT`.
+fn split_top_level(list: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in list.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    parts
+}
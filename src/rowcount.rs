@@ -0,0 +1,45 @@
+// Exact row counts for the table list. Tables are listed with the statistics'
+// estimates straight away; those without any are counted here one at a time
+// in the background and fill in as each count arrives, so a slow COUNT(*)
+// never holds up connecting or browsing.
+
+use crate::database::{DatabasePool, TableInfo};
+use std::collections::HashSet;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+#[derive(Debug)]
+pub struct RowCountTask {
+    pub pending: HashSet<String>, // Qualified names of the tables not counted yet
+    pub total: usize,
+    pub counts: mpsc::UnboundedReceiver<(String, Option<i64>)>, // None when counting failed
+    pub handle: JoinHandle<()>,
+}
+
+impl RowCountTask {
+    pub fn start(pool: DatabasePool, tables: Vec<TableInfo>) -> Self {
+        let pending: HashSet<String> = tables.iter().map(TableInfo::qualified_name).collect();
+        let (sender, counts) = mpsc::unbounded_channel();
+        let handle = tokio::spawn(async move {
+            for table in tables {
+                let count = pool.count_rows(&table).await.ok();
+                // The receiver is gone once the table list is reloaded
+                if sender.send((table.qualified_name(), count)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            total: pending.len(),
+            pending,
+            counts,
+            handle,
+        }
+    }
+}
+
+impl Drop for RowCountTask {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
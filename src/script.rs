@@ -0,0 +1,94 @@
+// Runs a SQL file statement by statement on its own connection, reporting
+// progress as it goes and stopping at the first statement that fails.
+
+use crate::database::DatabasePool;
+use crate::sql::ScriptStatement;
+use anyhow::Result;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// Characters of a failed statement quoted in the report
+const STATEMENT_PREVIEW_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Default)]
+pub struct ScriptProgress {
+    pub executed: usize,
+    pub total: usize,
+    pub rows_affected: u64,
+}
+
+/// The statement a script stopped at.
+#[derive(Debug, Clone)]
+pub struct ScriptFailure {
+    pub index: usize, // 0-based position in the script
+    pub line: usize,
+    pub statement: String,
+    pub error: String,
+}
+
+#[derive(Debug)]
+pub struct ScriptOutcome {
+    pub progress: ScriptProgress,
+    pub failure: Option<ScriptFailure>,
+    pub elapsed: Duration,
+}
+
+/// A script running in a spawned task.
+#[derive(Debug)]
+pub struct ScriptTask {
+    pub path: PathBuf,
+    pub progress: watch::Receiver<ScriptProgress>,
+    pub handle: JoinHandle<Result<ScriptOutcome>>,
+}
+
+impl ScriptFailure {
+    /// The statement's first line, shortened for the error popup.
+    pub fn preview(&self) -> String {
+        let first_line = self.statement.lines().next().unwrap_or_default();
+        let mut preview: String = first_line.chars().take(STATEMENT_PREVIEW_CHARS).collect();
+        if preview.len() < self.statement.len() {
+            preview.push_str("...");
+        }
+        preview
+    }
+}
+
+/// Executes the statements in order, publishing progress after each one.
+pub async fn run(
+    pool: DatabasePool,
+    statements: Vec<ScriptStatement>,
+    progress: watch::Sender<ScriptProgress>,
+) -> ScriptOutcome {
+    let start = Instant::now();
+    let mut state = ScriptProgress {
+        total: statements.len(),
+        ..ScriptProgress::default()
+    };
+    progress.send_replace(state.clone());
+
+    let texts: Vec<String> = statements.iter().map(|s| s.text.clone()).collect();
+    let result = pool
+        .execute_script(&texts, |rows| {
+            state.executed += 1;
+            state.rows_affected += rows;
+            progress.send_replace(state.clone());
+        })
+        .await;
+
+    let failure = match (result, statements.get(state.executed)) {
+        (Err(e), Some(statement)) => Some(ScriptFailure {
+            index: state.executed,
+            line: statement.line,
+            statement: statement.text.clone(),
+            error: e.to_string(),
+        }),
+        _ => None,
+    };
+    ScriptOutcome {
+        progress: state,
+        failure,
+        elapsed: start.elapsed(),
+    }
+}
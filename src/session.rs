@@ -0,0 +1,43 @@
+// Session restore: what was open when the app quit (the connection, the query
+// editor's text and the selected table) is saved to session.json and offered
+// back on the next launch. Results aren't saved, as restoring them would mean
+// running the query again.
+
+use crate::settings::config_dir;
+use anyhow::Result;
+use std::fs;
+
+const SESSION_FILE: &str = "session.json";
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub connection: Option<String>, // Name of the connection that was open
+    pub table: Option<String>,      // Qualified name of the selected table
+    pub query: String,
+    pub query_cursor: usize,
+    pub in_editor: bool, // The query editor was open rather than the table browser
+    pub schemas: Vec<String>, // PostgreSQL schemas that were expanded
+}
+
+impl Session {
+    /// Whether there is anything worth offering to restore.
+    pub fn is_empty(&self) -> bool {
+        self.connection.is_none() && self.query.trim().is_empty()
+    }
+}
+
+pub fn load() -> Result<Option<Session>> {
+    let file = config_dir()?.join(SESSION_FILE);
+    if !file.exists() {
+        return Ok(None);
+    }
+    let session: Session = serde_json::from_str(&fs::read_to_string(file)?)?;
+    Ok(Some(session).filter(|s| !s.is_empty()))
+}
+
+pub fn save(session: &Session) -> Result<()> {
+    let file = config_dir()?.join(SESSION_FILE);
+    fs::write(file, serde_json::to_string_pretty(session)?)?;
+    Ok(())
+}
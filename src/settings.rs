@@ -0,0 +1,228 @@
+use crate::units::ColumnUnit;
+use anyhow::Result;
+use std::fs;
+use std::path::PathBuf;
+
+/// Returns the application's config directory, creating it if needed.
+pub fn config_dir() -> Result<PathBuf> {
+    let dir = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?
+        .join("rata-db");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Global application settings, persisted to `settings.json` in the config directory.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    // Privacy mode: mask values of columns matching `masked_columns`
+    pub privacy_mode: bool,
+    pub masked_columns: Vec<String>,
+
+    // Ask before running DROP/TRUNCATE or DELETE/UPDATE without WHERE
+    pub confirm_destructive_statements: bool,
+
+    // Check queries against the loaded schema before running them
+    pub lint_queries: bool,
+
+    // Unit-aware display: humanize byte and duration columns matching these patterns
+    pub humanize_units: bool,
+    pub byte_columns: Vec<String>,
+    pub duration_seconds_columns: Vec<String>,
+    pub duration_millis_columns: Vec<String>,
+
+    // Show the query editor and latest results on one screen
+    pub split_view: bool,
+
+    // Percent of the split view's height given to the editor (20-80)
+    pub split_editor_percent: u16,
+
+    // Show size, index and maintenance statistics of the selected table
+    pub show_table_stats: bool,
+
+    // Color theme: a preset name ("dark", "light", "solarized") or "custom" for theme.json
+    pub theme: String,
+
+    // Modal vim-style editing in the query editor and hjkl navigation elsewhere
+    pub vim_mode: bool,
+
+    // Order paged single-table SELECTs by primary key so pages are deterministic
+    pub stable_pagination_order: bool,
+
+    // Persist every executed query to query_log.jsonl for the statistics screen
+    pub log_queries: bool,
+    pub slow_query_threshold_ms: u64,
+
+    // Always run a full COUNT(*) for result totals instead of using estimates
+    pub exact_row_counts: bool,
+
+    // Count the rows of tables without statistics in the background after
+    // listing them; when off, only tables counted with `n` show a count
+    pub count_table_rows: bool,
+
+    // Rows read from a result's cursor before asking to fetch more
+    pub max_result_rows: usize,
+
+    // Seconds between server monitor refreshes; 0 turns auto-refresh off
+    pub monitor_refresh_seconds: u64,
+
+    // List the most recently used connections first instead of in saved order
+    pub sort_connections_by_recency: bool,
+
+    // Connect to the last used connection on launch, skipping the list
+    pub auto_connect_last: bool,
+
+    // Save the open connection, query and table on exit and offer them back on launch
+    pub restore_session: bool,
+
+    // Seconds between autosaves of the query editor to a crash draft; 0 turns them off
+    pub draft_autosave_seconds: u64,
+
+    // Seconds between checks of a SQLite file for changes by other programs; 0 turns them off
+    pub sqlite_watch_seconds: u64,
+
+    // Also raise a desktop notification (notify-send or osascript) when a dashboard alert fires
+    pub desktop_notifications: bool,
+
+    // Natural-language assistant: base URL of an OpenAI-compatible API (empty
+    // turns the assistant off), its API key and the model asked
+    pub assistant_endpoint: String,
+    pub assistant_api_key: String,
+    pub assistant_model: String,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            privacy_mode: false,
+            masked_columns: vec![
+                "*email*".to_string(),
+                "*ssn*".to_string(),
+                "*password*".to_string(),
+                "*phone*".to_string(),
+                "*token*".to_string(),
+            ],
+            confirm_destructive_statements: true,
+            lint_queries: true,
+            humanize_units: false,
+            byte_columns: vec![
+                "*bytes*".to_string(),
+                "*_size".to_string(),
+                "size".to_string(),
+            ],
+            duration_seconds_columns: vec![
+                "*duration*".to_string(),
+                "*seconds*".to_string(),
+                "*_secs".to_string(),
+                "uptime".to_string(),
+            ],
+            duration_millis_columns: vec!["*_ms".to_string(), "*millis*".to_string()],
+            split_view: false,
+            split_editor_percent: 40,
+            show_table_stats: true,
+            theme: "dark".to_string(),
+            vim_mode: false,
+            stable_pagination_order: true,
+            log_queries: true,
+            slow_query_threshold_ms: 1000,
+            exact_row_counts: false,
+            count_table_rows: true,
+            max_result_rows: 10_000,
+            monitor_refresh_seconds: 2,
+            sort_connections_by_recency: false,
+            auto_connect_last: false,
+            restore_session: true,
+            draft_autosave_seconds: 5,
+            sqlite_watch_seconds: 2,
+            desktop_notifications: false,
+            assistant_endpoint: String::new(),
+            assistant_api_key: String::new(),
+            assistant_model: "gpt-4o-mini".to_string(),
+        }
+    }
+}
+
+impl AppSettings {
+    pub fn load() -> Result<Self> {
+        let settings_file = config_dir()?.join("settings.json");
+
+        if settings_file.exists() {
+            let content = fs::read_to_string(settings_file)?;
+            Ok(serde_json::from_str(&content)?)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let settings_file = config_dir()?.join("settings.json");
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(settings_file, json)?;
+        Ok(())
+    }
+
+    /// Whether values of `column` should be masked while privacy mode is on.
+    pub fn is_column_masked(&self, column: &str) -> bool {
+        self.privacy_mode
+            && self
+                .masked_columns
+                .iter()
+                .any(|pattern| glob_matches(pattern, column))
+    }
+
+    /// Unit to humanize `column` as, if unit-aware display is enabled and a pattern matches.
+    pub fn column_unit(&self, column: &str) -> Option<ColumnUnit> {
+        if !self.humanize_units {
+            return None;
+        }
+        let matches = |patterns: &[String]| patterns.iter().any(|p| glob_matches(p, column));
+
+        // Millisecond patterns are more specific than the generic duration ones
+        if matches(&self.duration_millis_columns) {
+            Some(ColumnUnit::Milliseconds)
+        } else if matches(&self.duration_seconds_columns) {
+            Some(ColumnUnit::Seconds)
+        } else if matches(&self.byte_columns) {
+            Some(ColumnUnit::Bytes)
+        } else {
+            None
+        }
+    }
+}
+
+/// Case-insensitive glob match supporting `*` (any run) and `?` (any single char).
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<usize> = None;
+    let mut star_t = 0;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some(p);
+            star_t = t;
+            p += 1;
+        } else if let Some(star_p) = star {
+            // Backtrack: let the last '*' absorb one more character
+            p = star_p + 1;
+            star_t += 1;
+            t = star_t;
+        } else {
+            return false;
+        }
+    }
+
+    while p < pattern.len() && pattern[p] == '*' {
+        p += 1;
+    }
+    p == pattern.len()
+}
+
+/// Placeholder shown instead of a masked cell value.
+pub const MASKED_VALUE: &str = "••••••";
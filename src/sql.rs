@@ -0,0 +1,658 @@
+use crate::database::DatabaseType;
+use crate::dialect::Dialect;
+use sqlparser::ast::{
+    Expr, FromTable, GroupByExpr, Query, Select, SetExpr, Statement, TableFactor, visit_expressions,
+};
+use sqlparser::parser::Parser;
+use std::ops::ControlFlow;
+
+/// A statement that can destroy data and should be confirmed before running.
+#[derive(Debug, Clone)]
+pub struct DestructiveStatement {
+    pub kind: &'static str,
+    pub table: String,
+    pub statement: String,
+}
+
+/// A statement of a script and the (1-based) line it starts on.
+#[derive(Debug, Clone)]
+pub struct ScriptStatement {
+    pub line: usize,
+    pub text: String,
+}
+
+/// Splits a script into individual statements on `;`, ignoring semicolons inside
+/// quoted strings, identifiers, comments, PostgreSQL dollar-quoted bodies and
+/// the BEGIN ... END bodies of routines and triggers.
+pub fn split_statements(query: &str, dialect: Dialect) -> Vec<String> {
+    split_script(query, dialect)
+        .into_iter()
+        .map(|s| s.text)
+        .collect()
+}
+
+/// Splits a script like [`split_statements`], keeping each statement's line.
+/// Comments before a statement are dropped, so comment-only parts of a file
+/// don't become statements. A script that ends inside a quote, comment or
+/// body can't be split safely and comes back whole.
+pub fn split_script(script: &str, dialect: Dialect) -> Vec<ScriptStatement> {
+    enum State {
+        Code,
+        Quote(char),
+        DollarQuote(String), // Tag between the dollars, e.g. `body` of `$body$`
+        LineComment,
+        BlockComment,
+    }
+
+    let chars: Vec<char> = script.chars().collect();
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut start_line = 1;
+    let mut line = 1;
+    let mut state = State::Code;
+    let mut skip = 0; // Characters taken as they are: a quote tag's rest, an escaped one
+    let mut word = String::new();
+    let mut first_word = String::new();
+    let mut previous_word = String::new();
+    let mut depth = 0usize; // BEGIN/CASE blocks open in a CREATE statement's body
+
+    let mut finish = |current: &mut String, start_line: usize| {
+        let text = current.trim();
+        if !text.is_empty() {
+            statements.push(ScriptStatement {
+                line: start_line,
+                text: text.to_string(),
+            });
+        }
+        current.clear();
+    };
+    // Whether `chars` continue at `i` with `$tag$`
+    let closes_tag = |i: usize, tag: &str| {
+        let tag: Vec<char> = tag.chars().collect();
+        chars.get(i + 1..i + 1 + tag.len()) == Some(tag.as_slice())
+            && chars.get(i + 1 + tag.len()) == Some(&'$')
+    };
+
+    for (i, &c) in chars.iter().enumerate() {
+        if skip > 0 {
+            skip -= 1;
+            // Empty only after a comment ending before the statement
+            if !current.is_empty() {
+                current.push(c);
+            }
+            if c == '\n' {
+                line += 1;
+            }
+            continue;
+        }
+        if matches!(state, State::Code) && (c.is_alphanumeric() || c == '_') {
+            word.push(c);
+        } else if !word.is_empty() {
+            let upper = std::mem::take(&mut word).to_uppercase();
+            if first_word.is_empty() {
+                first_word = upper.clone();
+            }
+            // END IF, END LOOP, ... close blocks that weren't counted
+            if first_word == "CREATE" {
+                match upper.as_str() {
+                    "BEGIN" => depth += 1,
+                    "CASE" if previous_word != "END" => depth += 1,
+                    "END" => depth = depth.saturating_sub(1),
+                    "IF" | "LOOP" | "WHILE" | "REPEAT" if previous_word == "END" => depth += 1,
+                    _ => {}
+                }
+            }
+            previous_word = upper;
+        }
+
+        match &state {
+            State::Quote(q) if c == *q => state = State::Code,
+            // MySQL strings also escape with backslashes; identifiers don't
+            State::Quote(q) if c == '\\' && *q != '`' && dialect == Dialect::MySQL => skip = 1,
+            State::Quote(_) => {}
+            State::DollarQuote(tag) if c == '$' && closes_tag(i, tag) => {
+                skip = tag.chars().count() + 1;
+                state = State::Code;
+            }
+            State::DollarQuote(_) => {}
+            State::LineComment if c == '\n' => state = State::Code,
+            State::LineComment => {}
+            State::BlockComment if c == '*' && chars.get(i + 1) == Some(&'/') => {
+                skip = 1;
+                state = State::Code;
+                if !current.is_empty() {
+                    current.push('*');
+                }
+                continue;
+            }
+            State::BlockComment => {}
+            State::Code => match c {
+                '\'' | '"' | '`' => state = State::Quote(c),
+                '$' if dialect == Dialect::PostgreSQL => {
+                    let tag: String = chars[i + 1..]
+                        .iter()
+                        .take_while(|c| c.is_alphanumeric() || **c == '_')
+                        .collect();
+                    let is_tag = !tag.starts_with(|c: char| c.is_ascii_digit());
+                    if is_tag && closes_tag(i, &tag) {
+                        skip = tag.chars().count() + 1;
+                        state = State::DollarQuote(tag);
+                    }
+                }
+                '-' if chars.get(i + 1) == Some(&'-') => state = State::LineComment,
+                '/' if chars.get(i + 1) == Some(&'*') => state = State::BlockComment,
+                ';' if depth == 0 => {
+                    finish(&mut current, start_line);
+                    first_word.clear();
+                    previous_word.clear();
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        if current.is_empty() {
+            // Whitespace and comments before a statement aren't part of it
+            let starts_statement = !c.is_whitespace()
+                && matches!(state, State::Code | State::Quote(_) | State::DollarQuote(_));
+            if starts_statement {
+                start_line = line;
+                current.push(c);
+            }
+        } else {
+            current.push(c);
+        }
+        if c == '\n' {
+            line += 1;
+        }
+    }
+
+    if depth > 0
+        || matches!(
+            state,
+            State::Quote(_) | State::DollarQuote(_) | State::BlockComment
+        )
+    {
+        let text = script.trim();
+        let line = 1 + script[..script.len() - script.trim_start().len()]
+            .matches('\n')
+            .count();
+        return vec![ScriptStatement {
+            line,
+            text: text.to_string(),
+        }];
+    }
+    finish(&mut current, start_line);
+    statements
+}
+
+/// Splits a statement into whitespace-separated words, treating `(`, `)` and `,`
+/// as separators.
+fn words(statement: &str) -> Vec<String> {
+    statement
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == ',')
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_string())
+        .collect()
+}
+
+fn has_keyword(words: &[String], keyword: &str) -> bool {
+    words.iter().any(|w| w.eq_ignore_ascii_case(keyword))
+}
+
+/// Returns the word following the leading keywords, skipping `IF EXISTS`.
+fn target_after(words: &[String], skip: usize) -> String {
+    let mut rest = words.iter().skip(skip).peekable();
+    if rest.peek().is_some_and(|w| w.eq_ignore_ascii_case("IF")) {
+        rest.next();
+        rest.next();
+    }
+    rest.next().cloned().unwrap_or_else(|| "?".to_string())
+}
+
+/// Classifies a single statement as destructive (DROP, TRUNCATE, or DELETE/UPDATE
+/// without a WHERE clause). Statements that don't parse fall back to their
+/// words, with comments and string contents left out.
+pub fn destructive_statement(statement: &str, dialect: Dialect) -> Option<DestructiveStatement> {
+    let parser = dialect.parser();
+    let (kind, table) = match Parser::parse_sql(parser.as_ref(), statement).as_deref() {
+        Ok([parsed]) => match parsed {
+            Statement::Update {
+                table,
+                selection: None,
+                ..
+            } => ("UPDATE without WHERE", table.relation.to_string()),
+            Statement::Delete(delete) if delete.selection.is_none() => {
+                let tables = match &delete.from {
+                    FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables) => {
+                        tables
+                    }
+                };
+                let names: Vec<String> = tables.iter().map(|t| t.relation.to_string()).collect();
+                ("DELETE without WHERE", names.join(", "))
+            }
+            Statement::Truncate { table_names, .. } => {
+                let names: Vec<String> = table_names.iter().map(|t| t.name.to_string()).collect();
+                ("TRUNCATE", names.join(", "))
+            }
+            // Every kind of DROP, read back from the statement as parsed
+            parsed => {
+                let words = words(&parsed.to_string());
+                if !words.first()?.eq_ignore_ascii_case("DROP") {
+                    return None;
+                }
+                ("DROP", target_after(&words, 2))
+            }
+        },
+        Ok(_) => return None,
+        Err(_) => {
+            let words = words(&blank_string_literals(&strip_comments(statement)));
+            let first = words.first()?.to_uppercase();
+            let second = words.get(1).map(|w| w.to_uppercase()).unwrap_or_default();
+            match first.as_str() {
+                "DROP" => ("DROP", target_after(&words, 2)),
+                "TRUNCATE" if second == "TABLE" => ("TRUNCATE", target_after(&words, 2)),
+                "TRUNCATE" => ("TRUNCATE", target_after(&words, 1)),
+                "DELETE" if !has_keyword(&words, "WHERE") => {
+                    ("DELETE without WHERE", target_after(&words, 2))
+                }
+                "UPDATE" if !has_keyword(&words, "WHERE") => {
+                    ("UPDATE without WHERE", target_after(&words, 1))
+                }
+                _ => return None,
+            }
+        }
+    };
+
+    Some(DestructiveStatement {
+        kind,
+        table,
+        statement: statement.to_string(),
+    })
+}
+
+/// Returns every destructive statement in a (possibly multi-statement) query.
+pub fn find_destructive_statements(query: &str, dialect: Dialect) -> Vec<DestructiveStatement> {
+    split_statements(query, dialect)
+        .iter()
+        .filter_map(|s| destructive_statement(s, dialect))
+        .collect()
+}
+
+/// Whether the SELECT has no ORDER BY, so rows can come back in any order.
+/// Only considers plain SELECTs without LIMIT/OFFSET (the ones read page by
+/// page); aggregates without grouping return a single row and are skipped.
+/// Clauses of subqueries and CTEs don't count.
+pub fn lacks_stable_order(query: &str, database_type: &DatabaseType) -> bool {
+    let Some(parsed) = parse_query(query, database_type) else {
+        return false;
+    };
+    let Some(select) = plain_select(&parsed) else {
+        return false;
+    };
+    let is_aggregate = !is_grouped(select) && has_aggregate(select);
+
+    !is_aggregate
+        && parsed.order_by.is_none()
+        && parsed.limit.is_none()
+        && parsed.offset.is_none()
+        && parsed.fetch.is_none()
+}
+
+/// Returns the table a SELECT reads from when it reads from exactly one table
+/// (no joins, derived tables, set operations, grouping or aggregates), else
+/// `None`. Subqueries elsewhere, e.g. in the WHERE clause, are allowed.
+pub fn single_table_source(query: &str, database_type: &DatabaseType) -> Option<String> {
+    let parsed = parse_query(query, database_type)?;
+    select_table(plain_select(&parsed)?)
+}
+
+/// Like [`single_table_source`], but only for queries that read the whole table
+/// (no WHERE, HAVING or LIMIT), whose row count equals the table's.
+pub fn whole_table_source(query: &str, database_type: &DatabaseType) -> Option<String> {
+    let parsed = parse_query(query, database_type)?;
+    let select = plain_select(&parsed)?;
+    if select.selection.is_some()
+        || select.having.is_some()
+        || parsed.limit.is_some()
+        || parsed.offset.is_some()
+        || parsed.fetch.is_some()
+    {
+        return None;
+    }
+    select_table(select)
+}
+
+/// The query's body when it is one SELECT: no set operation, VALUES or CTEs.
+fn plain_select(query: &Query) -> Option<&Select> {
+    match query.body.as_ref() {
+        SetExpr::Select(select) if query.with.is_none() => Some(select),
+        _ => None,
+    }
+}
+
+/// The table named in the SELECT's FROM, as written, when each of its rows is
+/// one row of that table.
+fn select_table(select: &Select) -> Option<String> {
+    if select.distinct.is_some() || is_grouped(select) || has_aggregate(select) {
+        return None;
+    }
+    let [from] = select.from.as_slice() else {
+        return None;
+    };
+    if !from.joins.is_empty() {
+        return None;
+    }
+    match &from.relation {
+        TableFactor::Table {
+            name, args: None, ..
+        } => Some(name.to_string()),
+        _ => None,
+    }
+}
+
+fn is_grouped(select: &Select) -> bool {
+    !matches!(&select.group_by, GroupByExpr::Expressions(exprs, _) if exprs.is_empty())
+}
+
+/// Whether the select list calls an aggregate function (not as a window
+/// function).
+fn has_aggregate(select: &Select) -> bool {
+    const AGGREGATES: [&str; 5] = ["COUNT", "SUM", "AVG", "MIN", "MAX"];
+    visit_expressions(&select.projection, |expr| match expr {
+        Expr::Function(function)
+            if function.over.is_none()
+                && AGGREGATES
+                    .iter()
+                    .any(|a| function.name.to_string().eq_ignore_ascii_case(a)) =>
+        {
+            ControlFlow::Break(())
+        }
+        _ => ControlFlow::Continue(()),
+    })
+    .is_break()
+}
+
+/// Appends `ORDER BY` on `columns` to a SELECT that has none.
+pub fn with_order_by(query: &str, columns: &[String], database_type: &DatabaseType) -> String {
+    let dialect = Dialect::of(database_type);
+    let columns: Vec<String> = columns
+        .iter()
+        .map(|c| dialect.quote_identifier(c))
+        .collect();
+    format!(
+        "{} ORDER BY {}",
+        query.trim().trim_end_matches(';'),
+        columns.join(", ")
+    )
+}
+
+/// Parses `query` as exactly one query (SELECT, WITH, VALUES, set operations).
+/// `None` when it doesn't parse or is some other statement.
+fn parse_query(query: &str, database_type: &DatabaseType) -> Option<Box<Query>> {
+    let dialect = Dialect::of(database_type).parser();
+    let mut statements = Parser::parse_sql(dialect.as_ref(), query).ok()?;
+    match (statements.pop(), statements.is_empty()) {
+        (Some(Statement::Query(query)), true) => Some(query),
+        _ => None,
+    }
+}
+
+/// Whether the query only reads: no INSERT/UPDATE/DELETE in its body or CTEs,
+/// so running it again (to count it) has no side effects.
+fn reads_only(query: &Query) -> bool {
+    fn body_reads_only(body: &SetExpr) -> bool {
+        match body {
+            SetExpr::Select(_) | SetExpr::Values(_) | SetExpr::Table(_) => true,
+            SetExpr::Query(query) => reads_only(query),
+            SetExpr::SetOperation { left, right, .. } => {
+                body_reads_only(left) && body_reads_only(right)
+            }
+            _ => false,
+        }
+    }
+    let ctes_read_only = query
+        .with
+        .as_ref()
+        .is_none_or(|with| with.cte_tables.iter().all(|cte| reads_only(&cte.query)));
+    ctes_read_only && body_reads_only(&query.body) && query.locks.is_empty()
+}
+
+/// Whether the statement is a read-only query whose rows can be counted,
+/// including ones starting with WITH.
+pub fn is_query(query: &str, database_type: &DatabaseType) -> bool {
+    match parse_query(query, database_type) {
+        Some(parsed) => reads_only(&parsed),
+        // Unparsed (e.g. unsupported syntax): trust a leading SELECT
+        None => words(query)
+            .first()
+            .is_some_and(|w| w.eq_ignore_ascii_case("SELECT")),
+    }
+}
+
+/// Whether the statement changes data or schema without returning rows (DML
+/// without RETURNING, DDL), so it is run for its affected row count.
+pub fn reports_affected_rows(query: &str, database_type: &DatabaseType) -> bool {
+    let dialect = Dialect::of(database_type).parser();
+    match Parser::parse_sql(dialect.as_ref(), query).as_deref() {
+        Ok([statement]) => match statement {
+            Statement::Insert(insert) => insert.returning.is_none(),
+            Statement::Update { returning, .. } => returning.is_none(),
+            Statement::Delete(delete) => delete.returning.is_none(),
+            Statement::Merge { .. }
+            | Statement::Truncate { .. }
+            | Statement::CreateTable(_)
+            | Statement::CreateView { .. }
+            | Statement::CreateIndex(_)
+            | Statement::CreateSchema { .. }
+            | Statement::CreateDatabase { .. }
+            | Statement::CreateSequence { .. }
+            | Statement::CreateType { .. }
+            | Statement::CreateFunction(_)
+            | Statement::CreateTrigger { .. }
+            | Statement::CreateExtension { .. }
+            | Statement::CreateRole { .. }
+            | Statement::AlterTable { .. }
+            | Statement::AlterIndex { .. }
+            | Statement::AlterView { .. }
+            | Statement::AlterRole { .. }
+            | Statement::Drop { .. }
+            | Statement::DropFunction { .. }
+            | Statement::DropTrigger { .. }
+            | Statement::Grant { .. }
+            | Statement::Revoke { .. }
+            | Statement::Comment { .. } => true,
+            _ => false,
+        },
+        // Unparsed (e.g. unsupported syntax): go by the leading keyword
+        _ => {
+            let words = words(query);
+            const CHANGING: [&str; 10] = [
+                "INSERT", "UPDATE", "DELETE", "REPLACE", "CREATE", "DROP", "ALTER", "TRUNCATE",
+                "GRANT", "REVOKE",
+            ];
+            words
+                .first()
+                .is_some_and(|w| CHANGING.iter().any(|k| w.eq_ignore_ascii_case(k)))
+                && !has_keyword(&words, "RETURNING")
+        }
+    }
+}
+
+/// Whether the statement calls a stored procedure, which can return several
+/// result sets.
+pub fn is_procedure_call(statement: &str) -> bool {
+    words(statement).first().is_some_and(|w| {
+        ["CALL", "EXEC", "EXECUTE"]
+            .iter()
+            .any(|k| w.eq_ignore_ascii_case(k))
+    })
+}
+
+/// Drops the query's own LIMIT, OFFSET and FETCH, leaving ones inside
+/// subqueries and CTEs alone. The ORDER BY is kept unless `keep_order` is false.
+fn strip_paging(query: &mut Query, keep_order: bool) {
+    query.limit = None;
+    query.limit_by.clear();
+    query.offset = None;
+    query.fetch = None;
+    if !keep_order {
+        query.order_by = None;
+    }
+}
+
+/// The query without its top-level LIMIT/OFFSET, for estimating all the rows
+/// it would return. Queries that don't parse are only trimmed.
+pub fn without_limit(query: &str, database_type: &DatabaseType) -> String {
+    match parse_query(query, database_type) {
+        Some(mut parsed) => {
+            strip_paging(&mut parsed, true);
+            parsed.to_string()
+        }
+        None => query.trim().trim_end_matches(';').to_string(),
+    }
+}
+
+/// `SELECT COUNT(*)` over the query with its top-level LIMIT/OFFSET and ORDER BY
+/// removed. Queries that don't parse are counted as they are.
+pub fn count_query(query: &str, database_type: &DatabaseType) -> String {
+    let inner = match parse_query(query, database_type) {
+        Some(mut parsed) => {
+            strip_paging(&mut parsed, false);
+            parsed.to_string()
+        }
+        None => query.trim().trim_end_matches(';').to_string(),
+    };
+    // PostgreSQL and MySQL require an alias on a derived table
+    format!("SELECT COUNT(*) FROM ({}) AS counted", inner)
+}
+
+/// The statement without its `--` and `/* */` comments, leaving quoted text alone.
+fn strip_comments(statement: &str) -> String {
+    let mut out = String::new();
+    let mut quote = None;
+    let mut chars = statement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '-' if chars.peek() == Some(&'-') => {
+                    chars.find(|&c| c == '\n');
+                    out.push('\n');
+                    continue;
+                }
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    let mut last = ' ';
+                    for c in chars.by_ref() {
+                        if last == '*' && c == '/' {
+                            break;
+                        }
+                        last = c;
+                    }
+                    out.push(' ');
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Replaces the contents of single-quoted string literals with spaces so that
+/// text inside them isn't mistaken for SQL.
+fn blank_string_literals(query: &str) -> String {
+    let mut in_string = false;
+    query
+        .chars()
+        .map(|c| {
+            if c == '\'' {
+                in_string = !in_string;
+                c
+            } else if in_string {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+const NON_TABLE_WORDS: [&str; 16] = [
+    "WHERE", "ON", "USING", "JOIN", "LEFT", "RIGHT", "INNER", "OUTER", "CROSS", "FULL", "NATURAL",
+    "GROUP", "ORDER", "LIMIT", "HAVING", "SET",
+];
+
+/// Returns `(alias, table)` pairs for the tables named after FROM, JOIN, and
+/// UPDATE. Tables without an alias map to themselves.
+pub fn table_aliases(query: &str) -> Vec<(String, String)> {
+    let query = blank_string_literals(query).replace(',', " , ");
+    let tokens: Vec<&str> = query.split_whitespace().collect();
+    let is_word = |t: &str, words: &[&str]| words.iter().any(|w| t.eq_ignore_ascii_case(w));
+
+    let mut aliases = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let starts_list = is_word(tokens[i], &["FROM", "JOIN", "UPDATE"]);
+        i += 1;
+        if !starts_list {
+            continue;
+        }
+
+        // FROM a x, b y: keep reading tables while separated by commas
+        while let Some(&table) = tokens.get(i) {
+            if table.starts_with('(') || is_word(table, &NON_TABLE_WORDS) {
+                break;
+            }
+            let table = table.trim_matches('`').to_string();
+            i += 1;
+            if tokens.get(i).is_some_and(|t| t.eq_ignore_ascii_case("AS")) {
+                i += 1;
+            }
+            let alias = match tokens.get(i) {
+                Some(&t) if t != "," && !is_word(t, &NON_TABLE_WORDS) => {
+                    i += 1;
+                    t.trim_matches('`').to_string()
+                }
+                _ => table.clone(),
+            };
+            aliases.push((alias, table));
+
+            if tokens.get(i) == Some(&",") {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+    }
+    aliases
+}
+
+/// Returns pairs of column references compared against each other, such as
+/// `a.name = b.name`. Comparisons with literals or function calls are skipped.
+pub fn column_comparisons(query: &str) -> Vec<(String, String)> {
+    let query = blank_string_literals(query);
+    let pattern = regex::Regex::new(
+        r"(?i)([A-Za-z_`][\w`]*(?:\.[A-Za-z_`][\w`]*)?)\s*(?:=|<>|!=|<=>|<=|>=|<|>|\bLIKE\b)\s*([A-Za-z_`][\w`]*(?:\.[A-Za-z_`][\w`]*)?)",
+    )
+    .unwrap();
+    const KEYWORDS: [&str; 6] = ["NULL", "TRUE", "FALSE", "ANY", "ALL", "SELECT"];
+
+    pattern
+        .captures_iter(&query)
+        .filter(|caps| {
+            // `x = LOWER(y)` compares against a function result, not a column
+            let end = caps.get(0).unwrap().end();
+            !query[end..].trim_start().starts_with('(')
+        })
+        .map(|caps| (caps[1].replace('`', ""), caps[2].replace('`', "")))
+        .filter(|(left, right)| {
+            ![left, right]
+                .iter()
+                .any(|side| KEYWORDS.iter().any(|k| side.eq_ignore_ascii_case(k)))
+        })
+        .collect()
+}
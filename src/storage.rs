@@ -0,0 +1,144 @@
+// Storage overview (PostgreSQL): the server's tablespaces from pg_tablespace
+// with where they are on disk and how much they hold, and the tables,
+// materialized views and indexes of this database in each one, largest first.
+// Relations without a tablespace of their own live in the database's default
+// one. Sizes are pg_relation_size, the main data of each relation; a
+// tablespace's total (pg_tablespace_size) counts every database in it, and is
+// only shown to roles allowed to read it.
+
+use crate::database::DatabasePool;
+use anyhow::{Result, anyhow};
+use sqlx::Row;
+
+#[derive(Debug, Clone)]
+pub struct TablespaceInfo {
+    pub name: String,
+    pub owner: String,
+    pub location: String, // Empty for pg_default and pg_global, inside the data directory
+    pub total_bytes: Option<i64>, // Every database's files in it
+    pub relations: Vec<RelationStorage>, // This database's, largest first
+}
+
+impl TablespaceInfo {
+    /// Size of this database's relations in the tablespace.
+    pub fn relation_bytes(&self) -> i64 {
+        self.relations.iter().map(|r| r.bytes).sum()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RelationStorage {
+    pub name: String, // schema.name
+    pub kind: &'static str,
+    pub table: Option<String>, // Table of an index
+    pub bytes: i64,
+}
+
+/// State of the storage screen.
+#[derive(Debug, Default)]
+pub struct StorageOverview {
+    pub tablespaces: Vec<TablespaceInfo>,
+    pub default_tablespace: String, // The database's
+    pub selected: usize,
+    pub relation_scroll: usize,
+}
+
+impl StorageOverview {
+    pub fn selected_tablespace(&self) -> Option<&TablespaceInfo> {
+        self.tablespaces.get(self.selected)
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.selected = index.min(self.tablespaces.len().saturating_sub(1));
+        self.relation_scroll = 0;
+    }
+}
+
+/// Loads the tablespaces and the relations in each.
+pub async fn get_storage(pool: &DatabasePool) -> Result<StorageOverview> {
+    let DatabasePool::PostgreSQL(pool) = pool else {
+        return Err(anyhow!("Tablespaces are only shown on PostgreSQL"));
+    };
+    let default_tablespace: String = sqlx::query_scalar(
+        "SELECT t.spcname::text FROM pg_database d
+         JOIN pg_tablespace t ON t.oid = d.dattablespace
+         WHERE d.datname = current_database()",
+    )
+    .fetch_one(pool)
+    .await?;
+
+    // pg_tablespace_size fails without CREATE on the tablespace, unless it is
+    // the database's default or the role reads all statistics
+    let rows = sqlx::query(
+        "SELECT t.spcname::text AS name, pg_get_userbyid(t.spcowner)::text AS owner,
+                pg_tablespace_location(t.oid) AS location,
+                CASE WHEN t.oid = d.dattablespace
+                          OR has_tablespace_privilege(t.oid, 'CREATE')
+                          OR pg_has_role('pg_read_all_stats', 'MEMBER')
+                     THEN pg_tablespace_size(t.oid) END AS total_bytes
+         FROM pg_tablespace t
+         JOIN pg_database d ON d.datname = current_database()
+         ORDER BY t.spcname",
+    )
+    .fetch_all(pool)
+    .await?;
+    let mut tablespaces: Vec<TablespaceInfo> = rows
+        .iter()
+        .map(|row| TablespaceInfo {
+            name: row.get("name"),
+            owner: row.get("owner"),
+            location: row.get::<Option<String>, _>("location").unwrap_or_default(),
+            total_bytes: row.get("total_bytes"),
+            relations: Vec::new(),
+        })
+        .collect();
+
+    let rows = sqlx::query(
+        "SELECT COALESCE(t.spcname, dt.spcname)::text AS tablespace,
+                n.nspname || '.' || c.relname AS name, c.relkind::text AS kind,
+                tn.nspname || '.' || tc.relname AS table_name,
+                pg_relation_size(c.oid) AS bytes
+         FROM pg_class c
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         LEFT JOIN pg_tablespace t ON t.oid = c.reltablespace
+         JOIN pg_database d ON d.datname = current_database()
+         JOIN pg_tablespace dt ON dt.oid = d.dattablespace
+         LEFT JOIN pg_index x ON x.indexrelid = c.oid
+         LEFT JOIN pg_class tc ON tc.oid = x.indrelid
+         LEFT JOIN pg_namespace tn ON tn.oid = tc.relnamespace
+         WHERE c.relkind IN ('r', 'm', 'i')
+           AND n.nspname NOT IN ('pg_catalog', 'information_schema', 'pg_toast')
+           AND n.nspname NOT LIKE 'pg_temp%'
+         ORDER BY bytes DESC, name",
+    )
+    .fetch_all(pool)
+    .await?;
+    for row in &rows {
+        let tablespace: String = row.get("tablespace");
+        let Some(tablespace) = tablespaces.iter_mut().find(|t| t.name == tablespace) else {
+            continue;
+        };
+        tablespace.relations.push(RelationStorage {
+            name: row.get("name"),
+            kind: match row.get::<String, _>("kind").as_str() {
+                "m" => "materialized view",
+                "i" => "index",
+                _ => "table",
+            },
+            table: row.get("table_name"),
+            bytes: row.get("bytes"),
+        });
+    }
+
+    // Starts on the database's default tablespace, where most relations are
+    let selected = tablespaces
+        .iter()
+        .position(|t| t.name == default_tablespace)
+        .unwrap_or(0);
+    Ok(StorageOverview {
+        tablespaces,
+        default_tablespace,
+        selected,
+        relation_scroll: 0,
+    })
+}
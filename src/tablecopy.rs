@@ -0,0 +1,278 @@
+// Copies a table's schema and rows from the current connection to another one.
+// Column types are mapped to the closest type of the target database and rows
+// are read through a cursor and written as batched INSERTs.
+
+use crate::database::{ColumnInfo, DatabasePool, DatabaseType, TableInfo};
+use crate::dialect::Dialect;
+use crate::dump;
+use anyhow::Result;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// Rows per INSERT statement
+const INSERT_BATCH_ROWS: usize = 200;
+
+// Rows read from the source cursor at a time
+const FETCH_ROWS: usize = 1000;
+
+/// Picking the connection a table is copied to.
+#[derive(Debug, Clone)]
+pub struct CopyWizard {
+    pub table: TableInfo,
+    pub selected: usize, // Index into `App::connections`
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CopyProgress {
+    pub rows: usize,
+    pub total: Option<i64>, // Row count (or estimate) of the source table
+}
+
+/// What a finished copy did.
+#[derive(Debug)]
+pub struct CopySummary {
+    pub rows: usize,
+    pub created: bool, // False when rows were appended to an existing table
+}
+
+/// A copy running in a spawned task.
+#[derive(Debug)]
+pub struct CopyTask {
+    pub table: String,
+    pub target: String, // "connection/table"
+    pub progress: watch::Receiver<CopyProgress>,
+    pub handle: JoinHandle<Result<CopySummary>>,
+}
+
+/// Broad type families, used to map a column type between databases.
+#[derive(Debug, PartialEq)]
+enum TypeKind {
+    Boolean,
+    SmallInt,
+    Integer,
+    BigInt,
+    Real,
+    Double,
+    Decimal(Option<String>),
+    Char(Option<String>),
+    VarChar(Option<String>),
+    Text,
+    Binary,
+    Date,
+    Time,
+    Timestamp,
+    Json,
+    Uuid,
+}
+
+fn classify(data_type: &str) -> TypeKind {
+    let lower = data_type.to_lowercase();
+    let (base, args) = match lower.split_once('(') {
+        Some((base, rest)) => (
+            base.trim().to_string(),
+            rest.split_once(')').map(|(args, _)| args.replace(' ', "")),
+        ),
+        None => (lower.clone(), None),
+    };
+    let base = base
+        .trim_end_matches(" zerofill")
+        .trim_end_matches(" unsigned")
+        .trim();
+
+    match base {
+        "boolean" | "bool" => TypeKind::Boolean,
+        "tinyint" if args.as_deref() == Some("1") => TypeKind::Boolean,
+        "tinyint" | "smallint" | "int2" | "smallserial" => TypeKind::SmallInt,
+        "integer" | "int" | "int4" | "mediumint" | "serial" => TypeKind::Integer,
+        "bigint" | "int8" | "bigserial" => TypeKind::BigInt,
+        "real" | "float" | "float4" => TypeKind::Real,
+        "double" | "double precision" | "float8" => TypeKind::Double,
+        "numeric" | "decimal" => TypeKind::Decimal(args),
+        "character" | "char" | "nchar" => TypeKind::Char(args),
+        "character varying" | "varchar" | "nvarchar" => TypeKind::VarChar(args),
+        "bytea" | "blob" | "tinyblob" | "mediumblob" | "longblob" | "binary" | "varbinary" => {
+            TypeKind::Binary
+        }
+        "date" => TypeKind::Date,
+        "time" | "time without time zone" | "time with time zone" => TypeKind::Time,
+        "datetime" | "timestamptz" => TypeKind::Timestamp,
+        t if t.starts_with("timestamp") => TypeKind::Timestamp,
+        "json" | "jsonb" => TypeKind::Json,
+        "uuid" => TypeKind::Uuid,
+        // SQLite gives any type naming INT integer affinity
+        t if t.contains("int") => TypeKind::Integer,
+        _ => TypeKind::Text,
+    }
+}
+
+/// The target database's type for a source column. Types are kept as they are
+/// between databases of the same kind, except PostgreSQL arrays and
+/// user-defined types, which information_schema doesn't spell out and which are
+/// copied as text.
+pub fn translate_type(source: &DatabaseType, target: &DatabaseType, column: &ColumnInfo) -> String {
+    let same_kind = std::mem::discriminant(source) == std::mem::discriminant(target);
+    if same_kind && !matches!(column.data_type.as_str(), "ARRAY" | "USER-DEFINED") {
+        return column.data_type.clone();
+    }
+
+    let kind = classify(&column.data_type);
+    let sized = |name: &str, args: &Option<String>| match args {
+        Some(args) => format!("{}({})", name, args),
+        None => name.to_string(),
+    };
+    match target {
+        DatabaseType::SQLite => match kind {
+            TypeKind::Boolean => "BOOLEAN".to_string(),
+            TypeKind::SmallInt | TypeKind::Integer | TypeKind::BigInt => "INTEGER".to_string(),
+            TypeKind::Real | TypeKind::Double => "REAL".to_string(),
+            TypeKind::Decimal(_) => "NUMERIC".to_string(),
+            TypeKind::Binary => "BLOB".to_string(),
+            _ => "TEXT".to_string(),
+        },
+        DatabaseType::PostgreSQL => match kind {
+            TypeKind::Boolean => "boolean".to_string(),
+            TypeKind::SmallInt => "smallint".to_string(),
+            TypeKind::Integer => "integer".to_string(),
+            TypeKind::BigInt => "bigint".to_string(),
+            TypeKind::Real => "real".to_string(),
+            TypeKind::Double => "double precision".to_string(),
+            TypeKind::Decimal(args) => sized("numeric", &args),
+            TypeKind::Char(args) => sized("char", &args),
+            TypeKind::VarChar(args) => sized("varchar", &args),
+            TypeKind::Text => "text".to_string(),
+            TypeKind::Binary => "bytea".to_string(),
+            TypeKind::Date => "date".to_string(),
+            TypeKind::Time => "time".to_string(),
+            TypeKind::Timestamp => "timestamp".to_string(),
+            TypeKind::Json => "json".to_string(),
+            TypeKind::Uuid => "uuid".to_string(),
+        },
+        // MySQL can't index TEXT/BLOB columns without a prefix length, so key
+        // columns get bounded types
+        DatabaseType::MySQL => match kind {
+            TypeKind::Boolean => "TINYINT(1)".to_string(),
+            TypeKind::SmallInt => "SMALLINT".to_string(),
+            TypeKind::Integer => "INT".to_string(),
+            TypeKind::BigInt => "BIGINT".to_string(),
+            TypeKind::Real => "FLOAT".to_string(),
+            TypeKind::Double => "DOUBLE".to_string(),
+            TypeKind::Decimal(None) => "DECIMAL(65,30)".to_string(),
+            TypeKind::Decimal(args) => sized("DECIMAL", &args),
+            TypeKind::Char(args) => sized("CHAR", &args),
+            TypeKind::VarChar(Some(args)) => format!("VARCHAR({})", args),
+            TypeKind::VarChar(None) | TypeKind::Text if column.is_primary_key => {
+                "VARCHAR(255)".to_string()
+            }
+            TypeKind::VarChar(None) | TypeKind::Text => "LONGTEXT".to_string(),
+            TypeKind::Binary if column.is_primary_key => "VARBINARY(255)".to_string(),
+            TypeKind::Binary => "LONGBLOB".to_string(),
+            TypeKind::Date => "DATE".to_string(),
+            TypeKind::Time => "TIME".to_string(),
+            TypeKind::Timestamp => "DATETIME(6)".to_string(),
+            TypeKind::Json => "JSON".to_string(),
+            TypeKind::Uuid => "CHAR(36)".to_string(),
+        },
+    }
+}
+
+/// PostgreSQL writes booleans as `true`/`false`, which the other databases
+/// don't accept for their integer-backed booleans.
+fn convert_value(value: &str, kind: &TypeKind, target: &DatabaseType) -> String {
+    match (kind, target, value) {
+        (TypeKind::Boolean, DatabaseType::SQLite | DatabaseType::MySQL, "true") => "1".to_string(),
+        (TypeKind::Boolean, DatabaseType::SQLite | DatabaseType::MySQL, "false") => "0".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// Whether `name` (optionally `schema.table` on PostgreSQL) is one of `tables`.
+fn table_exists(tables: &[TableInfo], name: &str) -> bool {
+    tables.iter().any(|t| {
+        t.qualified_name() == name
+            || (t.name == name && t.schema.as_deref().is_none_or(|s| s == "public"))
+    })
+}
+
+/// Copies `table` from `source` into `target_table` on `target`, creating the
+/// table when it doesn't exist there and appending to it otherwise.
+pub async fn copy_table(
+    source: DatabasePool,
+    target: DatabasePool,
+    table: TableInfo,
+    target_table: String,
+    progress: watch::Sender<CopyProgress>,
+) -> Result<CopySummary> {
+    let source_type = source.database_type();
+    let target_type = target.database_type();
+    let columns = source
+        .get_table_columns(&table.name, table.schema.as_deref())
+        .await?;
+    let mut state = CopyProgress {
+        rows: 0,
+        total: table.row_count,
+    };
+    progress.send_replace(state.clone());
+
+    let created = !table_exists(&target.get_tables().await?, &target_table);
+    if created {
+        let translated: Vec<ColumnInfo> = columns
+            .iter()
+            .map(|col| ColumnInfo {
+                data_type: translate_type(&source_type, &target_type, col),
+                ..col.clone()
+            })
+            .collect();
+        let create = dump::create_table_statement(&target_type, &target_table, &translated, &[]);
+        target.execute_query(&create).await?;
+    }
+
+    let kinds: Vec<TypeKind> = columns.iter().map(|col| classify(&col.data_type)).collect();
+    // Read as `\x` hex from every database, written as the target's binary literals
+    let binary: Vec<bool> = kinds.iter().map(|kind| *kind == TypeKind::Binary).collect();
+    let select = format!(
+        "SELECT {} FROM {}",
+        dump::select_list(&source_type, &columns),
+        Dialect::of(&source_type).quote_table(&table.qualified_name())
+    );
+    let (mut first, mut stream) = source.stream_query(&select, FETCH_ROWS).await?;
+    let mut rows = first.take_cells();
+    loop {
+        if matches!(source_type, DatabaseType::MySQL) {
+            dump::ensure_lossless(&table.qualified_name(), &rows)?;
+        }
+        for batch in rows.chunks(INSERT_BATCH_ROWS) {
+            let converted: Vec<Vec<Option<String>>> = batch
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .zip(&kinds)
+                        .map(|(value, kind)| {
+                            value
+                                .as_deref()
+                                .map(|v| convert_value(v, kind, &target_type))
+                        })
+                        .collect()
+                })
+                .collect();
+            let insert = dump::insert_statement(
+                &target_type,
+                &target_table,
+                &first.columns,
+                &binary,
+                &converted,
+            );
+            target.execute_query(&insert).await?;
+            state.rows += batch.len();
+            progress.send_replace(state.clone());
+        }
+        if stream.exhausted {
+            break;
+        }
+        rows = stream.next_chunk(FETCH_ROWS).await?;
+    }
+
+    Ok(CopySummary {
+        rows: state.rows,
+        created,
+    })
+}
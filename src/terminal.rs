@@ -0,0 +1,81 @@
+// Terminal setup and teardown. The guard puts the terminal back in normal mode
+// when it is dropped, and the panic hook does so before the panic message is
+// printed, so neither an error nor a crash leaves the shell in raw mode on the
+// alternate screen. External programs get the terminal by suspending it.
+
+use anyhow::Result;
+use crossterm::{
+    cursor::Show,
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use std::io;
+
+/// Raw mode, the alternate screen, mouse capture and bracketed paste (pasted
+/// text arrives as one event), for as long as it lives.
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn enter() -> Result<Self> {
+        enable_raw_mode()?;
+        // Dropped on error, undoing raw mode
+        let guard = Self;
+        execute!(
+            io::stdout(),
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableBracketedPaste
+        )?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+/// Hands the terminal back to the shell's normal mode while another program
+/// runs in it; `resume` takes it back.
+pub fn suspend() {
+    restore();
+}
+
+pub fn resume() -> Result<()> {
+    enable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
+    Ok(())
+}
+
+/// Leaves raw mode and the alternate screen. Errors are ignored: there is
+/// nothing better to do with a terminal that can't be restored.
+fn restore() {
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        Show
+    );
+}
+
+/// Restores the terminal before a panic on the main thread is printed.
+/// Panics in spawned tasks are reported by the app when their handle is
+/// awaited, so they aren't printed over the screen.
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if std::thread::current().name() == Some("main") {
+            restore();
+            default_hook(info);
+        }
+    }));
+}
@@ -0,0 +1,88 @@
+// Unicode-aware measuring and cutting of text for the text inputs and the
+// grids. Strings are still indexed by byte, but cursors step over whole
+// grapheme clusters (an emoji with its modifiers, a letter with combining
+// accents) and cells are measured in terminal columns, where CJK characters and
+// most emoji take two.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Columns `text` takes in the terminal.
+pub fn width(text: &str) -> usize {
+    text.width()
+}
+
+/// Grapheme clusters in `text`, what a reader counts as characters.
+pub fn grapheme_count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// `pos` moved back to a char boundary within `text`.
+pub fn floor_boundary(text: &str, pos: usize) -> usize {
+    text.floor_char_boundary(pos)
+}
+
+/// Start of the grapheme before `pos`, or 0.
+pub fn prev_grapheme(text: &str, pos: usize) -> usize {
+    let pos = floor_boundary(text, pos);
+    text[..pos]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+/// End of the grapheme at `pos`, or the end of the text.
+pub fn next_grapheme(text: &str, pos: usize) -> usize {
+    let pos = floor_boundary(text, pos);
+    text[pos..]
+        .graphemes(true)
+        .next()
+        .map_or(text.len(), |g| pos + g.len())
+}
+
+/// The longest start of `text` fitting in `max` columns, never splitting a
+/// grapheme.
+pub fn clip(text: &str, max: usize) -> &str {
+    let mut used = 0;
+    for (i, grapheme) in text.grapheme_indices(true) {
+        used += grapheme.width();
+        if used > max {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+/// `text` cut to `max` columns, ending in "..." when it doesn't fit.
+pub fn truncate(text: &str, max: usize) -> String {
+    if width(text) <= max {
+        text.to_string()
+    } else {
+        format!("{}...", clip(text, max.saturating_sub(3)))
+    }
+}
+
+/// `text` padded with spaces to `columns` columns.
+pub fn pad(text: &str, columns: usize) -> String {
+    format!(
+        "{}{}",
+        text,
+        " ".repeat(columns.saturating_sub(width(text)))
+    )
+}
+
+/// `text` broken at spaces into lines of at most `max` columns; a word longer
+/// than that gets a line of its own.
+pub fn wrap(text: &str, max: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split(' ') {
+        match lines.last_mut() {
+            Some(line) if width(line) + 1 + width(word) <= max => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
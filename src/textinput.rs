@@ -0,0 +1,227 @@
+// Editable text with a cursor and a selection, shared by the query editor, the
+// connection form's fields, the list filters and the text prompts so they all
+// edit the same way: typing replaces the selection, Shift with ←/→/Home/End
+// selects, and pasted text goes in as one edit. Positions are byte offsets kept
+// on grapheme boundaries (see `text`), so multi-byte characters never split.
+
+use crate::text;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use std::ops::Range;
+
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    pub text: String,
+    pub cursor: usize,         // Byte offset into `text`
+    pub anchor: Option<usize>, // Where the selection started; it runs to the cursor
+}
+
+impl TextInput {
+    /// An input holding `text`, with the cursor at its end.
+    pub fn new(text: impl Into<String>) -> Self {
+        let mut input = Self::default();
+        input.set(text);
+        input
+    }
+
+    /// Replaces the text, putting the cursor at its end.
+    pub fn set(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+        self.anchor = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.set(String::new());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// The input with every grapheme shown as `symbol`, for drawing passwords.
+    pub fn masked(&self, symbol: char) -> TextInput {
+        let masked_pos = |pos: usize| {
+            let pos = text::floor_boundary(&self.text, pos);
+            text::grapheme_count(&self.text[..pos]) * symbol.len_utf8()
+        };
+        TextInput {
+            text: symbol.to_string().repeat(text::grapheme_count(&self.text)),
+            cursor: masked_pos(self.cursor),
+            anchor: self.anchor.map(masked_pos),
+        }
+    }
+
+    /// The selected byte range, if any text is selected.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = text::floor_boundary(&self.text, self.anchor?);
+        let cursor = text::floor_boundary(&self.text, self.cursor);
+        (anchor != cursor).then(|| anchor.min(cursor)..anchor.max(cursor))
+    }
+
+    /// Removes the selected text, returning whether there was any.
+    fn delete_selection(&mut self) -> bool {
+        let selection = self.selection();
+        self.anchor = None;
+        let Some(range) = selection else {
+            return false;
+        };
+        self.text.replace_range(range.clone(), "");
+        self.cursor = range.start;
+        true
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.insert_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Inserts `inserted` at the cursor in place of the selection.
+    pub fn insert_str(&mut self, inserted: &str) {
+        self.delete_selection();
+        let pos = text::floor_boundary(&self.text, self.cursor);
+        self.text.insert_str(pos, inserted);
+        self.cursor = pos + inserted.len();
+    }
+
+    /// Inserts pasted text; a single-line input gets its line breaks as spaces.
+    pub fn paste(&mut self, pasted: &str, multiline: bool) {
+        let pasted = pasted.replace("\r\n", "\n").replace('\r', "\n");
+        if multiline {
+            self.insert_str(&pasted);
+        } else {
+            self.insert_str(&pasted.replace('\n', " "));
+        }
+    }
+
+    /// Removes the selection, or the grapheme before the cursor (Backspace).
+    pub fn delete_before(&mut self) {
+        if !self.delete_selection() {
+            let pos = text::floor_boundary(&self.text, self.cursor);
+            let start = text::prev_grapheme(&self.text, pos);
+            self.text.replace_range(start..pos, "");
+            self.cursor = start;
+        }
+    }
+
+    /// Removes the selection, or the grapheme under the cursor (Delete).
+    pub fn delete_after(&mut self) {
+        if !self.delete_selection() {
+            let pos = text::floor_boundary(&self.text, self.cursor);
+            let end = text::next_grapheme(&self.text, pos);
+            self.text.replace_range(pos..end, "");
+            self.cursor = pos;
+        }
+    }
+
+    /// Moves the cursor to `pos`; with `select` the selection stretches to it.
+    pub fn move_to(&mut self, pos: usize, select: bool) {
+        if !select {
+            self.anchor = None;
+        } else if self.anchor.is_none() {
+            self.anchor = Some(self.cursor);
+        }
+        self.cursor = text::floor_boundary(&self.text, pos);
+    }
+
+    /// Without `select`, a selection collapses to its start instead.
+    pub fn move_left(&mut self, select: bool) {
+        match self.selection() {
+            Some(range) if !select => self.move_to(range.start, false),
+            _ => self.move_to(text::prev_grapheme(&self.text, self.cursor), select),
+        }
+    }
+
+    /// Without `select`, a selection collapses to its end instead.
+    pub fn move_right(&mut self, select: bool) {
+        match self.selection() {
+            Some(range) if !select => self.move_to(range.end, false),
+            _ => self.move_to(text::next_grapheme(&self.text, self.cursor), select),
+        }
+    }
+
+    /// Applies an editing key: a character, Backspace, Delete, or ←/→/Home/End
+    /// (selecting with Shift). Returns false for keys left to the caller.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let select = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Char(c)
+                if !c.is_control()
+                    && !key
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.insert_char(c)
+            }
+            KeyCode::Backspace => self.delete_before(),
+            KeyCode::Delete => self.delete_after(),
+            KeyCode::Left => self.move_left(select),
+            KeyCode::Right => self.move_right(select),
+            KeyCode::Home => self.move_to(0, select),
+            KeyCode::End => self.move_to(self.text.len(), select),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Columns to scroll a single-line input `width` columns wide by so the
+    /// cursor stays in view.
+    pub fn scroll(&self, width: u16) -> u16 {
+        let cursor = text::floor_boundary(&self.text, self.cursor);
+        (text::width(&self.text[..cursor]) + 1).saturating_sub(width as usize) as u16
+    }
+
+    /// The text as lines, with `cursor` drawn at the cursor when given and the
+    /// selection in the `selected` style.
+    pub fn lines(&self, cursor: Option<&'static str>, selected: Style) -> Vec<Line<'static>> {
+        let cursor = cursor.map(|symbol| (text::floor_boundary(&self.text, self.cursor), symbol));
+        let selection = self.selection();
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for line in self.text.split('\n') {
+            let end = start + line.len();
+            // The line is cut where the selection starts or ends and at the cursor
+            let mut cuts = vec![start, end];
+            if let Some(range) = &selection {
+                cuts.extend([range.start, range.end]);
+            }
+            if let Some((pos, _)) = cursor {
+                cuts.push(pos);
+            }
+            cuts.retain(|pos| (start..=end).contains(pos));
+            cuts.sort_unstable();
+            cuts.dedup();
+
+            let mut spans = Vec::new();
+            for (i, &pos) in cuts.iter().enumerate() {
+                if let Some((at, symbol)) = cursor
+                    && at == pos
+                {
+                    spans.push(Span::raw(symbol));
+                }
+                if let Some(&next) = cuts.get(i + 1) {
+                    let style = if selection
+                        .as_ref()
+                        .is_some_and(|range| range.start <= pos && next <= range.end)
+                    {
+                        selected
+                    } else {
+                        Style::default()
+                    };
+                    spans.push(Span::styled(self.text[pos..next].to_string(), style));
+                }
+            }
+            lines.push(Line::from(spans));
+            start = end + 1;
+        }
+        lines
+    }
+
+    /// The single line of a one-line input, as drawn by `lines`.
+    pub fn line(&self, cursor: Option<&'static str>, selected: Style) -> Line<'static> {
+        self.lines(cursor, selected)
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,195 @@
+use crate::settings::config_dir;
+use anyhow::Result;
+use ratatui::style::Color;
+use std::fs;
+
+const THEME_FILE: &str = "theme.json";
+
+// Built-in presets, in the order the theme switcher cycles through them
+pub const PRESETS: &[&str] = &["dark", "light", "solarized"];
+
+// Name under which a `theme.json` in the config directory is offered
+pub const CUSTOM_THEME: &str = "custom";
+
+/// Colors used by every screen and popup. Colors are written in `theme.json`
+/// as names (`"yellow"`, `"darkgray"`), hex (`"#268bd2"`) or indexed (`"208"`);
+/// fields left out keep the dark preset's color.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    #[serde(skip)]
+    pub name: String,
+
+    #[serde(with = "color_string")]
+    pub title: Color, // Screen titles and paths
+    #[serde(with = "color_string")]
+    pub text: Color, // Query editor text
+    #[serde(with = "color_string")]
+    pub muted: Color, // Key hints and secondary text
+    #[serde(with = "color_string")]
+    pub accent: Color, // Focused fields and borders
+    #[serde(with = "color_string")]
+    pub header: Color, // Table header rows
+    #[serde(with = "color_string")]
+    pub warning: Color, // Slow queries, running jobs, changed cells
+    #[serde(with = "color_string")]
+    pub error: Color,
+    #[serde(with = "color_string")]
+    pub success: Color, // Active connection, added rows
+    #[serde(with = "color_string")]
+    pub emphasis: Color, // Values worth a second look, e.g. differing collations
+    #[serde(with = "color_string")]
+    pub directory: Color,
+    #[serde(with = "color_string")]
+    pub selection_fg: Color,
+    #[serde(with = "color_string")]
+    pub selection_bg: Color,
+    #[serde(with = "color_string")]
+    pub highlight_fg: Color, // Active tab and picked day
+    #[serde(with = "color_string")]
+    pub highlight_bg: Color,
+    #[serde(with = "color_string")]
+    pub status_fg: Color,
+    #[serde(with = "color_string")]
+    pub status_bg: Color,
+    #[serde(with = "color_string")]
+    pub popup_fg: Color,
+    #[serde(with = "color_string")]
+    pub popup_bg: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// The original palette, meant for dark terminal backgrounds.
+    pub fn dark() -> Self {
+        Self {
+            name: "dark".to_string(),
+            title: Color::Cyan,
+            text: Color::White,
+            muted: Color::Gray,
+            accent: Color::Yellow,
+            header: Color::Yellow,
+            warning: Color::Yellow,
+            error: Color::Red,
+            success: Color::Green,
+            emphasis: Color::Magenta,
+            directory: Color::Blue,
+            selection_fg: Color::White,
+            selection_bg: Color::Blue,
+            highlight_fg: Color::Black,
+            highlight_bg: Color::Yellow,
+            status_fg: Color::White,
+            status_bg: Color::Blue,
+            popup_fg: Color::White,
+            popup_bg: Color::Black,
+        }
+    }
+
+    /// Dark text and saturated accents for light terminal backgrounds.
+    pub fn light() -> Self {
+        Self {
+            name: "light".to_string(),
+            title: Color::Blue,
+            text: Color::Black,
+            muted: Color::DarkGray,
+            accent: Color::Rgb(0, 95, 175),
+            header: Color::Rgb(135, 0, 135),
+            warning: Color::Rgb(175, 95, 0),
+            error: Color::Rgb(175, 0, 0),
+            success: Color::Rgb(0, 120, 0),
+            emphasis: Color::Rgb(135, 0, 135),
+            directory: Color::Rgb(0, 95, 175),
+            selection_fg: Color::Black,
+            selection_bg: Color::Rgb(175, 215, 255),
+            highlight_fg: Color::White,
+            highlight_bg: Color::Rgb(0, 95, 175),
+            status_fg: Color::White,
+            status_bg: Color::Rgb(0, 95, 175),
+            popup_fg: Color::Black,
+            popup_bg: Color::Rgb(238, 238, 238),
+        }
+    }
+
+    /// Solarized dark (https://ethanschoonover.com/solarized/).
+    pub fn solarized() -> Self {
+        let base03 = Color::Rgb(0, 43, 54);
+        let base02 = Color::Rgb(7, 54, 66);
+        let base01 = Color::Rgb(88, 110, 117);
+        let base0 = Color::Rgb(131, 148, 150);
+        let base1 = Color::Rgb(147, 161, 161);
+        let yellow = Color::Rgb(181, 137, 0);
+        let orange = Color::Rgb(203, 75, 22);
+        let red = Color::Rgb(220, 50, 47);
+        let magenta = Color::Rgb(211, 54, 130);
+        let blue = Color::Rgb(38, 139, 210);
+        let cyan = Color::Rgb(42, 161, 152);
+        let green = Color::Rgb(133, 153, 0);
+        Self {
+            name: "solarized".to_string(),
+            title: cyan,
+            text: base1,
+            muted: base01,
+            accent: yellow,
+            header: blue,
+            warning: orange,
+            error: red,
+            success: green,
+            emphasis: magenta,
+            directory: blue,
+            selection_fg: base1,
+            selection_bg: base02,
+            highlight_fg: base03,
+            highlight_bg: yellow,
+            status_fg: base03,
+            status_bg: base0,
+            popup_fg: base1,
+            popup_bg: base03,
+        }
+    }
+
+    /// Loads the named theme: a built-in preset, or `custom` for `theme.json`.
+    /// Unknown names fall back to the dark preset.
+    pub fn load(name: &str) -> Result<Self> {
+        match name {
+            "light" => Ok(Self::light()),
+            "solarized" => Ok(Self::solarized()),
+            CUSTOM_THEME => {
+                let content = fs::read_to_string(config_dir()?.join(THEME_FILE))?;
+                let mut theme: Theme = serde_json::from_str(&content)?;
+                theme.name = CUSTOM_THEME.to_string();
+                Ok(theme)
+            }
+            _ => Ok(Self::dark()),
+        }
+    }
+
+    /// Theme names available to the switcher; `custom` only when `theme.json` exists.
+    pub fn available() -> Vec<&'static str> {
+        let mut names = PRESETS.to_vec();
+        if config_dir().is_ok_and(|dir| dir.join(THEME_FILE).exists()) {
+            names.push(CUSTOM_THEME);
+        }
+        names
+    }
+}
+
+/// Serializes colors as the strings ratatui parses, e.g. `"yellow"` or `"#268bd2"`.
+mod color_string {
+    use ratatui::style::Color;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&color.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Color::from_str(&value).map_err(|_| D::Error::custom(format!("invalid color: {}", value)))
+    }
+}
@@ -0,0 +1,323 @@
+// Export of a whole table to a CSV file and import of a CSV file into a table.
+// PostgreSQL goes through the COPY protocol, streaming the file in a single
+// statement instead of one query per batch of rows, which is many times faster
+// on large tables. MySQL and SQLite read rows through a cursor and load them as
+// batched INSERTs, in one transaction on MySQL. MySQL's own fast path, LOAD DATA
+// LOCAL INFILE, isn't available: the server sends the client a request for the
+// file's contents, which sqlx neither answers nor announces it can (it leaves
+// out the CLIENT_LOCAL_FILES capability), so the server refuses the statement.
+// Both paths write and read the same CSV: a header of column names, and an
+// unquoted empty field for NULL (`""` is the empty string).
+
+use crate::database::{DatabasePool, DatabaseType, TableInfo};
+use crate::dialect::Dialect;
+use crate::dump;
+use crate::export;
+use anyhow::{Result, anyhow};
+use futures_util::StreamExt;
+use sqlx::postgres::PgPoolCopyExt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// Rows per INSERT statement
+const INSERT_BATCH_ROWS: usize = 200;
+
+// Rows read from the cursor at a time
+const FETCH_ROWS: usize = 1000;
+
+// Bytes of the file sent to COPY at a time
+const COPY_CHUNK_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransferKind {
+    Export,
+    Import,
+}
+
+/// An export or import running in a spawned task.
+#[derive(Debug)]
+pub struct TransferTask {
+    pub kind: TransferKind,
+    pub table: String,
+    pub path: PathBuf,
+    pub uses_copy: bool,
+    pub progress: watch::Receiver<usize>, // Rows transferred so far
+    pub handle: JoinHandle<Result<usize>>,
+}
+
+impl TransferTask {
+    pub fn describe(&self) -> String {
+        let rows = *self.progress.borrow();
+        let method = if self.uses_copy { " with COPY" } else { "" };
+        match self.kind {
+            TransferKind::Export => format!("Exporting {}{} ({} rows)", self.table, method, rows),
+            TransferKind::Import => {
+                format!("Importing into {}{} ({} rows)", self.table, method, rows)
+            }
+        }
+    }
+}
+
+/// Whether transfers with this database use COPY.
+pub fn uses_copy(database_type: &DatabaseType) -> bool {
+    matches!(database_type, DatabaseType::PostgreSQL)
+}
+
+fn write_record(out: &mut impl Write, fields: &[Option<&str>]) -> Result<()> {
+    let fields: Vec<String> = fields.iter().map(|f| export::csv_cell(*f)).collect();
+    writeln!(out, "{}", fields.join(","))?;
+    Ok(())
+}
+
+/// Counts the records in CSV text fed to it in chunks: line ends outside
+/// quotes. Quote toggling also reads `""` inside a quoted field correctly.
+#[derive(Debug, Default)]
+struct RecordCounter {
+    in_quotes: bool,
+    records: usize,
+}
+
+impl RecordCounter {
+    fn feed(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            match byte {
+                b'"' => self.in_quotes = !self.in_quotes,
+                b'\n' if !self.in_quotes => self.records += 1,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Reads CSV records one at a time; a field is None when it was empty and
+/// unquoted.
+struct CsvReader<R> {
+    input: R,
+    line: String,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    fn new(input: R) -> Self {
+        Self {
+            input,
+            line: String::new(),
+        }
+    }
+
+    fn next_record(&mut self) -> Result<Option<Vec<Option<String>>>> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+        self.line.clear();
+        if self.input.read_line(&mut self.line)? == 0 {
+            return Ok(None);
+        }
+        loop {
+            let mut chars = self.line.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' if in_quotes && chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    '"' if in_quotes => in_quotes = false,
+                    '"' if field.is_empty() && !quoted => {
+                        quoted = true;
+                        in_quotes = true;
+                    }
+                    ',' if !in_quotes => {
+                        fields.push(finish_field(&mut field, quoted));
+                        quoted = false;
+                    }
+                    '\r' | '\n' if !in_quotes => {}
+                    c => field.push(c),
+                }
+            }
+            if !in_quotes {
+                break;
+            }
+            // A quoted field runs on to the next line
+            self.line.clear();
+            if self.input.read_line(&mut self.line)? == 0 {
+                return Err(anyhow!("The file ends inside a quoted field"));
+            }
+        }
+        fields.push(finish_field(&mut field, quoted));
+        Ok(Some(fields))
+    }
+}
+
+fn finish_field(field: &mut String, quoted: bool) -> Option<String> {
+    let value = std::mem::take(field);
+    if value.is_empty() && !quoted {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// Reads the header of a CSV file: the columns its rows fill.
+fn read_header(path: &Path) -> Result<Vec<String>> {
+    let mut reader = CsvReader::new(BufReader::new(File::open(path)?));
+    let header = reader
+        .next_record()?
+        .ok_or_else(|| anyhow!("The file is empty"))?;
+    header
+        .into_iter()
+        .map(|name| name.ok_or_else(|| anyhow!("The header has an empty column name")))
+        .collect()
+}
+
+/// Exports every row of `table` to `path`. Runs on its own connection pool;
+/// returns the rows written.
+pub async fn export_csv(
+    pool: DatabasePool,
+    table: TableInfo,
+    path: &Path,
+    progress: watch::Sender<usize>,
+) -> Result<usize> {
+    let database_type = pool.database_type();
+    let name = Dialect::of(&database_type).quote_table(&table.qualified_name());
+    let mut out = BufWriter::new(File::create(path)?);
+
+    if let DatabasePool::PostgreSQL(pg) = &pool {
+        // A query rather than the bare table, so views export too
+        let statement = format!(
+            "COPY (SELECT * FROM {}) TO STDOUT WITH (FORMAT csv, HEADER true)",
+            name
+        );
+        let mut stream = pg.copy_out_raw(&statement).await?;
+        let mut counter = RecordCounter::default();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            out.write_all(&chunk)?;
+            counter.feed(&chunk);
+            progress.send_replace(counter.records.saturating_sub(1));
+        }
+        out.flush()?;
+        return Ok(counter.records.saturating_sub(1));
+    }
+
+    let columns = pool
+        .get_table_columns(&table.name, table.schema.as_deref())
+        .await?;
+    let names: Vec<Option<&str>> = columns.iter().map(|c| Some(c.name.as_str())).collect();
+    write_record(&mut out, &names)?;
+    let select = format!(
+        "SELECT {} FROM {}",
+        dump::select_list(&database_type, &columns),
+        name
+    );
+    let (mut first, mut stream) = pool.stream_query(&select, FETCH_ROWS).await?;
+    let mut rows = first.take_cells();
+    let mut written = 0;
+    loop {
+        for row in &rows {
+            let fields: Vec<Option<&str>> = row.iter().map(Option::as_deref).collect();
+            write_record(&mut out, &fields)?;
+        }
+        written += rows.len();
+        progress.send_replace(written);
+        if stream.exhausted {
+            break;
+        }
+        rows = stream.next_chunk(FETCH_ROWS).await?;
+    }
+    out.flush()?;
+    Ok(written)
+}
+
+/// Imports the rows of the CSV file at `path` into `table`, matching the
+/// header's names to its columns. With COPY or MySQL's transaction the import
+/// is all or nothing; on SQLite batches already run stay when a later one
+/// fails. Returns the rows imported.
+pub async fn import_csv(
+    pool: DatabasePool,
+    table: TableInfo,
+    path: &Path,
+    progress: watch::Sender<usize>,
+) -> Result<usize> {
+    let database_type = pool.database_type();
+    let columns = read_header(path)?;
+    let qualified = table.qualified_name();
+
+    if let DatabasePool::PostgreSQL(pg) = &pool {
+        let column_list: Vec<String> = columns
+            .iter()
+            .map(|c| Dialect::PostgreSQL.quote_identifier(c))
+            .collect();
+        let statement = format!(
+            "COPY {} ({}) FROM STDIN WITH (FORMAT csv, HEADER true)",
+            Dialect::PostgreSQL.quote_table(&qualified),
+            column_list.join(", ")
+        );
+        let mut copy = pg.copy_in_raw(&statement).await?;
+        let mut file = File::open(path)?;
+        let mut buffer = vec![0; COPY_CHUNK_BYTES];
+        let mut counter = RecordCounter::default();
+        loop {
+            let read = match file.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) => {
+                    copy.abort(e.to_string()).await?;
+                    return Err(e.into());
+                }
+            };
+            copy.send(&buffer[..read]).await?;
+            counter.feed(&buffer[..read]);
+            progress.send_replace(counter.records.saturating_sub(1));
+        }
+        let rows = copy.finish().await?;
+        return Ok(rows as usize);
+    }
+
+    let mut reader = CsvReader::new(BufReader::new(File::open(path)?));
+    reader.next_record()?; // The header
+    // InnoDB flushes its log once at the commit instead of after every batch
+    let mut transaction = match &pool {
+        DatabasePool::MySQL(my) => Some(my.begin().await?),
+        _ => None,
+    };
+    let mut imported = 0;
+    let mut batch = Vec::with_capacity(INSERT_BATCH_ROWS);
+    loop {
+        let record = reader.next_record()?;
+        if let Some(record) = record.as_ref() {
+            if record.len() != columns.len() {
+                return Err(anyhow!(
+                    "Row {} has {} fields but the header names {} columns",
+                    imported + batch.len() + 1,
+                    record.len(),
+                    columns.len()
+                ));
+            }
+            batch.push(record.clone());
+        }
+        if !batch.is_empty() && (batch.len() == INSERT_BATCH_ROWS || record.is_none()) {
+            let insert = dump::insert_statement(&database_type, &qualified, &columns, &[], &batch);
+            match transaction.as_mut() {
+                Some(transaction) => {
+                    sqlx::Executor::execute(&mut **transaction, sqlx::raw_sql(&insert)).await?;
+                }
+                None => {
+                    pool.execute_query(&insert).await?;
+                }
+            }
+            imported += batch.len();
+            batch.clear();
+            progress.send_replace(imported);
+        }
+        if record.is_none() {
+            break;
+        }
+    }
+    if let Some(transaction) = transaction {
+        transaction.commit().await?;
+    }
+    Ok(imported)
+}
@@ -0,0 +1,148 @@
+// Undo/redo for the query editor. Instead of hooking every place that edits
+// the query, the text is compared with its last known state once per event and
+// the difference stored as an edit operation, so generated statements, loaded
+// files and vim commands can all be undone.
+
+// Operations kept for undo
+const HISTORY_LIMIT: usize = 500;
+
+/// One change to the text: `removed` at `position` was replaced by `inserted`.
+#[derive(Debug, Clone)]
+struct EditOp {
+    position: usize, // Byte offset
+    removed: String,
+    inserted: String,
+    cursor_before: usize,
+    cursor_after: usize,
+}
+
+impl EditOp {
+    fn is_insert(&self) -> bool {
+        self.removed.is_empty() && !self.inserted.is_empty()
+    }
+
+    fn is_delete(&self) -> bool {
+        self.inserted.is_empty() && !self.removed.is_empty()
+    }
+
+    /// Folds a following keystroke into this operation, so undo takes back a
+    /// typed word or a run of Backspaces at once rather than each character.
+    fn merge(&mut self, next: &EditOp) -> bool {
+        let is_word = |s: &str| s.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if self.is_insert()
+            && next.is_insert()
+            && next.position == self.position + self.inserted.len()
+            && is_word(&next.inserted)
+            && is_word(&self.inserted)
+        {
+            self.inserted.push_str(&next.inserted);
+        } else if self.is_delete()
+            && next.is_delete()
+            && next.position + next.removed.len() == self.position
+        {
+            self.position = next.position;
+            self.removed.insert_str(0, &next.removed);
+        } else {
+            return false;
+        }
+        self.cursor_after = next.cursor_after;
+        true
+    }
+}
+
+/// Edit history of one text buffer.
+#[derive(Debug, Default)]
+pub struct UndoHistory {
+    undo: Vec<EditOp>,
+    redo: Vec<EditOp>,
+    text: String,  // Text as last seen
+    cursor: usize, // Cursor as last seen
+    sealed: bool,  // Last operation was undone or redone and takes no more keystrokes
+}
+
+impl UndoHistory {
+    /// Records how the text changed since the last call, if it did.
+    pub fn observe(&mut self, text: &str, cursor: usize) {
+        if text == self.text {
+            self.cursor = cursor;
+            return;
+        }
+
+        // The changed range is what lies between the common prefix and suffix
+        let prefix = self
+            .text
+            .char_indices()
+            .zip(text.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(self.text.len().min(text.len()), |((i, _), _)| i);
+        let max_suffix = self.text.len().min(text.len()) - prefix;
+        let suffix = self.text[prefix..]
+            .chars()
+            .rev()
+            .zip(text[prefix..].chars().rev())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a.len_utf8())
+            .scan(0, |total, len| {
+                *total += len;
+                Some(*total)
+            })
+            .take_while(|total| *total <= max_suffix)
+            .last()
+            .unwrap_or(0);
+
+        let op = EditOp {
+            position: prefix,
+            removed: self.text[prefix..self.text.len() - suffix].to_string(),
+            inserted: text[prefix..text.len() - suffix].to_string(),
+            cursor_before: self.cursor,
+            cursor_after: cursor,
+        };
+        let merged = !self.sealed && self.undo.last_mut().is_some_and(|last| last.merge(&op));
+        if !merged {
+            self.undo.push(op);
+            if self.undo.len() > HISTORY_LIMIT {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.sealed = false;
+        self.text = text.to_string();
+        self.cursor = cursor;
+    }
+
+    /// Reverts the last edit. Returns false when there is nothing to undo.
+    pub fn undo(&mut self, text: &mut String, cursor: &mut usize) -> bool {
+        // Pick up an edit made since the last observation first
+        self.observe(text, *cursor);
+        let Some(op) = self.undo.pop() else {
+            return false;
+        };
+        text.replace_range(op.position..op.position + op.inserted.len(), &op.removed);
+        *cursor = op.cursor_before.min(text.len());
+        self.redo.push(op);
+        self.text = text.clone();
+        self.cursor = *cursor;
+        self.sealed = true;
+        true
+    }
+
+    /// Applies the last undone edit again. Returns false when there is nothing
+    /// to redo.
+    pub fn redo(&mut self, text: &mut String, cursor: &mut usize) -> bool {
+        if *text != self.text {
+            // Edited since the undo, so the redo history no longer applies
+            self.observe(text, *cursor);
+            return false;
+        }
+        let Some(op) = self.redo.pop() else {
+            return false;
+        };
+        text.replace_range(op.position..op.position + op.removed.len(), &op.inserted);
+        *cursor = op.cursor_after.min(text.len());
+        self.undo.push(op);
+        self.text = text.clone();
+        self.cursor = *cursor;
+        self.sealed = true;
+        true
+    }
+}
@@ -0,0 +1,54 @@
+/// Unit a numeric column's values are humanized as.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnUnit {
+    Bytes,
+    Seconds,
+    Milliseconds,
+}
+
+/// Formats a byte count using binary (IEC) units, e.g. `1048576` -> `1.0 MiB`.
+pub fn humanize_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+    if bytes.abs() < 1024.0 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes;
+    let mut unit = 0;
+    while value.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Formats a duration using its three largest units, e.g. `93784` seconds -> `1d 2h 3m`.
+pub fn humanize_duration(seconds: f64) -> String {
+    if seconds.abs() < 1.0 {
+        return format!("{}ms", (seconds * 1000.0).round());
+    }
+
+    let sign = if seconds < 0.0 { "-" } else { "" };
+    let mut remaining = seconds.abs().round() as u64;
+    let mut parts = Vec::new();
+    for (unit, size) in [("d", 86_400), ("h", 3_600), ("m", 60), ("s", 1)] {
+        let amount = remaining / size;
+        remaining %= size;
+        if amount > 0 {
+            parts.push(format!("{}{}", amount, unit));
+        }
+    }
+    parts.truncate(3);
+    format!("{}{}", sign, parts.join(" "))
+}
+
+/// Humanizes `value` in the given unit, returning `None` if it isn't numeric.
+pub fn humanize(value: &str, unit: ColumnUnit) -> Option<String> {
+    let number: f64 = value.trim().parse().ok()?;
+    Some(match unit {
+        ColumnUnit::Bytes => humanize_bytes(number),
+        ColumnUnit::Seconds => humanize_duration(number),
+        ColumnUnit::Milliseconds => humanize_duration(number / 1000.0),
+    })
+}
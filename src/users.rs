@@ -0,0 +1,196 @@
+// Creating users and changing passwords from the roles screen. A form collects
+// the name, password, whether the account can log in and its connection limit,
+// and the generated CREATE or ALTER statement is previewed, with the password
+// hidden, before it runs. The statement is logged and recorded with the
+// password hidden too.
+
+use crate::dialect::Dialect;
+use crate::textinput::TextInput;
+use anyhow::{Result, anyhow};
+use crossterm::event::{KeyCode, KeyEvent};
+
+// Shown in place of the password in previews, the query log and recordings
+const HIDDEN_PASSWORD: &str = "********";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UserField {
+    Name,
+    Host, // MySQL
+    Password,
+    Login,
+    ConnectionLimit,
+}
+
+#[derive(Debug, Clone)]
+pub struct UserForm {
+    pub dialect: Dialect,
+    pub existing: Option<String>, // Role whose password is changed; None creates one
+    pub name: TextInput,
+    pub host: TextInput,
+    pub password: TextInput,
+    pub login: bool,
+    pub connection_limit: TextInput, // Empty for no limit
+    pub selected: usize,             // Index into `fields()`
+    pub preview: bool,               // The statement waits for confirmation
+}
+
+impl UserForm {
+    pub fn create(dialect: Dialect) -> Self {
+        Self {
+            dialect,
+            existing: None,
+            name: TextInput::default(),
+            host: TextInput::new("%"),
+            password: TextInput::default(),
+            login: true,
+            connection_limit: TextInput::default(),
+            selected: 0,
+            preview: false,
+        }
+    }
+
+    /// A form changing the password of `role` (`user@host` on MySQL).
+    pub fn change_password(dialect: Dialect, role: &str) -> Self {
+        let (name, host) = match dialect {
+            Dialect::MySQL => role.rsplit_once('@').unwrap_or((role, "%")),
+            _ => (role, ""),
+        };
+        Self {
+            existing: Some(role.to_string()),
+            name: TextInput::new(name),
+            host: TextInput::new(host),
+            ..Self::create(dialect)
+        }
+    }
+
+    pub fn fields(&self) -> Vec<UserField> {
+        if self.existing.is_some() {
+            return vec![UserField::Password];
+        }
+        let mut fields = vec![UserField::Name];
+        if self.dialect == Dialect::MySQL {
+            fields.push(UserField::Host);
+        }
+        fields.extend([
+            UserField::Password,
+            UserField::Login,
+            UserField::ConnectionLimit,
+        ]);
+        fields
+    }
+
+    pub fn selected_field(&self) -> UserField {
+        self.fields()[self.selected.min(self.fields().len() - 1)]
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.fields().len() - 1;
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    fn selected_input(&mut self) -> Option<&mut TextInput> {
+        match self.selected_field() {
+            UserField::Name => Some(&mut self.name),
+            UserField::Host => Some(&mut self.host),
+            UserField::Password => Some(&mut self.password),
+            UserField::ConnectionLimit => Some(&mut self.connection_limit),
+            UserField::Login => None,
+        }
+    }
+
+    /// Edits the selected field with a key; the connection limit takes digits only.
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if self.selected_field() == UserField::ConnectionLimit
+            && matches!(key.code, KeyCode::Char(c) if !c.is_ascii_digit())
+        {
+            return;
+        }
+        if let Some(input) = self.selected_input() {
+            input.handle_key(key);
+        }
+    }
+
+    pub fn paste(&mut self, pasted: &str) {
+        let digits_only = self.selected_field() == UserField::ConnectionLimit;
+        let Some(input) = self.selected_input() else {
+            return;
+        };
+        if digits_only {
+            let digits: String = pasted.chars().filter(char::is_ascii_digit).collect();
+            input.insert_str(&digits);
+        } else {
+            input.paste(pasted, false);
+        }
+    }
+
+    pub fn toggle_login(&mut self) {
+        if self.selected_field() == UserField::Login {
+            self.login = !self.login;
+        }
+    }
+
+    /// The statement to run; with `hide_password` the password is replaced,
+    /// for showing and logging it.
+    pub fn statement(&self, hide_password: bool) -> Result<String> {
+        let name = self.name.text.trim();
+        if name.is_empty() {
+            return Err(anyhow!("The user needs a name"));
+        }
+        if self.existing.is_some() && self.password.is_empty() {
+            return Err(anyhow!("Type the new password"));
+        }
+        let password = if hide_password {
+            format!("'{}'", HIDDEN_PASSWORD)
+        } else {
+            self.dialect.string_literal(&self.password.text)
+        };
+        let limit = self.connection_limit.text.trim();
+
+        Ok(match self.dialect {
+            Dialect::MySQL => {
+                let account = format!(
+                    "{}@{}",
+                    self.dialect.string_literal(name),
+                    self.dialect.string_literal(self.host.text.trim())
+                );
+                if self.existing.is_some() {
+                    format!("ALTER USER {} IDENTIFIED BY {};", account, password)
+                } else {
+                    let mut statement = format!("CREATE USER {}", account);
+                    if !self.password.is_empty() {
+                        statement.push_str(&format!(" IDENTIFIED BY {}", password));
+                    }
+                    if !limit.is_empty() {
+                        statement.push_str(&format!(" WITH MAX_USER_CONNECTIONS {}", limit));
+                    }
+                    // MySQL accounts always log in unless locked
+                    if !self.login {
+                        statement.push_str(" ACCOUNT LOCK");
+                    }
+                    statement.push(';');
+                    statement
+                }
+            }
+            _ => {
+                let role = self.dialect.quote_identifier(name);
+                if self.existing.is_some() {
+                    format!("ALTER ROLE {} WITH PASSWORD {};", role, password)
+                } else {
+                    let mut statement = format!(
+                        "CREATE ROLE {} WITH {}",
+                        role,
+                        if self.login { "LOGIN" } else { "NOLOGIN" }
+                    );
+                    if !self.password.is_empty() {
+                        statement.push_str(&format!(" PASSWORD {}", password));
+                    }
+                    if !limit.is_empty() {
+                        statement.push_str(&format!(" CONNECTION LIMIT {}", limit));
+                    }
+                    statement.push(';');
+                    statement
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,220 @@
+// Text motions and edits for the query editor's vim mode. Positions are byte
+// offsets into the query, like its `TextInput` cursor; motions step over
+// whole graphemes and `j`/`k` keep the display column.
+
+use crate::text;
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EditorMode {
+    Normal,
+    Insert,
+}
+
+#[derive(PartialEq)]
+enum CharClass {
+    Space,
+    Word,
+    Punct,
+}
+
+fn class(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Space
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punct
+    }
+}
+
+fn char_at(text: &str, pos: usize) -> Option<char> {
+    text.get(pos..).and_then(|rest| rest.chars().next())
+}
+
+fn prev_char_start(text: &str, pos: usize) -> usize {
+    text::prev_grapheme(text, pos)
+}
+
+pub fn next_char_start(text: &str, pos: usize) -> usize {
+    text::next_grapheme(text, pos)
+}
+
+pub fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Position of the line's terminating newline (or the end of the text).
+pub fn line_end(text: &str, pos: usize) -> usize {
+    text[pos..].find('\n').map_or(text.len(), |i| pos + i)
+}
+
+/// First non-blank character of the line (`^`/`I`).
+pub fn first_non_blank(text: &str, pos: usize) -> usize {
+    let start = line_start(text, pos);
+    let end = line_end(text, pos);
+    text[start..end]
+        .char_indices()
+        .find(|(_, c)| !c.is_whitespace())
+        .map_or(end, |(i, _)| start + i)
+}
+
+/// Last character of the line, where normal mode keeps the cursor (`$`).
+pub fn last_char(text: &str, pos: usize) -> usize {
+    let start = line_start(text, pos);
+    let end = line_end(text, pos);
+    if end > start {
+        prev_char_start(text, end)
+    } else {
+        start
+    }
+}
+
+pub fn left(text: &str, pos: usize) -> usize {
+    if pos > line_start(text, pos) {
+        prev_char_start(text, pos)
+    } else {
+        pos
+    }
+}
+
+pub fn right(text: &str, pos: usize) -> usize {
+    let next = next_char_start(text, pos);
+    if next < line_end(text, pos) {
+        next
+    } else {
+        pos
+    }
+}
+
+/// Moves to the same column on the previous (`up`) or next line.
+pub fn vertical(text: &str, pos: usize, up: bool) -> usize {
+    let start = line_start(text, pos);
+    let column = text::width(&text[start..pos]);
+    let target_start = if up {
+        if start == 0 {
+            return pos;
+        }
+        line_start(text, start - 1)
+    } else {
+        let end = line_end(text, pos);
+        if end == text.len() {
+            return pos;
+        }
+        end + 1
+    };
+    let target_end = line_end(text, target_start);
+    let mut width = 0;
+    for (i, grapheme) in text[target_start..target_end].grapheme_indices(true) {
+        if width >= column {
+            return target_start + i;
+        }
+        width += text::width(grapheme);
+    }
+    last_char(text, target_start)
+}
+
+/// Start of the next word (`w`): skips the rest of the current word or run of
+/// punctuation, then any whitespace.
+pub fn next_word(text: &str, pos: usize) -> usize {
+    let mut pos = pos;
+    if let Some(c) = char_at(text, pos) {
+        let current = class(c);
+        if current != CharClass::Space {
+            while char_at(text, pos).is_some_and(|c| class(c) == current) {
+                pos = next_char_start(text, pos);
+            }
+        }
+    }
+    while char_at(text, pos).is_some_and(|c| class(c) == CharClass::Space) {
+        pos = next_char_start(text, pos);
+    }
+    pos.min(text.len())
+}
+
+/// Start of the current or previous word (`b`).
+pub fn previous_word(text: &str, pos: usize) -> usize {
+    let mut pos = pos;
+    while pos > 0 && char_at(text, prev_char_start(text, pos)).is_some_and(char::is_whitespace) {
+        pos = prev_char_start(text, pos);
+    }
+    if pos == 0 {
+        return 0;
+    }
+    let run = char_at(text, prev_char_start(text, pos)).map(class);
+    while pos > 0 && char_at(text, prev_char_start(text, pos)).map(class) == run {
+        pos = prev_char_start(text, pos);
+    }
+    pos
+}
+
+/// Removes the line under the cursor (`dd`), returning it with its newline and
+/// the new cursor position.
+pub fn delete_line(text: &mut String, pos: usize) -> (String, usize) {
+    let start = line_start(text, pos);
+    let end = line_end(text, pos);
+    let removed = if end < text.len() {
+        text.drain(start..=end).collect::<String>()
+    } else if start > 0 {
+        // Last line: take the newline before it instead
+        let line = text.drain(start - 1..end).collect::<String>();
+        format!("{}\n", &line[1..])
+    } else {
+        format!("{}\n", std::mem::take(text))
+    };
+    let pos = start.min(text.len());
+    (removed, first_non_blank(text, line_start(text, pos)))
+}
+
+/// The line under the cursor with a trailing newline (`yy`).
+pub fn yank_line(text: &str, pos: usize) -> String {
+    format!("{}\n", &text[line_start(text, pos)..line_end(text, pos)])
+}
+
+/// Pastes a yanked line below (`p`) or above (`P`) the current line,
+/// returning the cursor position on the pasted line.
+pub fn paste_line(text: &mut String, pos: usize, line: &str, below: bool) -> usize {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    if text.is_empty() {
+        text.push_str(line);
+        return first_non_blank(text, 0);
+    }
+    let at = if below {
+        let end = line_end(text, pos);
+        if end == text.len() {
+            text.push('\n');
+            text.push_str(line);
+            return first_non_blank(text, end + 1);
+        }
+        end + 1
+    } else {
+        line_start(text, pos)
+    };
+    text.insert_str(at, &format!("{}\n", line));
+    first_non_blank(text, at)
+}
+
+/// Deletes the character under the cursor (`x`), keeping the cursor on the line.
+pub fn delete_char(text: &mut String, pos: usize) -> usize {
+    if pos < line_end(text, pos) {
+        text.replace_range(pos..next_char_start(text, pos), "");
+    }
+    if pos >= line_end(text, pos) {
+        last_char(text, pos)
+    } else {
+        pos
+    }
+}
+
+/// Opens an empty line below or above the current one, returning its position.
+pub fn open_line(text: &mut String, pos: usize, below: bool) -> usize {
+    if below {
+        let end = line_end(text, pos);
+        text.insert(end, '\n');
+        end + 1
+    } else {
+        let start = line_start(text, pos);
+        text.insert(start, '\n');
+        start
+    }
+}